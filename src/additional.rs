@@ -1,9 +1,13 @@
-use crate::{Buffer, DnsMessage, DnsMessageError, MutBuffer};
-use crate::answer::DnsAClass;
+use crate::{Buffer, DnsError, DnsMessage, DnsMessageError, MutBuffer};
+use crate::answer::{CACHE_FLUSH_MASK, DnsAClass};
+use crate::edns::{Cookie, Edns, EdnsOption, PaddingPolicy};
+use crate::header::DnsHeaderResponseCode;
 use crate::name::DnsName;
 use crate::parse::{Parse, ParseBytes};
 use crate::question::DnsQType;
-use crate::rdata::{DnsAType, RData};
+use crate::rdata::{DnsAType, Opt, RawBytes, RData, Sig, TSig};
+use crate::sig0::{self, Sig0Signer, Sig0Status, Sig0Verifier};
+use crate::tsig::{self, TSigHmac, TSigKey, TSigMode, TSigStatus};
 use crate::write::WriteBytes;
 
 /// A DNS message additionals section.
@@ -13,6 +17,14 @@ pub struct DnsAdditionals<
 > {
     message: DnsMessage<PTR_STORAGE, 3, B>,
     remaining: usize,
+    // Position of the rdata length of the OPT record, if it was the last
+    // record written.
+    edns_position: Option<usize>,
+    // The padding policy of the message.
+    padding: Option<PaddingPolicy>,
+    // Position of the padding option, if it is the last option of the OPT
+    // record and the OPT record is the last record written.
+    padding_position: Option<usize>,
 }
 
 impl<
@@ -21,10 +33,13 @@ impl<
 > DnsAdditionals<PTR_STORAGE, B> {
     #[inline(always)]
     pub(crate) fn new(message: DnsMessage<PTR_STORAGE, 3, B>) -> Self {
-        let remaining = message.header().unwrap().answer_count() as usize;
+        let remaining = message.header().unwrap().additional_records_count() as usize;
         Self {
             message,
             remaining,
+            edns_position: None,
+            padding: None,
+            padding_position: None,
         }
     }
 
@@ -40,6 +55,140 @@ impl<
         })
     }
 
+    /// Return the EDNS(0) view of the OPT pseudo-record in the remaining
+    /// additionals, if there is one. This does not advance the section.
+    pub fn edns(&self) -> Result<Option<Edns<'_>>, DnsMessageError> {
+        let bytes = self.message.buffer.bytes();
+        let mut position = self.message.position;
+        for _ in 0..self.remaining {
+            let mut i = position;
+            DnsName::parse(bytes, &mut i)?;
+            if u16::parse(bytes, &mut i)? == DnsQType::OPT.id() {
+                return Ok(Some(Edns::parse(bytes, &mut position)?));
+            }
+
+            DnsAdditional::parse(bytes, &mut position)?;
+        }
+
+        Ok(None)
+    }
+
+    /// Return the key name and the TSIG record, if the last of the remaining
+    /// additionals is a TSIG record. This does not advance the section.
+    #[inline]
+    pub fn tsig(&self) -> Result<Option<(DnsName<'_>, TSig<'_>)>, DnsMessageError> {
+        Ok(self.find_tsig()?.map(|(_, name, tsig)| (name, tsig)))
+    }
+
+    /// Verify the TSIG record of the message with the given key. The HMAC is
+    /// keyed with the secret of the key and `now` is the current time in
+    /// seconds since the epoch.
+    ///
+    /// According to [RFC 8945 Section 5.2](https://tools.ietf.org/rfc/rfc8945#section-5.2).
+    pub fn verify_tsig<H: TSigHmac>(
+        &self,
+        key: TSigKey,
+        mut hmac: H,
+        mode: TSigMode,
+        now: u64,
+    ) -> Result<TSigStatus, DnsMessageError> {
+        let (position, name, tsig) = match self.find_tsig()? {
+            Some(tsig) => tsig,
+            None => return Ok(TSigStatus::Missing),
+        };
+        if name != key.name || tsig.algorithm != key.algorithm {
+            return Ok(TSigStatus::BadKey);
+        }
+
+        let additional_count = self.message.header()?.additional_records_count() - 1;
+        let bytes = &self.message.buffer.bytes()[..position];
+        tsig::digest(&mut hmac, bytes, additional_count, name, &tsig, mode)?;
+        let status = tsig::verify_mac(hmac.finalize().as_ref(), tsig.mac);
+        if status != TSigStatus::Valid {
+            return Ok(status);
+        }
+        if !tsig::verify_time(&tsig, now) {
+            return Ok(TSigStatus::BadTime);
+        }
+
+        Ok(TSigStatus::Valid)
+    }
+
+    /// Return the SIG(0) record, if the last of the remaining additionals is a
+    /// SIG(0) record. This does not advance the section.
+    #[inline]
+    pub fn sig0(&self) -> Result<Option<Sig<'_>>, DnsMessageError> {
+        Ok(self.find_sig0()?.map(|(_, sig)| sig))
+    }
+
+    /// Verify the SIG(0) record of the message. The verifier holds the public
+    /// key of the signer, `now` is the current time in seconds since the
+    /// epoch, truncated to 32 bits. For a response, the request must be
+    /// given.
+    ///
+    /// According to [RFC 2931 Section 3.2](https://tools.ietf.org/rfc/rfc2931#section-3.2).
+    pub fn verify_sig0<V: Sig0Verifier>(
+        &self,
+        mut verifier: V,
+        request: Option<&[u8]>,
+        now: u32,
+    ) -> Result<Sig0Status, DnsMessageError> {
+        let (position, sig) = match self.find_sig0()? {
+            Some(sig) => sig,
+            None => return Ok(Sig0Status::Missing),
+        };
+
+        let additional_count = self.message.header()?.additional_records_count() - 1;
+        let bytes = &self.message.buffer.bytes()[..position];
+        sig0::digest(|data| verifier.update(data), bytes, additional_count, &sig, request)?;
+        if !verifier.verify(sig.signature.bytes()) {
+            return Ok(Sig0Status::BadSig);
+        }
+        if !sig0::verify_time(&sig, now) {
+            return Ok(Sig0Status::BadTime);
+        }
+
+        Ok(Sig0Status::Valid)
+    }
+
+    // Find the TSIG record, which must be the last additional, and return its
+    // position.
+    fn find_tsig(&self) -> Result<Option<(usize, DnsName<'_>, TSig<'_>)>, DnsMessageError> {
+        match self.find_last(DnsQType::TSIG)? {
+            Some((position, DnsAdditional { name, rdata: DnsAType::TSIG(tsig), .. })) => {
+                Ok(Some((position, name, tsig)))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    // Find the SIG(0) record, which must be the last additional, and return
+    // its position.
+    fn find_sig0(&self) -> Result<Option<(usize, Sig<'_>)>, DnsMessageError> {
+        match self.find_last(DnsQType::SIG)? {
+            Some((position, DnsAdditional { rdata: DnsAType::SIG(sig), .. })) if sig.type_covered == 0 => {
+                Ok(Some((position, sig)))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    // Return the last of the remaining additionals and its position, if it
+    // is of the given type.
+    fn find_last(&self, qtype: DnsQType) -> Result<Option<(usize, DnsAdditional<'_, DnsAType<'_>>)>, DnsMessageError> {
+        let bytes = self.message.buffer.bytes();
+        let mut position = self.message.position;
+        for remaining in (0..self.remaining).rev() {
+            let start = position;
+            let additional = DnsAdditional::parse(bytes, &mut position)?;
+            if remaining == 0 && additional.rdata.id() == qtype.id() {
+                return Ok(Some((start, additional.into_parsed()?)));
+            }
+        }
+
+        Ok(None)
+    }
+
     /// Complete writing to the additionals section and return the message.
     #[inline(always)]
     pub fn complete(mut self) -> Result<DnsMessage<PTR_STORAGE, 3, B>, DnsMessageError> {
@@ -66,12 +215,283 @@ impl<
         // Truncate the buffer to the current position.
         self.message.truncate()?;
         answer.write(&mut self.message)?;
-        // Set answer_count in the header to the current answer count + 1.
-        let answer_count = self.message.header().unwrap().answer_count();
-        let answer_count = answer_count + 1 - self.remaining as u16;
-        self.message.header_mut()?.set_answer_count(answer_count);
-        self.message.header_mut()?.set_name_server_count(0);
-        self.message.header_mut()?.set_additional_records_count(0);
+        self.edns_position = None;
+        self.padding_position = None;
+        self.increment_count()
+    }
+
+    /// Append an EDNS(0) OPT pseudo-record to the message. This will overwrite
+    /// the next additional, if any. A message must not contain more than one
+    /// OPT record. Further options can be added with
+    /// [`append_edns_option`](Self::append_edns_option). If a padding policy
+    /// is set, the padding option is appended.
+    pub fn append_edns(&mut self, edns: Edns) -> Result<(), DnsMessageError> {
+        // Truncate the buffer to the current position.
+        self.message.truncate()?;
+        // The rdata length follows the root name, type, class and ttl.
+        let edns_position = self.message.position + 9;
+        edns.write(&mut self.message)?;
+        self.edns_position = Some(edns_position);
+        self.padding_position = None;
+        self.increment_count()?;
+        self.pad(0)
+    }
+
+    /// Append an EDNS(0) OPT pseudo-record to the message, passing each of
+    /// its options through the given filter. Options for which the filter
+    /// returns `None` are dropped. This is used when relaying a message, e.g.
+    /// to strip or clamp the client subnet option.
+    pub fn append_edns_filtered<'a, F>(&mut self, edns: Edns<'a>, mut filter: F) -> Result<(), DnsMessageError>
+    where
+        F: FnMut(EdnsOption<'a>) -> Option<EdnsOption<'a>>,
+    {
+        // The options are parsed before anything is written, so that an
+        // invalid option does not leave a partial OPT record.
+        for option in edns.iter() {
+            option?;
+        }
+
+        let options = edns.iter();
+        self.append_edns(Edns {
+            options: Opt::empty(),
+            ..edns
+        })?;
+
+        for option in options {
+            if let Some(option) = filter(option?) {
+                self.append_edns_option(option)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Append an EDNS(0) OPT pseudo-record with the BADCOOKIE response code
+    /// and the given cookie, which should contain a fresh server cookie. The
+    /// options of the given record are not written.
+    ///
+    /// According to [RFC 7873 Section 5.2.3](https://tools.ietf.org/rfc/rfc7873#section-5.2.3).
+    pub fn append_bad_cookie(&mut self, mut edns: Edns, cookie: Cookie) -> Result<(), DnsMessageError> {
+        edns.set_response_code(self.message.header_mut()?, DnsHeaderResponseCode::BadCookie);
+        edns.options = Opt::empty();
+        self.append_edns(edns)?;
+        self.append_edns_option(EdnsOption::Cookie(cookie))
+    }
+
+    /// Append an option to the OPT pseudo-record. The OPT record must be the
+    /// last record appended to the message. The padding option, if any, is
+    /// kept last and resized.
+    pub fn append_edns_option(&mut self, option: EdnsOption) -> Result<(), DnsMessageError> {
+        self.remove_padding()?;
+        let written = self.write_edns_option(option);
+        self.pad(0)?;
+
+        written
+    }
+
+    // Append an option to the OPT pseudo-record, after the padding option, if
+    // any.
+    fn write_edns_option(&mut self, option: EdnsOption) -> Result<(), DnsMessageError> {
+        let edns_position = self.edns_position
+            .ok_or(DnsMessageError::DnsError(DnsError::MissingEdnsRecord))?;
+        let rdata_len = self.message.buffer.read_bytes_at(edns_position, 2)?;
+        let rdata_len = u16::from_be_bytes([rdata_len[0], rdata_len[1]]) as usize;
+        let position = self.message.position;
+        let written = option.write(&mut self.message).and_then(|option_len| {
+            let rdata_len = rdata_len + option_len;
+            if rdata_len > u16::MAX as usize {
+                return Err(DnsMessageError::DnsError(DnsError::RDataLongerThanMessage));
+            }
+
+            Ok(rdata_len)
+        });
+        // The option is removed again on error, so that the message matches
+        // its header.
+        let rdata_len = match written {
+            Ok(rdata_len) => rdata_len,
+            Err(err) => {
+                self.truncate_to(position)?;
+                return Err(err);
+            }
+        };
+
+        self.message.buffer.write_array_at(edns_position, (rdata_len as u16).to_be_bytes())?;
+
+        Ok(())
+    }
+
+    /// Sign the message and append the TSIG record. The MAC of the given
+    /// record is replaced, the HMAC is keyed with the secret of the key. The
+    /// TSIG record must be the last record of the message, so this should be
+    /// called last. Return the MAC, which is needed to verify the response or
+    /// sign the next message of a multi-message response.
+    ///
+    /// According to [RFC 8945 Section 5.3](https://tools.ietf.org/rfc/rfc8945#section-5.3).
+    pub fn append_tsig<H: TSigHmac>(
+        &mut self,
+        key_name: DnsName,
+        tsig: TSig,
+        mut hmac: H,
+        mode: TSigMode,
+    ) -> Result<H::Mac, DnsMessageError> {
+        // Truncate the buffer to the current position.
+        self.message.truncate()?;
+        if self.padding_position.is_some() {
+            // The padding covers the TSIG record, which only lacks the MAC.
+            let position = self.message.position;
+            let length = self.write_tsig(key_name, &TSig { mac: &[], ..tsig });
+            self.truncate_to(position)?;
+            self.pad(length? + hmac.mac_length())?;
+        }
+
+        let additional_count = self.message.header()?.additional_records_count() - self.remaining as u16;
+        let bytes = &self.message.buffer.bytes()[..self.message.position];
+        tsig::digest(&mut hmac, bytes, additional_count, key_name, &tsig, mode)?;
+        let mac = hmac.finalize();
+        self.write_tsig(key_name, &TSig {
+            mac: mac.as_ref(),
+            ..tsig
+        })?;
+        self.edns_position = None;
+        self.padding_position = None;
+        self.increment_count()?;
+
+        Ok(mac)
+    }
+
+    // Write the TSIG record and return its length.
+    fn write_tsig(&mut self, key_name: DnsName, tsig: &TSig) -> Result<usize, DnsMessageError> {
+        let mut bytes = 0;
+        // The key name must not be compressed.
+        bytes += self.message.write_name_uncompressed(key_name)?;
+        bytes += DnsQType::TSIG.id().write(&mut self.message)?;
+        bytes += tsig::TSIG_CLASS.write(&mut self.message)?;
+        bytes += tsig::TSIG_TTL.write(&mut self.message)?;
+        let rdata_len_placeholder = self.message.write_placeholder::<2>()?;
+        let rdata_len = tsig.write(&mut self.message)?;
+        bytes += rdata_len;
+        bytes += rdata_len_placeholder(&mut self.message, (rdata_len as u16).to_be_bytes());
+
+        Ok(bytes)
+    }
+
+    /// Sign the message and append the SIG(0) record. The signature of the
+    /// given record is replaced and the covered type, labels and original TTL
+    /// are set to 0. The SIG(0) record must be the last record of the
+    /// message, so this should be called last. For a response, the request
+    /// must be given.
+    ///
+    /// According to [RFC 2931 Section 3.1](https://tools.ietf.org/rfc/rfc2931#section-3.1).
+    pub fn append_sig0<S: Sig0Signer>(
+        &mut self,
+        sig: Sig,
+        mut signer: S,
+        request: Option<&[u8]>,
+    ) -> Result<(), DnsMessageError> {
+        // Truncate the buffer to the current position.
+        self.message.truncate()?;
+        let sig = Sig {
+            type_covered: 0,
+            labels: 0,
+            original_ttl: 0,
+            ..sig
+        };
+        if self.padding_position.is_some() {
+            // The padding covers the SIG(0) record, which only lacks the
+            // signature.
+            let position = self.message.position;
+            let length = self.write_sig0(&Sig { signature: RawBytes::new(&[]), ..sig });
+            self.truncate_to(position)?;
+            self.pad(length? + signer.signature_length())?;
+        }
+
+        let additional_count = self.message.header()?.additional_records_count() - self.remaining as u16;
+        let bytes = &self.message.buffer.bytes()[..self.message.position];
+        sig0::digest(|data| signer.update(data), bytes, additional_count, &sig, request)?;
+        let signature = signer.finalize();
+        self.write_sig0(&Sig {
+            signature: RawBytes::new(signature.as_ref()),
+            ..sig
+        })?;
+        self.edns_position = None;
+        self.padding_position = None;
+
+        self.increment_count()
+    }
+
+    // Write the SIG(0) record and return its length.
+    fn write_sig0(&mut self, sig: &Sig) -> Result<usize, DnsMessageError> {
+        let mut bytes = 0;
+        // The owner name is the root.
+        bytes += self.message.write_bytes(&[0])?;
+        bytes += DnsQType::SIG.id().write(&mut self.message)?;
+        bytes += sig0::SIG0_CLASS.write(&mut self.message)?;
+        bytes += sig0::SIG0_TTL.write(&mut self.message)?;
+        let rdata_len_placeholder = self.message.write_placeholder::<2>()?;
+        let rdata_len = sig.write(&mut self.message)?;
+        bytes += rdata_len;
+        bytes += rdata_len_placeholder(&mut self.message, (rdata_len as u16).to_be_bytes());
+
+        Ok(bytes)
+    }
+
+    /// Set the padding policy of the message. The padding option is kept as
+    /// the last option of the OPT record and sized for the complete message,
+    /// including a TSIG or SIG(0) record appended after the OPT record. Other
+    /// records must not follow the OPT record. A message without an OPT
+    /// record is not padded.
+    ///
+    /// According to [RFC 7830 Section 4](https://tools.ietf.org/rfc/rfc7830#section-4).
+    #[inline]
+    pub fn set_padding(&mut self, policy: PaddingPolicy) -> Result<(), DnsMessageError> {
+        self.padding = Some(policy);
+        self.pad(0)
+    }
+
+    // Write the padding option at the end of the OPT record, if it is the
+    // last record and a padding policy is set, sized for a message followed
+    // by `reserved` more bytes.
+    fn pad(&mut self, reserved: usize) -> Result<(), DnsMessageError> {
+        let policy = match (self.padding, self.edns_position) {
+            (Some(policy), Some(_)) => policy,
+            _ => return Ok(()),
+        };
+
+        self.remove_padding()?;
+        let padding_position = self.message.position;
+        let padding = policy.padding_length(padding_position + reserved);
+        self.write_edns_option(EdnsOption::Padding(padding))?;
+        self.padding_position = Some(padding_position);
+
+        Ok(())
+    }
+
+    // Remove the padding option from the end of the OPT record, if any.
+    fn remove_padding(&mut self) -> Result<(), DnsMessageError> {
+        let (edns_position, padding_position) = match (self.edns_position, self.padding_position.take()) {
+            (Some(edns_position), Some(padding_position)) => (edns_position, padding_position),
+            _ => return Ok(()),
+        };
+
+        let rdata_len = self.message.buffer.read_bytes_at(edns_position, 2)?;
+        let rdata_len = u16::from_be_bytes([rdata_len[0], rdata_len[1]]) as usize;
+        let rdata_len = rdata_len - (self.message.position - padding_position);
+        self.message.buffer.write_array_at(edns_position, (rdata_len as u16).to_be_bytes())?;
+        self.truncate_to(padding_position)
+    }
+
+    #[inline(always)]
+    fn truncate_to(&mut self, position: usize) -> Result<(), DnsMessageError> {
+        self.message.position = position;
+        self.message.truncate()
+    }
+
+    #[inline(always)]
+    fn increment_count(&mut self) -> Result<(), DnsMessageError> {
+        // Set additional_records_count in the header to the current additional count + 1.
+        let additional_count = self.message.header().unwrap().additional_records_count();
+        let additional_count = additional_count + 1 - self.remaining as u16;
+        self.message.header_mut()?.set_additional_records_count(additional_count);
         self.remaining = 0;
 
         Ok(())
@@ -110,7 +530,8 @@ pub struct DnsAdditional<'a, D> {
     pub name: DnsName<'a>,
     /// The data of the additional.
     pub rdata: D,
-    /// Whether the additional should be cached.
+    /// Whether the additional replaces the cached records of its name and
+    /// type (mDNS only).
     pub cache_flush: bool,
     /// The class of the additional.
     pub aclass: DnsAClass,
@@ -135,10 +556,10 @@ impl<'a> DnsAdditional<'a, RData<'a>> {
 impl<'a> ParseBytes<'a> for DnsAdditional<'a, RData<'a>> {
     fn parse_bytes(bytes: &'a [u8], i: &mut usize) -> Result<Self, DnsMessageError> {
         let name = DnsName::parse(bytes, i)?;
-        let atype_id = u16::parse(bytes, i)?;
-        let atype = DnsQType::from_id(atype_id);
-        let cache_flush = atype_id & 0b1000_0000_0000_0000 != 0;
-        let aclass = DnsAClass::from_id(u16::parse(bytes, i)?);
+        let atype = DnsQType::from_id(u16::parse(bytes, i)?);
+        let aclass = u16::parse(bytes, i)?;
+        let cache_flush = aclass & CACHE_FLUSH_MASK != 0;
+        let aclass = DnsAClass::from_id(aclass & !CACHE_FLUSH_MASK);
         let ttl = u32::parse(bytes, i)?;
         let rdata = RData::parse(bytes, i, atype)?;
 
@@ -165,7 +586,7 @@ impl<'a> WriteBytes for DnsAdditional<'a, DnsAType<'a>> {
         bytes += self.rdata.id().write(message)?;
         let mut aclass = self.aclass.id();
         if self.cache_flush {
-            aclass |= 0b1000_0000;
+            aclass |= CACHE_FLUSH_MASK;
         }
         bytes += aclass.write(message)?;
         // Write the ttl to the buffer.
@@ -5,6 +5,10 @@ use crate::question::DnsQType;
 use crate::rdata::{DnsAType, RData};
 use crate::write::WriteBytes;
 
+// The top bit of the class is the cache-flush bit in mDNS.
+// According to RFC 6762 Section 10.2.
+pub(crate) const CACHE_FLUSH_MASK: u16 = 0b1000_0000_0000_0000;
+
 /// A DNS message answers section.
 pub struct DnsAnswers<
     const PTR_STORAGE: usize,
@@ -110,7 +114,8 @@ pub struct DnsAnswer<'a, D> {
     pub name: DnsName<'a>,
     /// The answer data.
     pub rdata: D,
-    /// Whether the answer should be cached.
+    /// Whether the answer replaces the cached records of its name and type
+    /// (mDNS only).
     pub cache_flush: bool,
     /// The class of the answer.
     pub aclass: DnsAClass,
@@ -135,10 +140,10 @@ impl<'a> DnsAnswer<'a, RData<'a>> {
 impl<'a> ParseBytes<'a> for DnsAnswer<'a, RData<'a>> {
     fn parse_bytes(bytes: &'a [u8], i: &mut usize) -> Result<Self, DnsMessageError> {
         let name = DnsName::parse(bytes, i)?;
-        let atype_id = u16::parse(bytes, i)?;
-        let atype = DnsQType::from_id(atype_id);
-        let cache_flush = atype_id & 0b1000_0000_0000_0000 != 0;
-        let aclass = DnsAClass::from_id(u16::parse(bytes, i)?);
+        let atype = DnsQType::from_id(u16::parse(bytes, i)?);
+        let aclass = u16::parse(bytes, i)?;
+        let cache_flush = aclass & CACHE_FLUSH_MASK != 0;
+        let aclass = DnsAClass::from_id(aclass & !CACHE_FLUSH_MASK);
         let ttl = u32::parse(bytes, i)?;
         let rdata = RData::parse(bytes, i, atype)?;
 
@@ -165,7 +170,7 @@ impl<'a> WriteBytes for DnsAnswer<'a, DnsAType<'a>> {
         bytes += self.rdata.id().write(message)?;
         let mut aclass = self.aclass.id();
         if self.cache_flush {
-            aclass |= 0b1000_0000;
+            aclass |= CACHE_FLUSH_MASK;
         }
         bytes += aclass.write(message)?;
         // Write the ttl to the buffer.
@@ -182,13 +187,13 @@ impl<'a> WriteBytes for DnsAnswer<'a, DnsAType<'a>> {
 
 /// A DNS message answer class.
 #[derive(Copy, Clone, Debug, PartialEq)]
-#[repr(u16)]
 pub enum DnsAClass {
-    IN = 1,
-    CS = 2,
-    CH = 3,
-    HS = 4,
-    Reserved,
+    IN,
+    CS,
+    CH,
+    HS,
+    /// A class without a name in this crate, with its ID.
+    Unknown(u16),
 }
 
 impl DnsAClass {
@@ -196,8 +201,11 @@ impl DnsAClass {
     #[inline(always)]
     pub fn from_id(id: u16) -> Self {
         match id {
-            1..=4 => unsafe { core::mem::transmute(id) },
-            _ => DnsAClass::Reserved,
+            1 => DnsAClass::IN,
+            2 => DnsAClass::CS,
+            3 => DnsAClass::CH,
+            4 => DnsAClass::HS,
+            id => DnsAClass::Unknown(id),
         }
     }
 
@@ -205,8 +213,11 @@ impl DnsAClass {
     #[inline(always)]
     pub fn id(&self) -> u16 {
         match self {
-            DnsAClass::Reserved => panic!("Reserved QClass"),
-            _ => *self as u16,
+            DnsAClass::IN => 1,
+            DnsAClass::CS => 2,
+            DnsAClass::CH => 3,
+            DnsAClass::HS => 4,
+            DnsAClass::Unknown(id) => *id,
         }
     }
 }
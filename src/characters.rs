@@ -87,6 +87,12 @@ impl<'a> Characters<'a> {
     pub const unsafe fn new_unchecked(bytes: &'a [u8]) -> Self {
         Characters { bytes }
     }
+
+    // The characters as parsed from a message, without the length.
+    #[inline(always)]
+    pub(crate) fn bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
 }
 
 impl<'a> ParseBytes<'a> for Characters<'a> {
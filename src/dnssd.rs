@@ -0,0 +1,358 @@
+use crate::{Buffer, DNS_HEADER_SIZE, DnsMessage, DnsMessageError, MutBuffer};
+use crate::answer::DnsAnswer;
+use crate::name::DnsName;
+use crate::parse::Parse;
+use crate::question::{DnsQClass, DnsQType, DnsQuestion};
+use crate::rdata::{DnsAType, Txt};
+
+/// Write a query for the instances of a service, e.g. `_http._tcp.local`.
+/// The message is returned after the question, so that known answers can be
+/// appended.
+///
+/// According to [RFC 6763 Section 4.1](https://tools.ietf.org/rfc/rfc6763#section-4.1).
+pub fn browse<const PTR_STORAGE: usize, B: MutBuffer + Buffer>(
+    service: DnsName,
+    buffer: B,
+) -> Result<DnsMessage<PTR_STORAGE, 1, B>, DnsMessageError> {
+    let message: DnsMessage<PTR_STORAGE, 0, _> = DnsMessage::new_mut(buffer)?;
+    let mut questions = message.questions();
+    questions.append(DnsQuestion {
+        name: service,
+        qtype: DnsQType::PTR,
+        unicast_response: false,
+        qclass: DnsQClass::IN,
+    })?;
+
+    questions.complete()
+}
+
+/// An iterator over the instances of a service, from the PTR records in any
+/// section of a message.
+///
+/// According to [RFC 6763 Section 4.1](https://tools.ietf.org/rfc/rfc6763#section-4.1).
+pub struct DnsSdInstances<'a, 's> {
+    records: Records<'a>,
+    service: &'s DnsName<'s>,
+}
+
+impl<'a, 's> DnsSdInstances<'a, 's> {
+    /// Create an iterator over the instances of the service in the message.
+    #[inline]
+    pub fn new(packet: &'a [u8], service: &'s DnsName<'s>) -> Result<Self, DnsMessageError> {
+        Ok(Self {
+            records: Records::new(packet)?,
+            service,
+        })
+    }
+}
+
+impl<'a> Iterator for DnsSdInstances<'a, '_> {
+    type Item = Result<DnsName<'a>, DnsMessageError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for record in self.records.by_ref() {
+            match record {
+                Ok(DnsAnswer { name, rdata: DnsAType::Ptr(ptr), .. }) if name == *self.service => {
+                    return Some(Ok(ptr.name));
+                }
+                Ok(_) => {}
+                Err(e) => return Some(Err(e)),
+            }
+        }
+
+        None
+    }
+}
+
+/// A service instance, resolved from the SRV and TXT records in any section
+/// of a message.
+///
+/// According to [RFC 6763 Section 6](https://tools.ietf.org/rfc/rfc6763#section-6).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DnsSdInstance<'a> {
+    /// The name of the instance, e.g. `Printer._http._tcp.local`.
+    pub name: DnsName<'a>,
+    /// The host providing the service.
+    pub host: DnsName<'a>,
+    /// The port of the service on the host.
+    pub port: u16,
+    /// The priority of the host.
+    pub priority: u16,
+    /// The weight of the host.
+    pub weight: u16,
+    /// The txt record of the instance, use [`Txt::attributes`] for its
+    /// key/value pairs.
+    pub txt: Option<Txt<'a>>,
+    packet: &'a [u8],
+}
+
+impl<'a> DnsSdInstance<'a> {
+    /// Resolve the instance with the given name from the message. If the
+    /// message contains multiple SRV records for the instance, the one with
+    /// the lowest priority and then the highest weight is used. Return
+    /// `None` if the message contains no SRV record for the instance.
+    pub fn resolve(packet: &'a [u8], name: &DnsName) -> Result<Option<Self>, DnsMessageError> {
+        let mut instance: Option<Self> = None;
+        let mut txt = None;
+        for record in Records::new(packet)? {
+            let record = record?;
+            if record.name != *name {
+                continue;
+            }
+
+            match record.rdata {
+                DnsAType::Srv(srv) => {
+                    let preferred = match instance {
+                        Some(instance) => srv.priority < instance.priority
+                            || (srv.priority == instance.priority && srv.weight > instance.weight),
+                        None => true,
+                    };
+                    if preferred {
+                        instance = Some(Self {
+                            name: record.name,
+                            host: srv.target,
+                            port: srv.port,
+                            priority: srv.priority,
+                            weight: srv.weight,
+                            txt: None,
+                            packet,
+                        });
+                    }
+                }
+                DnsAType::Txt(record) if txt.is_none() => txt = Some(record),
+                _ => {}
+            }
+        }
+
+        Ok(instance.map(|instance| Self { txt, ..instance }))
+    }
+
+    /// Return an iterator over the addresses of the host, from the A and AAAA
+    /// records in any section of the message.
+    ///
+    /// According to [RFC 6763 Section 12.2](https://tools.ietf.org/rfc/rfc6763#section-12.2).
+    #[inline]
+    pub fn addresses(&self) -> Result<DnsSdAddresses<'a>, DnsMessageError> {
+        Ok(DnsSdAddresses {
+            records: Records::new(self.packet)?,
+            host: self.host,
+        })
+    }
+}
+
+/// An address of the host of a service instance.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum DnsSdAddress {
+    /// An IPv4 address, from an A record.
+    V4([u8; 4]),
+    /// An IPv6 address, from an AAAA record.
+    V6([u8; 16]),
+}
+
+/// An iterator over the addresses of the host of a service instance.
+pub struct DnsSdAddresses<'a> {
+    records: Records<'a>,
+    host: DnsName<'a>,
+}
+
+impl Iterator for DnsSdAddresses<'_> {
+    type Item = Result<DnsSdAddress, DnsMessageError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for record in self.records.by_ref() {
+            match record {
+                Ok(record) if record.name == self.host => match record.rdata {
+                    DnsAType::A(a) => return Some(Ok(DnsSdAddress::V4(a.address))),
+                    DnsAType::AAAA(aaaa) => return Some(Ok(DnsSdAddress::V6(aaaa.address))),
+                    _ => {}
+                },
+                Ok(_) => {}
+                Err(e) => return Some(Err(e)),
+            }
+        }
+
+        None
+    }
+}
+
+// An iterator over the records of the answers, authority and additional
+// sections, which borrow the message rather than a section, so that they can
+// be returned.
+struct Records<'a> {
+    packet: &'a [u8],
+    position: usize,
+    remaining: usize,
+}
+
+impl<'a> Records<'a> {
+    fn new(packet: &'a [u8]) -> Result<Self, DnsMessageError> {
+        let message: DnsMessage<0, 0, _> = DnsMessage::new(packet)?;
+        let header = message.header()?;
+        let questions = header.question_count();
+        let remaining = header.answer_count() as usize
+            + header.name_server_count() as usize
+            + header.additional_records_count() as usize;
+
+        let mut position = DNS_HEADER_SIZE;
+        for _ in 0..questions {
+            DnsQuestion::parse(packet, &mut position)?;
+        }
+
+        Ok(Self {
+            packet,
+            position,
+            remaining,
+        })
+    }
+}
+
+impl<'a> Iterator for Records<'a> {
+    type Item = Result<DnsAnswer<'a, DnsAType<'a>>, DnsMessageError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        let record = DnsAnswer::parse(self.packet, &mut self.position)
+            .and_then(|record| record.into_parsed());
+        if record.is_err() {
+            self.remaining = 0;
+        }
+
+        Some(record)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use arrayvec::ArrayVec;
+    use crate::additional::DnsAdditional;
+    use crate::answer::DnsAClass;
+    use crate::rdata::{A, Aaaa, Ptr, Srv, TxtAttribute};
+    use super::*;
+
+    fn service() -> DnsName<'static> {
+        DnsName::new(b"\x05_http\x04_tcp\x05local\x00").unwrap()
+    }
+
+    fn instance() -> DnsName<'static> {
+        DnsName::new(b"\x07Printer\x05_http\x04_tcp\x05local\x00").unwrap()
+    }
+
+    fn host() -> DnsName<'static> {
+        DnsName::new(b"\x07printer\x05local\x00").unwrap()
+    }
+
+    fn additional(name: DnsName<'static>, rdata: DnsAType<'static>) -> DnsAdditional<'static, DnsAType<'static>> {
+        DnsAdditional {
+            name,
+            rdata,
+            cache_flush: true,
+            aclass: DnsAClass::IN,
+            ttl: 120,
+        }
+    }
+
+    fn response() -> ArrayVec<u8, 512> {
+        let message: DnsMessage<16, 1, _> = DnsMessage::new_mut(ArrayVec::<u8, 512>::new()).unwrap();
+        let mut answers = message.answers();
+        answers.append(DnsAnswer {
+            name: service(),
+            rdata: DnsAType::Ptr(Ptr { name: instance() }),
+            cache_flush: false,
+            aclass: DnsAClass::IN,
+            ttl: 4500,
+        }).unwrap();
+        let message = answers.complete().unwrap();
+        let message = message.name_servers().complete().unwrap();
+        let mut additionals = message.additionals();
+        for srv in [(10, 0, 8080), (0, 5, 631), (0, 10, 80)] {
+            additionals.append(additional(instance(), DnsAType::Srv(Srv {
+                priority: srv.0,
+                weight: srv.1,
+                port: srv.2,
+                target: host(),
+            }))).unwrap();
+        }
+        additionals.append(additional(
+            instance(),
+            DnsAType::Txt(Txt::new(b"\x09txtvers=1\x05Color").unwrap()),
+        )).unwrap();
+        additionals.append(additional(host(), DnsAType::A(A { address: [192, 168, 1, 20] }))).unwrap();
+        additionals.append(additional(
+            DnsName::new(b"\x05other\x05local\x00").unwrap(),
+            DnsAType::A(A { address: [192, 168, 1, 21] }),
+        )).unwrap();
+        additionals.append(additional(host(), DnsAType::AAAA(Aaaa { address: [0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1] }))).unwrap();
+
+        additionals.complete().unwrap().abort().unwrap()
+    }
+
+    #[test]
+    fn browse() {
+        let message = super::browse::<16, _>(service(), ArrayVec::<u8, 512>::new()).unwrap();
+        let bytes = message.abort().unwrap();
+        assert_eq!(
+            bytes.as_slice(),
+            [
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // Header
+                0x05, b'_', b'h', b't', b't', b'p', 0x04, b'_', b't', b'c', b'p', // Name
+                0x05, b'l', b'o', b'c', b'a', b'l', 0x00, // Name
+                0x00, 0x0c, // Type
+                0x00, 0x01, // Class
+            ].as_slice(),
+        );
+    }
+
+    #[test]
+    fn instances() {
+        let bytes = response();
+        let service = service();
+        let mut instances = DnsSdInstances::new(&bytes, &service).unwrap();
+        assert_eq!(instances.next(), Some(Ok(instance())));
+        assert_eq!(instances.next(), None);
+
+        let other = DnsName::new(b"\x04_ipp\x04_tcp\x05local\x00").unwrap();
+        assert_eq!(DnsSdInstances::new(&bytes, &other).unwrap().next(), None);
+    }
+
+    #[test]
+    fn resolve() {
+        let bytes = response();
+        let instance = DnsSdInstance::resolve(&bytes, &instance()).unwrap().unwrap();
+        assert_eq!(instance.name, self::instance());
+        assert_eq!(instance.host, host());
+        assert_eq!(instance.port, 80);
+        assert_eq!(instance.priority, 0);
+        assert_eq!(instance.weight, 10);
+
+        let txt = instance.txt.unwrap();
+        assert_eq!(txt.attribute(b"TXTVERS"), Ok(Some(TxtAttribute { key: b"txtvers", value: Some(b"1") })));
+        assert_eq!(txt.attribute(b"color"), Ok(Some(TxtAttribute { key: b"Color", value: None })));
+
+        let mut addresses = instance.addresses().unwrap();
+        assert_eq!(addresses.next(), Some(Ok(DnsSdAddress::V4([192, 168, 1, 20]))));
+        assert_eq!(
+            addresses.next(),
+            Some(Ok(DnsSdAddress::V6([0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]))),
+        );
+        assert_eq!(addresses.next(), None);
+
+        let other = DnsName::new(b"\x07Scanner\x05_http\x04_tcp\x05local\x00").unwrap();
+        assert_eq!(DnsSdInstance::resolve(&bytes, &other), Ok(None));
+    }
+
+    #[test]
+    fn truncated() {
+        let bytes = response();
+        let bytes = &bytes[..bytes.len() - 4];
+        assert!(DnsSdInstance::resolve(bytes, &instance()).is_err());
+        let service = service();
+        let mut instances = DnsSdInstances::new(bytes, &service).unwrap();
+        assert_eq!(instances.next(), Some(Ok(instance())));
+        assert!(matches!(instances.next(), Some(Err(_))));
+        assert_eq!(instances.next(), None);
+    }
+}
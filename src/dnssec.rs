@@ -0,0 +1,15 @@
+mod canonical;
+pub(crate) mod digest;
+mod nsec;
+pub(crate) mod nsec3;
+mod validator;
+#[cfg(feature = "ring")]
+mod ring;
+
+pub use canonical::{write_canonical_record, write_signing_input};
+pub use digest::{DigestHasher, DsDigestType};
+pub use nsec::{NsecOutcome, NsecProof};
+pub use nsec3::{Nsec3Hash, Nsec3Proof};
+pub use validator::{DnssecAlgorithm, DnssecReason, DnssecStatus, DnssecValidator, DnssecVerifier};
+#[cfg(feature = "ring")]
+pub use self::ring::RingVerifier;
@@ -0,0 +1,372 @@
+use core::cmp::Ordering;
+use core::ops::Range;
+
+use crate::{Buffer, DnsError, DnsMessage, DnsMessageError, MutBuffer};
+use crate::answer::DnsAnswer;
+use crate::name::DnsName;
+use crate::question::DnsQType;
+use crate::rdata::{DnsAType, RawBytes, RRSig};
+use crate::write::WriteBytes;
+
+/// Write the canonical form of the record to the end of the buffer, with the
+/// given original TTL, and return the number of bytes written. The owner
+/// name and the names embedded in the record data are written uncompressed
+/// and in lowercase.
+///
+/// According to [RFC 4034 Section 6.2](https://tools.ietf.org/rfc/rfc4034#section-6.2).
+pub fn write_canonical_record<B: MutBuffer + Buffer>(
+    record: &DnsAnswer<DnsAType>,
+    original_ttl: u32,
+    buffer: &mut B,
+) -> Result<usize, DnsMessageError> {
+    write_record(record, 0, original_ttl, buffer)
+}
+
+/// Write the data covered by the RRSIG record to the end of the buffer and
+/// return the number of bytes written. This is the record data of the RRSIG
+/// record without the signature, followed by the records of the RRset in
+/// canonical form and order, without duplicates. The records must share the
+/// owner name, the class and the type covered by the RRSIG record. An owner
+/// name expanded from a wildcard is replaced by the wildcard.
+///
+/// According to [RFC 4034 Section 3.1.8.1](https://tools.ietf.org/rfc/rfc4034#section-3.1.8.1)
+/// and [RFC 4035 Section 5.3.2](https://tools.ietf.org/rfc/rfc4035#section-5.3.2).
+pub fn write_signing_input<B: MutBuffer + Buffer>(
+    rrsig: &RRSig,
+    rrset: &[DnsAnswer<DnsAType>],
+    buffer: &mut B,
+) -> Result<usize, DnsMessageError> {
+    let first = rrset.first()
+        .ok_or(DnsMessageError::DnsError(DnsError::InvalidRRSet))?;
+    for record in rrset {
+        if record.rdata.id() != rrsig.type_covered
+            || record.aclass != first.aclass
+            || record.name != first.name {
+            return Err(DnsMessageError::DnsError(DnsError::InvalidRRSet));
+        }
+    }
+
+    let labels = label_count(&first.name)?;
+    if rrsig.labels > labels {
+        return Err(DnsMessageError::DnsError(DnsError::InvalidRRSet));
+    }
+
+    let start = buffer.len();
+    let mut message = DnsMessage::raw(&mut *buffer);
+    let rrsig = RRSig { signature: RawBytes::new(&[]), ..*rrsig };
+    let rdata_len = rrsig.write(&mut message)?;
+    lowercase_rdata(DnsQType::RRSIG, buffer.read_bytes_at_mut(start, rdata_len)?)?;
+
+    let records = buffer.len();
+    for record in rrset {
+        let position = buffer.len();
+        write_record(record, labels - rrsig.labels, rrsig.original_ttl, buffer)?;
+        insert_sorted(buffer, records, position)?;
+    }
+
+    Ok(buffer.len() - start)
+}
+
+// Write the record in canonical form, replacing the given number of leading
+// labels of the owner name with a wildcard. A leading wildcard label of the
+// owner name is not counted, like in the labels field of the RRSIG record.
+fn write_record<B: MutBuffer + Buffer>(
+    record: &DnsAnswer<DnsAType>,
+    wildcard_labels: u8,
+    original_ttl: u32,
+    buffer: &mut B,
+) -> Result<usize, DnsMessageError> {
+    let start = buffer.len();
+    let owner_len = {
+        let mut message = DnsMessage::raw(&mut *buffer);
+        let mut owner_len = 0;
+        if wildcard_labels > 0 {
+            owner_len += message.write_bytes(b"\x01*")?;
+            let mut labels = record.name.iter().peekable();
+            if matches!(labels.peek(), Some(Ok(label)) if *label == b"*") {
+                labels.next();
+            }
+            for label in labels.skip(wildcard_labels as usize) {
+                let label = label?;
+                owner_len += message.write_bytes(&[label.len() as u8])?;
+                owner_len += message.write_bytes(label)?;
+            }
+            owner_len += message.write_bytes(&[0])?;
+        } else {
+            owner_len += message.write_name_uncompressed(record.name)?;
+        }
+        // The cache flush bit is not part of the class.
+        message.write_bytes(&record.rdata.id().to_be_bytes())?;
+        message.write_bytes(&record.aclass.id().to_be_bytes())?;
+        message.write_bytes(&original_ttl.to_be_bytes())?;
+        let rdata_len_placeholder = message.write_placeholder::<2>()?;
+        let rdata_len = record.rdata.write(&mut message)?;
+        rdata_len_placeholder(&mut message, (rdata_len as u16).to_be_bytes());
+
+        owner_len
+    };
+
+    let length = buffer.len() - start;
+    let bytes = buffer.read_bytes_at_mut(start, length)?;
+    lowercase_name(bytes, 0)?;
+    lowercase_rdata(DnsQType::from_id(record.rdata.id()), &mut bytes[owner_len + 10..])?;
+
+    Ok(length)
+}
+
+// Move the record at the given position before the first of the sorted
+// records with greater record data, or remove it if it is a duplicate.
+fn insert_sorted<B: MutBuffer + Buffer>(
+    buffer: &mut B,
+    records: usize,
+    position: usize,
+) -> Result<(), DnsMessageError> {
+    let length = buffer.len() - records;
+    let bytes = buffer.read_bytes_at_mut(records, length)?;
+    let (sorted, record) = bytes.split_at_mut(position - records);
+    let record_len = record.len();
+    let rdata = &record[rdata_range(record, 0)?];
+
+    let mut i = 0;
+    while i < sorted.len() {
+        let range = rdata_range(sorted, i)?;
+        match rdata.cmp(&sorted[range.clone()]) {
+            Ordering::Less => break,
+            Ordering::Equal => {
+                buffer.truncate(position)?;
+                return Ok(());
+            }
+            Ordering::Greater => i = range.end,
+        }
+    }
+    bytes[i..].rotate_right(record_len);
+
+    Ok(())
+}
+
+// Return the range of the record data of the canonical record at the given
+// position.
+fn rdata_range(bytes: &[u8], i: usize) -> Result<Range<usize>, DnsMessageError> {
+    let i = skip_name(bytes, i)? + 8;
+    let length = bytes.get(i..i + 2)
+        .ok_or(DnsMessageError::DnsError(DnsError::UnexpectedEndOfBuffer))?;
+    let start = i + 2;
+    let end = start + u16::from_be_bytes([length[0], length[1]]) as usize;
+    if end > bytes.len() {
+        return Err(DnsMessageError::DnsError(DnsError::RDataOverrun));
+    }
+
+    Ok(start..end)
+}
+
+// Lowercase the names embedded in the record data. NSEC is left untouched,
+// as corrected by RFC 6840 Section 5.1, the other types listed in RFC 4034
+// are obsolete.
+fn lowercase_rdata(qtype: DnsQType, rdata: &mut [u8]) -> Result<(), DnsMessageError> {
+    match qtype {
+        DnsQType::NS | DnsQType::CNAME | DnsQType::PTR | DnsQType::DNAME => {
+            lowercase_name(rdata, 0)?;
+        }
+        DnsQType::MX | DnsQType::AFSDB | DnsQType::KX => {
+            lowercase_name(rdata, 2)?;
+        }
+        DnsQType::SRV => {
+            lowercase_name(rdata, 6)?;
+        }
+        DnsQType::SOA | DnsQType::RP => {
+            let i = lowercase_name(rdata, 0)?;
+            lowercase_name(rdata, i)?;
+        }
+        DnsQType::NAPTR => {
+            // Skip the order, the preference, the flags, the service and the
+            // regular expression.
+            let mut i = 4;
+            for _ in 0..3 {
+                i += 1 + *rdata.get(i)
+                    .ok_or(DnsMessageError::DnsError(DnsError::UnexpectedEndOfBuffer))? as usize;
+            }
+            lowercase_name(rdata, i)?;
+        }
+        DnsQType::SIG | DnsQType::RRSIG => {
+            lowercase_name(rdata, 18)?;
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+// Lowercase the uncompressed name at the given position and return the
+// position after it.
+fn lowercase_name(bytes: &mut [u8], mut i: usize) -> Result<usize, DnsMessageError> {
+    loop {
+        let length = *bytes.get(i)
+            .ok_or(DnsMessageError::DnsError(DnsError::UnexpectedEndOfBuffer))? as usize;
+        i += 1;
+        if length == 0 {
+            return Ok(i);
+        }
+
+        bytes.get_mut(i..i + length)
+            .ok_or(DnsMessageError::DnsError(DnsError::UnexpectedEndOfBuffer))?
+            .make_ascii_lowercase();
+        i += length;
+    }
+}
+
+// Return the position after the uncompressed name at the given position.
+fn skip_name(bytes: &[u8], mut i: usize) -> Result<usize, DnsMessageError> {
+    loop {
+        let length = *bytes.get(i)
+            .ok_or(DnsMessageError::DnsError(DnsError::UnexpectedEndOfBuffer))? as usize;
+        i += 1 + length;
+        if length == 0 {
+            return Ok(i);
+        }
+    }
+}
+
+// Return the number of labels of the name, without the root label and a
+// leading wildcard label, as in the labels field of the RRSIG record.
+pub(crate) fn label_count(name: &DnsName) -> Result<u8, DnsMessageError> {
+    let mut labels = 0;
+    for (i, label) in name.iter().enumerate() {
+        let label = label?;
+        if i > 0 || label != b"*" {
+            labels += 1;
+        }
+    }
+
+    Ok(labels)
+}
+
+#[cfg(test)]
+mod test {
+    use arrayvec::ArrayVec;
+    use crate::answer::DnsAClass;
+    use crate::rdata::{A, Mx};
+    use super::*;
+
+    fn rrsig(labels: u8) -> RRSig<'static> {
+        RRSig {
+            type_covered: DnsQType::A.id(),
+            algorithm: 8,
+            labels,
+            original_ttl: 3600,
+            signature_expiration: 0x6000_0000,
+            signature_inception: 0x5f00_0000,
+            key_tag: 12345,
+            signer_name: DnsName::new(b"\x07EXAMPLE\x00").unwrap(),
+            signature: RawBytes::new(&[0xAA; 8]),
+        }
+    }
+
+    fn a(name: &'static [u8], address: [u8; 4]) -> DnsAnswer<'static, DnsAType<'static>> {
+        DnsAnswer {
+            name: DnsName::new(name).unwrap(),
+            rdata: DnsAType::A(A { address }),
+            cache_flush: false,
+            aclass: DnsAClass::IN,
+            ttl: 60,
+        }
+    }
+
+    #[test]
+    fn canonical_record() {
+        let record = DnsAnswer {
+            name: DnsName::new(b"\x07Example\x03COM\x00").unwrap(),
+            rdata: DnsAType::MX(Mx {
+                preference: 10,
+                exchange: DnsName::new(b"\x04MAIL\x07Example\x03com\x00").unwrap(),
+            }),
+            cache_flush: true,
+            aclass: DnsAClass::IN,
+            ttl: 60,
+        };
+        let mut buffer = ArrayVec::<u8, 128>::new();
+        buffer.push(0xFF);
+
+        let length = write_canonical_record(&record, 3600, &mut buffer).unwrap();
+        assert_eq!(length, buffer.len() - 1);
+        assert_eq!(
+            &buffer[1..],
+            b"\x07example\x03com\x00\
+              \x00\x0f\x00\x01\x00\x00\x0e\x10\x00\x14\
+              \x00\x0a\x04mail\x07example\x03com\x00",
+        );
+    }
+
+    #[test]
+    fn signing_input() {
+        let rrset = [
+            a(b"\x03WWW\x07example\x00", [192, 0, 2, 2]),
+            a(b"\x03www\x07Example\x00", [192, 0, 2, 10]),
+            a(b"\x03www\x07example\x00", [192, 0, 2, 1]),
+            a(b"\x03www\x07example\x00", [192, 0, 2, 2]),
+        ];
+        let mut buffer = ArrayVec::<u8, 256>::new();
+
+        let length = write_signing_input(&rrsig(2), &rrset, &mut buffer).unwrap();
+        assert_eq!(length, buffer.len());
+        let record = |last: u8| -> [u8; 27] {
+            let mut bytes = [0; 27];
+            bytes.copy_from_slice(b"\x03www\x07example\x00\x00\x01\x00\x01\x00\x00\x0e\x10\x00\x04\xc0\x00\x02\x00");
+            bytes[26] = last;
+            bytes
+        };
+        let mut expected = ArrayVec::<u8, 256>::new();
+        expected.try_extend_from_slice(
+            b"\x00\x01\x08\x02\x00\x00\x0e\x10\x60\x00\x00\x00\x5f\x00\x00\x00\x30\x39\
+              \x07example\x00",
+        ).unwrap();
+        expected.try_extend_from_slice(&record(1)).unwrap();
+        expected.try_extend_from_slice(&record(2)).unwrap();
+        expected.try_extend_from_slice(&record(10)).unwrap();
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn signing_input_wildcard() {
+        let rrset = [a(b"\x01a\x01b\x07example\x00", [192, 0, 2, 1])];
+        let mut buffer = ArrayVec::<u8, 256>::new();
+
+        write_signing_input(&rrsig(2), &rrset, &mut buffer).unwrap();
+        assert_eq!(
+            &buffer[27..],
+            b"\x01*\x01b\x07example\x00\x00\x01\x00\x01\x00\x00\x0e\x10\x00\x04\xc0\x00\x02\x01",
+        );
+
+        // The wildcard label is not counted.
+        let rrset = [a(b"\x01*\x01b\x07example\x00", [192, 0, 2, 1])];
+        let mut wildcard = ArrayVec::<u8, 256>::new();
+        write_signing_input(&rrsig(2), &rrset, &mut wildcard).unwrap();
+        assert_eq!(wildcard, buffer);
+
+        // An owner name which is itself a wildcard below the wildcard.
+        let rrset = [a(b"\x01*\x01a\x01b\x07example\x00", [192, 0, 2, 1])];
+        let mut nested = ArrayVec::<u8, 256>::new();
+        write_signing_input(&rrsig(2), &rrset, &mut nested).unwrap();
+        assert_eq!(nested, buffer);
+    }
+
+    #[test]
+    fn invalid_rrset() {
+        let mut buffer = ArrayVec::<u8, 256>::new();
+        let error = Err(DnsMessageError::DnsError(DnsError::InvalidRRSet));
+
+        assert_eq!(write_signing_input(&rrsig(2), &[], &mut buffer), error);
+        // More labels than the owner name.
+        let rrset = [a(b"\x03www\x07example\x00", [192, 0, 2, 1])];
+        assert_eq!(write_signing_input(&rrsig(3), &rrset, &mut buffer), error);
+        // Different owner names.
+        let rrset = [
+            a(b"\x03www\x07example\x00", [192, 0, 2, 1]),
+            a(b"\x04mail\x07example\x00", [192, 0, 2, 2]),
+        ];
+        assert_eq!(write_signing_input(&rrsig(2), &rrset, &mut buffer), error);
+        // Not the type covered.
+        let rrsig = RRSig { type_covered: DnsQType::AAAA.id(), ..rrsig(2) };
+        let rrset = [a(b"\x03www\x07example\x00", [192, 0, 2, 1])];
+        assert_eq!(write_signing_input(&rrsig, &rrset, &mut buffer), error);
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{BufferError, DnsError, DnsMessageError};
+use crate::name::DnsName;
+use crate::rdata::DnsKey;
+
+/// A hash function used to create the digest of a DS record. A new instance
+/// is used for every digest.
+pub trait DigestHasher {
+    /// The digest, e.g. `[u8; 32]` for SHA-256.
+    type Digest: AsRef<[u8]>;
+
+    /// Add data to the hashed data.
+    fn update(&mut self, data: &[u8]);
+
+    /// Return the digest of all data added.
+    fn finalize(self) -> Self::Digest;
+}
+
+/// The digest type of a DS record.
+///
+/// According to the [IANA registry](https://www.iana.org/assignments/ds-rr-types/ds-rr-types.xhtml).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum DsDigestType {
+    Sha1,
+    Sha256,
+    Gost,
+    Sha384,
+    /// A digest type without a name in this crate, with its number.
+    Unknown(u8),
+}
+
+impl DsDigestType {
+    /// Create a new digest type from its number.
+    #[inline(always)]
+    pub fn from_id(id: u8) -> Self {
+        match id {
+            1 => DsDigestType::Sha1,
+            2 => DsDigestType::Sha256,
+            3 => DsDigestType::Gost,
+            4 => DsDigestType::Sha384,
+            id => DsDigestType::Unknown(id),
+        }
+    }
+
+    /// Get the number of the digest type.
+    #[inline(always)]
+    pub fn id(&self) -> u8 {
+        match self {
+            DsDigestType::Sha1 => 1,
+            DsDigestType::Sha256 => 2,
+            DsDigestType::Gost => 3,
+            DsDigestType::Sha384 => 4,
+            DsDigestType::Unknown(id) => *id,
+        }
+    }
+}
+
+/// Hash the owner name and the record data of the key into the buffer and
+/// return the digest. Only SHA-1, SHA-256 and SHA-384 digests are supported.
+///
+/// According to [RFC 4034 Section 5.1.4](https://tools.ietf.org/rfc/rfc4034#section-5.1.4).
+pub(crate) fn digest<'a, H: DigestHasher>(
+    owner: DnsName,
+    key: &DnsKey,
+    digest_type: DsDigestType,
+    mut hasher: H,
+    buffer: &'a mut [u8],
+) -> Result<&'a [u8], DnsMessageError> {
+    let length = match digest_type {
+        DsDigestType::Sha1 => 20,
+        DsDigestType::Sha256 => 32,
+        DsDigestType::Sha384 => 48,
+        _ => return Err(DnsMessageError::DnsError(DnsError::InvalidDigest)),
+    };
+
+    owner.canonical(|data| hasher.update(data))?;
+    hasher.update(&key.flags.to_be_bytes());
+    hasher.update(&[key.protocol, key.algorithm]);
+    hasher.update(key.public_key.bytes());
+    let digest = hasher.finalize();
+    let digest = digest.as_ref();
+    if digest.len() != length {
+        return Err(DnsMessageError::DnsError(DnsError::InvalidDigest));
+    }
+
+    let buffer = buffer.get_mut(..length)
+        .ok_or(DnsMessageError::BufferError(BufferError::OutOfMemory))?;
+    buffer.copy_from_slice(digest);
+
+    Ok(buffer)
+}
+
+#[cfg(test)]
+mod test {
+    use arrayvec::ArrayVec;
+    use crate::rdata::{Cds, Ds, RawBytes};
+    use super::*;
+
+    // Not a hash, the digest is of the given length and the hashed data is
+    // recorded.
+    struct TestHasher<'a> {
+        length: usize,
+        input: &'a mut ArrayVec<u8, 64>,
+    }
+
+    impl DigestHasher for TestHasher<'_> {
+        type Digest = ArrayVec<u8, 64>;
+
+        fn update(&mut self, data: &[u8]) {
+            self.input.try_extend_from_slice(data).unwrap();
+        }
+
+        fn finalize(self) -> Self::Digest {
+            (0..self.length).map(|i| i as u8).collect()
+        }
+    }
+
+    const KEY: DnsKey = DnsKey {
+        flags: 257,
+        protocol: 3,
+        algorithm: 15,
+        public_key: RawBytes::new(&[0xAA, 0xBB, 0xCC]),
+    };
+
+    fn owner() -> DnsName<'static> {
+        DnsName::new(b"\x07Example\x03COM\x00").unwrap()
+    }
+
+    #[test]
+    fn build() {
+        let mut input = ArrayVec::new();
+        let mut buffer = [0; 64];
+        let hasher = TestHasher { length: 32, input: &mut input };
+        let ds = Ds::build(owner(), &KEY, DsDigestType::Sha256, hasher, &mut buffer).unwrap();
+
+        // The owner name is hashed in lowercase, followed by the record data.
+        assert_eq!(input.as_slice(), b"\x07example\x03com\x00\x01\x01\x03\x0f\xaa\xbb\xcc");
+        assert_eq!(ds.key_tag, KEY.key_tag());
+        assert_eq!(ds.algorithm, 15);
+        assert_eq!(ds.digest_type, 2);
+        assert_eq!(ds.digest.bytes().len(), 32);
+        assert_eq!(ds.digest.bytes()[31], 31);
+
+        let mut input = ArrayVec::new();
+        let mut buffer = [0; 64];
+        let hasher = TestHasher { length: 48, input: &mut input };
+        let cds = Cds::build(owner(), &KEY, DsDigestType::Sha384, hasher, &mut buffer).unwrap();
+        assert_eq!(cds.digest_type, 4);
+        assert_eq!(cds.digest.bytes().len(), 48);
+    }
+
+    #[test]
+    fn invalid() {
+        let mut input = ArrayVec::new();
+        let mut buffer = [0; 64];
+
+        // The hasher does not match the digest type.
+        let hasher = TestHasher { length: 32, input: &mut input };
+        assert_eq!(
+            Ds::build(owner(), &KEY, DsDigestType::Sha1, hasher, &mut buffer),
+            Err(DnsMessageError::DnsError(DnsError::InvalidDigest)),
+        );
+        let hasher = TestHasher { length: 32, input: &mut input };
+        assert_eq!(
+            Ds::build(owner(), &KEY, DsDigestType::Gost, hasher, &mut buffer),
+            Err(DnsMessageError::DnsError(DnsError::InvalidDigest)),
+        );
+
+        let mut buffer = [0; 31];
+        let hasher = TestHasher { length: 32, input: &mut input };
+        assert_eq!(
+            Ds::build(owner(), &KEY, DsDigestType::Sha256, hasher, &mut buffer),
+            Err(DnsMessageError::BufferError(BufferError::OutOfMemory)),
+        );
+    }
+}
@@ -0,0 +1,301 @@
+use core::cmp::Ordering;
+use crate::{Buffer, DnsError, DnsMessageError};
+use crate::dnssec::DnssecReason;
+use crate::name::DnsName;
+use crate::name_servers::DnsNameServers;
+use crate::question::DnsQType;
+use crate::rdata::{DnsAType, Nsec};
+
+/// What the NSEC records of a response prove about a question.
+///
+/// According to [RFC 4035 Section 5.4](https://tools.ietf.org/rfc/rfc4035#section-5.4).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum NsecOutcome {
+    /// The name does not exist and neither does the wildcard at its closest
+    /// encloser.
+    NameError,
+    /// The name exists, but has no records of the type.
+    NoData,
+    /// The name does not exist and the wildcard at its closest encloser has
+    /// no records of the type.
+    WildcardNoData,
+    /// The name does not exist, but the wildcard at its closest encloser with
+    /// the number of labels may. A wildcard answer is valid if the labels
+    /// field of its signature is equal to the number of labels.
+    Wildcard(u8),
+    /// The records prove nothing about the question.
+    Unproven(DnssecReason),
+}
+
+/// Proofs of the denial of existence of a name or type with the NSEC records
+/// of a response. The records are added one by one, so they can be read from
+/// the name servers section while it is iterated. The signatures of the
+/// records must be validated separately.
+pub struct NsecProof<'q> {
+    qname: DnsName<'q>,
+    qtype: DnsQType,
+    // Whether the type exists, if a record matches the name.
+    type_exists: Option<bool>,
+    // The number of labels of the closest encloser, if a record covers the
+    // name.
+    closest_encloser: Option<usize>,
+    // The wildcards covered by a record and the wildcards without the type,
+    // with bit n for the wildcard at the ancestor with n labels.
+    wildcard_covered: u128,
+    wildcard_no_type: u128,
+}
+
+impl<'q> NsecProof<'q> {
+    /// Create a new proof for the question.
+    #[inline(always)]
+    pub fn new(qname: DnsName<'q>, qtype: DnsQType) -> Self {
+        Self {
+            qname,
+            qtype,
+            type_exists: None,
+            closest_encloser: None,
+            wildcard_covered: 0,
+            wildcard_no_type: 0,
+        }
+    }
+
+    /// Add the NSEC records of the remaining name servers section. Other
+    /// records are ignored.
+    pub fn add_name_servers<
+        const PTR_STORAGE: usize,
+        B: Buffer,
+    >(&mut self, name_servers: &mut DnsNameServers<PTR_STORAGE, B>) -> Result<(), DnsMessageError> {
+        for name_server in name_servers.iter()? {
+            let name_server = name_server?.into_parsed()?;
+            if let DnsAType::NSEC(nsec) = &name_server.rdata {
+                self.add(&name_server.name, nsec)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Add an NSEC record with its owner name.
+    pub fn add(&mut self, owner: &DnsName, nsec: &Nsec) -> Result<(), DnsMessageError> {
+        let types = nsec.type_bit_maps;
+        let delegation = types.contains(DnsQType::NS) && !types.contains(DnsQType::SOA);
+
+        match owner.canonical_cmp(&self.qname)? {
+            Ordering::Equal => {
+                // The record of a delegation only proves the absence of DS
+                // records and the record at the apex of a child zone only
+                // the absence of other records.
+                let parent_side = delegation && self.qtype != DnsQType::DS;
+                let child_side = self.qtype == DnsQType::DS
+                    && types.contains(DnsQType::SOA)
+                    && self.qname.parent().is_some();
+                if !parent_side && !child_side {
+                    self.type_exists = Some(types.contains(self.qtype) || types.contains(DnsQType::CNAME));
+                }
+            }
+            Ordering::Less if covers(owner, &nsec.next_domain_name, &self.qname)? => {
+                // Names below a delegation or a DNAME are not in the zone.
+                let redirect = delegation || types.contains(DnsQType::DNAME);
+                if !(redirect && self.qname.is_subdomain_of(owner)) {
+                    if nsec.next_domain_name.is_subdomain_of(&self.qname) {
+                        // The name is an empty non-terminal.
+                        self.type_exists.get_or_insert(false);
+                    } else {
+                        let closest_encloser = self.qname.common_labels(owner)?
+                            .max(self.qname.common_labels(&nsec.next_domain_name)?);
+                        self.closest_encloser.get_or_insert(closest_encloser);
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        let mut buffer = [0; 256];
+        let mut ancestor = self.qname.parent();
+        while let Some(name) = ancestor {
+            ancestor = name.parent();
+            let Ok(wildcard) = wildcard(&name, &mut buffer) else {
+                continue;
+            };
+            let bit = 1u128 << name.label_count()?;
+
+            match owner.canonical_cmp(&wildcard)? {
+                Ordering::Equal => if !types.contains(self.qtype) && !types.contains(DnsQType::CNAME) {
+                    self.wildcard_no_type |= bit;
+                },
+                Ordering::Less => if covers(owner, &nsec.next_domain_name, &wildcard)? {
+                    self.wildcard_covered |= bit;
+                },
+                Ordering::Greater => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Return what the added records prove about the question.
+    pub fn outcome(&self) -> NsecOutcome {
+        match self.type_exists {
+            Some(true) => return NsecOutcome::Unproven(DnssecReason::TypeExists),
+            Some(false) => return NsecOutcome::NoData,
+            None => {}
+        }
+
+        let Some(closest_encloser) = self.closest_encloser else {
+            return NsecOutcome::Unproven(DnssecReason::MissingDenial);
+        };
+        let bit = 1u128 << closest_encloser;
+        if self.wildcard_covered & bit != 0 {
+            NsecOutcome::NameError
+        } else if self.wildcard_no_type & bit != 0 {
+            NsecOutcome::WildcardNoData
+        } else {
+            NsecOutcome::Wildcard(closest_encloser as u8)
+        }
+    }
+}
+
+// Return whether the name is between the owner and the next name. The next
+// name of the last record is the apex of the zone. The owner must be before
+// the name.
+#[inline]
+fn covers(owner: &DnsName, next: &DnsName, name: &DnsName) -> Result<bool, DnsMessageError> {
+    Ok(match next.canonical_cmp(name)? {
+        Ordering::Greater => true,
+        Ordering::Equal => false,
+        Ordering::Less => next.canonical_cmp(owner)? != Ordering::Greater,
+    })
+}
+
+// Write the wildcard name below the name into the buffer.
+fn wildcard<'b>(name: &DnsName, buffer: &'b mut [u8; 256]) -> Result<DnsName<'b>, DnsMessageError> {
+    buffer[..2].copy_from_slice(b"\x01*");
+    let mut length = 2;
+    let mut overflow = false;
+    name.canonical(|data| {
+        match buffer.get_mut(length..length + data.len()) {
+            Some(bytes) => bytes.copy_from_slice(data),
+            None => overflow = true,
+        }
+        length += data.len();
+    })?;
+    if overflow {
+        return Err(DnsMessageError::DnsError(DnsError::NameTooLong));
+    }
+
+    DnsName::new(&buffer[..length])
+}
+
+#[cfg(test)]
+mod test {
+    use arrayvec::ArrayVec;
+    use crate::DnsMessage;
+    use crate::answer::DnsAClass;
+    use crate::name_servers::NameServer;
+    use crate::rdata::TypeBitMap;
+    use super::*;
+
+    // The type bit maps of the example zone of RFC 4035 Appendix A.
+    const APEX: &[u8] = &[0x00, 0x07, 0x22, 0x01, 0x00, 0x00, 0x00, 0x03, 0x80];
+    const NS_RRSIG_NSEC: &[u8] = &[0x00, 0x06, 0x20, 0x00, 0x00, 0x00, 0x00, 0x03];
+    const A_RRSIG_NSEC: &[u8] = &[0x00, 0x06, 0x40, 0x00, 0x00, 0x00, 0x00, 0x03];
+    const MX_RRSIG_NSEC: &[u8] = &[0x00, 0x06, 0x00, 0x01, 0x00, 0x00, 0x00, 0x03];
+
+    fn name(bytes: &'static [u8]) -> DnsName<'static> {
+        DnsName::new(bytes).unwrap()
+    }
+
+    fn nsec(next: &'static [u8], types: &'static [u8]) -> Nsec<'static> {
+        Nsec {
+            next_domain_name: name(next),
+            type_bit_maps: TypeBitMap::new(types).unwrap(),
+        }
+    }
+
+    fn outcome(qname: &'static [u8], qtype: DnsQType, records: &[(&'static [u8], Nsec)]) -> NsecOutcome {
+        let mut proof = NsecProof::new(name(qname), qtype);
+        for (owner, nsec) in records {
+            proof.add(&name(owner), nsec).unwrap();
+        }
+
+        proof.outcome()
+    }
+
+    #[test]
+    fn name_error() {
+        // RFC 4035 Appendix B.2, read from the name servers section.
+        let message: DnsMessage<8, 0, _> = DnsMessage::new_mut(ArrayVec::<u8, 512>::new()).unwrap();
+        let message = message.questions().complete().unwrap();
+        let message = message.answers().complete().unwrap();
+        let mut name_servers = message.name_servers();
+        for (owner, nsec) in [
+            (b"\x01b\x07example\x00".as_slice(), nsec(b"\x03ns1\x07example\x00", NS_RRSIG_NSEC)),
+            (b"\x07example\x00".as_slice(), nsec(b"\x01a\x07example\x00", APEX)),
+        ] {
+            name_servers.append(NameServer {
+                name: name(owner),
+                rdata: DnsAType::NSEC(nsec),
+                cache_flush: false,
+                aclass: DnsAClass::IN,
+                ttl: 3600,
+            }).unwrap();
+        }
+        let buffer = name_servers.complete().unwrap().abort().unwrap();
+
+        let message: DnsMessage<8, 0, _> = DnsMessage::new(buffer.as_slice()).unwrap();
+        let message = message.questions().complete().unwrap();
+        let message = message.answers().complete().unwrap();
+        let mut name_servers = message.name_servers();
+        let mut proof = NsecProof::new(name(b"\x02ml\x07example\x00"), DnsQType::A);
+        proof.add_name_servers(&mut name_servers).unwrap();
+        assert_eq!(proof.outcome(), NsecOutcome::NameError);
+
+        // The wildcard *.example is not denied.
+        let records = [(b"\x01b\x07example\x00".as_slice(), nsec(b"\x03ns1\x07example\x00", NS_RRSIG_NSEC))];
+        assert_eq!(outcome(b"\x02ml\x07example\x00", DnsQType::A, &records), NsecOutcome::Wildcard(1));
+
+        assert_eq!(outcome(b"\x02ml\x07example\x00", DnsQType::A, &[]), NsecOutcome::Unproven(DnssecReason::MissingDenial));
+    }
+
+    #[test]
+    fn no_data() {
+        // RFC 4035 Appendix B.3.
+        let records = [(b"\x03ns1\x07example\x00".as_slice(), nsec(b"\x03ns2\x07example\x00", A_RRSIG_NSEC))];
+        assert_eq!(outcome(b"\x03NS1\x07example\x00", DnsQType::MX, &records), NsecOutcome::NoData);
+        assert_eq!(outcome(b"\x03ns1\x07example\x00", DnsQType::A, &records), NsecOutcome::Unproven(DnssecReason::TypeExists));
+
+        // The empty non-terminal y.w.example.
+        let records = [(b"\x01x\x01w\x07example\x00".as_slice(), nsec(b"\x01x\x01y\x01w\x07example\x00", MX_RRSIG_NSEC))];
+        assert_eq!(outcome(b"\x01y\x01w\x07example\x00", DnsQType::A, &records), NsecOutcome::NoData);
+    }
+
+    #[test]
+    fn delegation() {
+        let records = [(b"\x01b\x07example\x00".as_slice(), nsec(b"\x03ns1\x07example\x00", NS_RRSIG_NSEC))];
+        assert_eq!(outcome(b"\x01b\x07example\x00", DnsQType::DS, &records), NsecOutcome::NoData);
+        assert_eq!(outcome(b"\x01b\x07example\x00", DnsQType::A, &records), NsecOutcome::Unproven(DnssecReason::MissingDenial));
+        // Names below the delegation are not in the zone.
+        assert_eq!(outcome(b"\x01c\x01b\x07example\x00", DnsQType::A, &records), NsecOutcome::Unproven(DnssecReason::MissingDenial));
+
+        // The apex of a child zone does not prove the absence of DS records.
+        let records = [(b"\x07example\x00".as_slice(), nsec(b"\x01a\x07example\x00", APEX))];
+        assert_eq!(outcome(b"\x07example\x00", DnsQType::DS, &records), NsecOutcome::Unproven(DnssecReason::MissingDenial));
+        assert_eq!(outcome(b"\x07example\x00", DnsQType::A, &records), NsecOutcome::NoData);
+    }
+
+    #[test]
+    fn wildcard() {
+        // RFC 4035 Appendix B.6, a.z.w.example is answered by *.w.example.
+        let covering = (b"\x01x\x01y\x01w\x07example\x00".as_slice(), nsec(b"\x02xx\x07example\x00", MX_RRSIG_NSEC));
+        let records = [covering];
+        assert_eq!(outcome(b"\x01a\x01z\x01w\x07example\x00", DnsQType::MX, &records), NsecOutcome::Wildcard(2));
+
+        // RFC 4035 Appendix B.7, *.w.example has no AAAA records.
+        let records = [
+            records[0],
+            (b"\x01*\x01w\x07example\x00".as_slice(), nsec(b"\x01x\x01w\x07example\x00", MX_RRSIG_NSEC)),
+        ];
+        assert_eq!(outcome(b"\x01a\x01z\x01w\x07example\x00", DnsQType::AAAA, &records), NsecOutcome::WildcardNoData);
+        assert_eq!(outcome(b"\x01a\x01z\x01w\x07example\x00", DnsQType::MX, &records), NsecOutcome::Wildcard(2));
+    }
+}
@@ -0,0 +1,659 @@
+use core::fmt::{Display, Formatter};
+use crate::{DnsError, DnsMessageError};
+use crate::answer::DnsAnswer;
+use crate::dnssec::canonical::label_count;
+use crate::dnssec::{DigestHasher, DnssecReason, DnssecStatus};
+use crate::name::DnsName;
+use crate::question::DnsQType;
+use crate::rdata::{DnsAType, Nsec3};
+
+// The only hash algorithm of NSEC3 records is SHA-1.
+const SHA1: u8 = 1;
+// Validators may treat responses with more iterations as insecure.
+// According to RFC 9276 Section 3.2.
+const MAX_ITERATIONS: u16 = 150;
+const BASE32HEX: &[u8; 32] = b"0123456789abcdefghijklmnopqrstuv";
+
+/// The SHA-1 hash of a name, as used in the owner names of NSEC3 records.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Nsec3Hash {
+    bytes: [u8; 20],
+}
+
+impl Nsec3Hash {
+    /// Create a new hash from the raw bytes.
+    #[inline(always)]
+    pub const fn new(bytes: [u8; 20]) -> Self {
+        Self { bytes }
+    }
+
+    /// Create a new hash from its base32hex encoded label, ignoring case.
+    pub fn from_label(label: &[u8]) -> Result<Self, DnsMessageError> {
+        if label.len() != 32 {
+            return Err(DnsMessageError::DnsError(DnsError::InvalidDigest));
+        }
+
+        let mut bytes = [0; 20];
+        for (chars, chunk) in label.chunks(8).zip(bytes.chunks_mut(5)) {
+            let mut group = 0u64;
+            for &c in chars {
+                let value = match c.to_ascii_lowercase() {
+                    c @ b'0'..=b'9' => c - b'0',
+                    c @ b'a'..=b'v' => c - b'a' + 10,
+                    _ => return Err(DnsMessageError::DnsError(DnsError::InvalidDigest)),
+                };
+                group = group << 5 | value as u64;
+            }
+            for (i, byte) in chunk.iter_mut().enumerate() {
+                *byte = (group >> (32 - 8 * i)) as u8;
+            }
+        }
+
+        Ok(Self { bytes })
+    }
+
+    /// The raw bytes of the hash.
+    #[inline(always)]
+    pub fn bytes(&self) -> &[u8; 20] {
+        &self.bytes
+    }
+
+    /// The hash encoded in lowercase base32hex, as used for the first label
+    /// of the owner name of NSEC3 records.
+    ///
+    /// According to [RFC 4648 Section 7](https://tools.ietf.org/rfc/rfc4648#section-7).
+    pub fn label(&self) -> [u8; 32] {
+        let mut label = [0; 32];
+        for (chunk, chars) in self.bytes.chunks(5).zip(label.chunks_mut(8)) {
+            let group = chunk.iter().fold(0u64, |group, &byte| group << 8 | byte as u64);
+            for (i, c) in chars.iter_mut().enumerate() {
+                *c = BASE32HEX[(group >> (35 - 5 * i) & 0x1F) as usize];
+            }
+        }
+
+        label
+    }
+}
+
+impl Display for Nsec3Hash {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let label = self.label();
+        f.write_str(core::str::from_utf8(&label).map_err(|_| core::fmt::Error)?)
+    }
+}
+
+/// Hash the name, or the wildcard name below it, with the salt and the
+/// number of additional iterations. A new clone of the hasher is used for
+/// every iteration.
+///
+/// According to [RFC 5155 Section 5](https://tools.ietf.org/rfc/rfc5155#section-5).
+pub(crate) fn hash<H: DigestHasher + Clone>(
+    name: &DnsName,
+    wildcard: bool,
+    hash_algorithm: u8,
+    salt: &[u8],
+    iterations: u16,
+    hasher: &H,
+) -> Result<Nsec3Hash, DnsMessageError> {
+    if hash_algorithm != SHA1 {
+        return Err(DnsMessageError::DnsError(DnsError::InvalidDigest));
+    }
+
+    let mut digest = hasher.clone();
+    if wildcard {
+        digest.update(b"\x01*");
+    }
+    name.canonical(|data| digest.update(data))?;
+    digest.update(salt);
+    let mut bytes = digest_bytes(digest.finalize())?;
+
+    for _ in 0..iterations {
+        let mut digest = hasher.clone();
+        digest.update(&bytes);
+        digest.update(salt);
+        bytes = digest_bytes(digest.finalize())?;
+    }
+
+    Ok(Nsec3Hash { bytes })
+}
+
+#[inline]
+fn digest_bytes<D: AsRef<[u8]>>(digest: D) -> Result<[u8; 20], DnsMessageError> {
+    digest.as_ref().try_into()
+        .map_err(|_| DnsMessageError::DnsError(DnsError::InvalidDigest))
+}
+
+// The parameters of the NSEC3 records used for a proof.
+struct Params<'a> {
+    zone: DnsName<'a>,
+    iterations: u16,
+    salt: &'a [u8],
+}
+
+/// Proofs of the denial of existence of a name or type with the NSEC3
+/// records of a response. The parameters of the first NSEC3 record with a
+/// supported hash algorithm are used, records with other parameters are
+/// ignored. The signatures of the records must be validated separately.
+///
+/// According to [RFC 5155 Section 8](https://tools.ietf.org/rfc/rfc5155#section-8).
+pub struct Nsec3Proof<'a, H> {
+    records: &'a [DnsAnswer<'a, DnsAType<'a>>],
+    hasher: H,
+}
+
+impl<'a, H: DigestHasher + Clone> Nsec3Proof<'a, H> {
+    /// Create a new proof with the records of a response and a SHA-1 hasher.
+    /// Records which are not NSEC3 records are ignored.
+    #[inline(always)]
+    pub fn new(records: &'a [DnsAnswer<'a, DnsAType<'a>>], hasher: H) -> Self {
+        Self { records, hasher }
+    }
+
+    /// Prove that the name does not exist, with the closest encloser proof
+    /// and a record covering the wildcard at the closest encloser. The proof
+    /// is insecure if the next closer name is in an opt-out span.
+    ///
+    /// According to [RFC 5155 Section 8.4](https://tools.ietf.org/rfc/rfc5155#section-8.4).
+    pub fn name_error(&self, qname: &DnsName) -> Result<DnssecStatus, DnsMessageError> {
+        let params = match self.params() {
+            Ok(params) => params,
+            Err(status) => return Ok(status),
+        };
+        let Some((closest_encloser, next_closer)) = self.closest_encloser(&params, qname)? else {
+            return Ok(DnssecStatus::Bogus(DnssecReason::NoClosestEncloser));
+        };
+
+        let wildcard = self.hash(&params, &closest_encloser, true)?;
+        if self.covering(&params, wildcard).is_none() {
+            return Ok(DnssecStatus::Bogus(DnssecReason::NotCovered));
+        }
+
+        if next_closer.opt_out() {
+            Ok(DnssecStatus::Insecure(DnssecReason::OptOut))
+        } else {
+            Ok(DnssecStatus::Secure)
+        }
+    }
+
+    /// Prove that the name has no records of the type. Either a record
+    /// matches the name, an opt-out span covers the delegation for a DS
+    /// query or a record matches the wildcard at the closest encloser.
+    ///
+    /// According to [RFC 5155 Sections 8.5 to 8.7](https://tools.ietf.org/rfc/rfc5155#section-8.5).
+    pub fn no_data(&self, qname: &DnsName, qtype: DnsQType) -> Result<DnssecStatus, DnsMessageError> {
+        let params = match self.params() {
+            Ok(params) => params,
+            Err(status) => return Ok(status),
+        };
+
+        let hash = self.hash(&params, qname, false)?;
+        if let Some(record) = self.matching(&params, hash) {
+            return Ok(type_absent(record, qtype));
+        }
+
+        let Some((closest_encloser, next_closer)) = self.closest_encloser(&params, qname)? else {
+            return Ok(DnssecStatus::Bogus(DnssecReason::NoClosestEncloser));
+        };
+        if qtype == DnsQType::DS {
+            return if next_closer.opt_out() {
+                Ok(DnssecStatus::Insecure(DnssecReason::OptOut))
+            } else {
+                Ok(DnssecStatus::Bogus(DnssecReason::NotCovered))
+            };
+        }
+
+        let wildcard = self.hash(&params, &closest_encloser, true)?;
+        match self.matching(&params, wildcard) {
+            Some(record) => Ok(type_absent(record, qtype)),
+            None => Ok(DnssecStatus::Bogus(DnssecReason::MissingDenial)),
+        }
+    }
+
+    /// Prove that a wildcard answer is valid, i.e. that the next closer name
+    /// does not exist. The labels are the labels field of the signature of
+    /// the answer.
+    ///
+    /// According to [RFC 5155 Section 8.8](https://tools.ietf.org/rfc/rfc5155#section-8.8).
+    pub fn wildcard(&self, qname: &DnsName, labels: u8) -> Result<DnssecStatus, DnsMessageError> {
+        let params = match self.params() {
+            Ok(params) => params,
+            Err(status) => return Ok(status),
+        };
+
+        let count = label_count(qname)?;
+        if labels >= count {
+            return Ok(DnssecStatus::Bogus(DnssecReason::Labels));
+        }
+        // The next closer name has one label more than the wildcard.
+        let mut next_closer = *qname;
+        for _ in labels + 1..count {
+            next_closer = next_closer.parent()
+                .ok_or(DnsMessageError::DnsError(DnsError::InvalidRRSet))?;
+        }
+
+        let hash = self.hash(&params, &next_closer, false)?;
+        if self.covering(&params, hash).is_some() {
+            Ok(DnssecStatus::Secure)
+        } else {
+            Ok(DnssecStatus::Bogus(DnssecReason::NotCovered))
+        }
+    }
+
+    fn params(&self) -> Result<Params<'a>, DnssecStatus> {
+        let mut nsec3 = false;
+        for record in self.records {
+            let DnsAType::NSEC3(rdata) = &record.rdata else {
+                continue;
+            };
+            nsec3 = true;
+
+            if let Some((_, _, zone)) = usable(record, rdata) {
+                if rdata.iterations > MAX_ITERATIONS {
+                    return Err(DnssecStatus::Insecure(DnssecReason::Iterations));
+                }
+
+                return Ok(Params {
+                    zone,
+                    iterations: rdata.iterations,
+                    salt: rdata.salt.bytes(),
+                });
+            }
+        }
+
+        if nsec3 {
+            Err(DnssecStatus::Insecure(DnssecReason::UnsupportedAlgorithm))
+        } else {
+            Err(DnssecStatus::Bogus(DnssecReason::MissingDenial))
+        }
+    }
+
+    #[inline]
+    fn hash(&self, params: &Params, name: &DnsName, wildcard: bool) -> Result<Nsec3Hash, DnsMessageError> {
+        hash(name, wildcard, SHA1, params.salt, params.iterations, &self.hasher)
+    }
+
+    // The records with the parameters, with their owner and next hashes.
+    fn records<'p>(&'p self, params: &'p Params<'a>) -> impl Iterator<Item = (Nsec3Hash, Nsec3Hash, &'a Nsec3<'a>)> + 'p {
+        self.records.iter().filter_map(move |record| {
+            let DnsAType::NSEC3(rdata) = &record.rdata else {
+                return None;
+            };
+            let (owner, next, zone) = usable(record, rdata)?;
+
+            let same = rdata.iterations == params.iterations
+                && rdata.salt.bytes() == params.salt
+                && zone == params.zone;
+            same.then_some((owner, next, rdata))
+        })
+    }
+
+    #[inline]
+    fn matching(&self, params: &Params<'a>, hash: Nsec3Hash) -> Option<&'a Nsec3<'a>> {
+        self.records(params)
+            .find(|&(owner, _, _)| owner == hash)
+            .map(|(_, _, rdata)| rdata)
+    }
+
+    // The last record of the zone covers the hashes after its owner and
+    // before the first owner.
+    #[inline]
+    fn covering(&self, params: &Params<'a>, hash: Nsec3Hash) -> Option<&'a Nsec3<'a>> {
+        self.records(params)
+            .find(|&(owner, next, _)| if owner < next {
+                owner < hash && hash < next
+            } else {
+                owner < hash || hash < next
+            })
+            .map(|(_, _, rdata)| rdata)
+    }
+
+    // Find the closest encloser of the name and the record covering the next
+    // closer name. The closest encloser must not be a delegation or a DNAME.
+    //
+    // According to RFC 5155 Section 8.3.
+    fn closest_encloser<'q>(
+        &self,
+        params: &Params<'a>,
+        qname: &DnsName<'q>,
+    ) -> Result<Option<(DnsName<'q>, &'a Nsec3<'a>)>, DnsMessageError> {
+        let mut next_closer = *qname;
+        while let Some(name) = next_closer.parent() {
+            if !name.is_subdomain_of(&params.zone) {
+                break;
+            }
+
+            let hash = self.hash(params, &name, false)?;
+            if let Some(record) = self.matching(params, hash) {
+                let types = record.type_bit_maps;
+                if types.contains(DnsQType::DNAME)
+                    || (types.contains(DnsQType::NS) && !types.contains(DnsQType::SOA)) {
+                    return Ok(None);
+                }
+
+                let hash = self.hash(params, &next_closer, false)?;
+                return Ok(self.covering(params, hash).map(|covering| (name, covering)));
+            }
+
+            next_closer = name;
+        }
+
+        Ok(None)
+    }
+}
+
+// The owner hash, the next hash and the zone of a record, if its hash
+// algorithm is supported.
+fn usable<'a>(record: &DnsAnswer<'a, DnsAType<'a>>, rdata: &Nsec3) -> Option<(Nsec3Hash, Nsec3Hash, DnsName<'a>)> {
+    if rdata.hash_algorithm != SHA1 {
+        return None;
+    }
+
+    let next = Nsec3Hash {
+        bytes: rdata.next_hashed_owner_name.bytes().try_into().ok()?,
+    };
+    let label = record.name.iter().next()?.ok()?;
+    let owner = Nsec3Hash::from_label(label).ok()?;
+
+    Some((owner, next, record.name.parent()?))
+}
+
+#[inline]
+fn type_absent(record: &Nsec3, qtype: DnsQType) -> DnssecStatus {
+    let types = record.type_bit_maps;
+    if types.contains(qtype) || types.contains(DnsQType::CNAME) {
+        DnssecStatus::Bogus(DnssecReason::TypeExists)
+    } else {
+        DnssecStatus::Secure
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use arrayvec::ArrayVec;
+    use crate::answer::DnsAClass;
+    use crate::characters::Characters;
+    use crate::rdata::{Nsec3Param, TypeBitMap};
+    use super::*;
+
+    // A minimal SHA-1, so the examples of RFC 5155 Appendix B can be tested
+    // without a cryptographic backend.
+    #[derive(Clone, Default)]
+    struct Sha1 {
+        data: ArrayVec<u8, 512>,
+    }
+
+    impl DigestHasher for Sha1 {
+        type Digest = [u8; 20];
+
+        fn update(&mut self, data: &[u8]) {
+            self.data.try_extend_from_slice(data).unwrap();
+        }
+
+        fn finalize(mut self) -> Self::Digest {
+            let bits = self.data.len() as u64 * 8;
+            self.data.push(0x80);
+            while self.data.len() % 64 != 56 {
+                self.data.push(0);
+            }
+            self.data.try_extend_from_slice(&bits.to_be_bytes()).unwrap();
+
+            let mut h = [0x67452301u32, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+            for block in self.data.chunks(64) {
+                let mut w = [0u32; 80];
+                for (w, word) in w.iter_mut().zip(block.chunks(4)) {
+                    *w = u32::from_be_bytes(word.try_into().unwrap());
+                }
+                for i in 16..80 {
+                    w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+                }
+
+                let [mut a, mut b, mut c, mut d, mut e] = h;
+                for (i, w) in w.into_iter().enumerate() {
+                    let (f, k) = match i {
+                        0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                        20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                        40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                        _ => (b ^ c ^ d, 0xCA62C1D6),
+                    };
+                    let t = a.rotate_left(5).wrapping_add(f).wrapping_add(e).wrapping_add(k).wrapping_add(w);
+                    e = d;
+                    d = c;
+                    c = b.rotate_left(30);
+                    b = a;
+                    a = t;
+                }
+                for (h, v) in h.iter_mut().zip([a, b, c, d, e]) {
+                    *h = h.wrapping_add(v);
+                }
+            }
+
+            let mut digest = [0; 20];
+            for (bytes, h) in digest.chunks_mut(4).zip(h) {
+                bytes.copy_from_slice(&h.to_be_bytes());
+            }
+
+            digest
+        }
+    }
+
+    const OPT_OUT: u8 = 1;
+
+    // The next hashed owner names of the example zone.
+    const NEXT_2T7B: [u8; 20] = [
+        0x17, 0x4e, 0xb2, 0x40, 0x9f, 0xe2, 0x8b, 0xcb, 0x48, 0x87,
+        0xa1, 0x83, 0x6f, 0x95, 0x7f, 0x0a, 0x84, 0x25, 0xe2, 0x7b,
+    ];
+    const NEXT_2VPT: [u8; 20] = [
+        0x17, 0xf3, 0xdf, 0x17, 0xb2, 0xb2, 0xad, 0xae, 0xf6, 0x15,
+        0x25, 0x7d, 0xe4, 0xd2, 0x02, 0x0b, 0x80, 0xac, 0x6c, 0x7c,
+    ];
+    const NEXT_B4UM: [u8; 20] = [
+        0x59, 0x3d, 0x64, 0x19, 0xd0, 0x8c, 0x5b, 0xc3, 0x5d, 0xca,
+        0x0a, 0x4d, 0xcb, 0x7e, 0xd5, 0xc1, 0x31, 0xbe, 0x25, 0x25,
+    ];
+    const NEXT_GJEQ: [u8; 20] = [
+        0x84, 0xdd, 0xa7, 0x14, 0x46, 0xcd, 0x56, 0xf0, 0xc1, 0x16,
+        0xa5, 0x72, 0x54, 0xba, 0xef, 0x69, 0xd0, 0x9b, 0xce, 0x12,
+    ];
+    const NEXT_K8UD: [u8; 20] = [
+        0xa2, 0x3c, 0xd7, 0x5b, 0xf9, 0x0c, 0xc4, 0xf3, 0xba, 0x06,
+        0x9b, 0x97, 0x9e, 0x04, 0xff, 0xc8, 0xee, 0x89, 0x15, 0x11,
+    ];
+    const NEXT_KOHA: [u8; 20] = [
+        0xa6, 0x22, 0xad, 0x9e, 0xcb, 0x5a, 0x1a, 0xc1, 0x31, 0xc8,
+        0x52, 0x75, 0xfa, 0xa2, 0x38, 0xb9, 0x28, 0x51, 0xfa, 0x32,
+    ];
+    const NEXT_R53B: [u8; 20] = [
+        0xd9, 0x46, 0xbd, 0x1d, 0x8c, 0x17, 0xbf, 0x6f, 0x2d, 0xfe,
+        0x2e, 0x19, 0x6b, 0x1b, 0x2e, 0xdf, 0x13, 0xda, 0x25, 0xd7,
+    ];
+    const NEXT_T644: [u8; 20] = [
+        0xe9, 0x88, 0x47, 0x2f, 0x54, 0x4a, 0xe4, 0xb6, 0x5d, 0x48,
+        0x39, 0x21, 0x2f, 0xec, 0xd3, 0xc4, 0xcc, 0x2b, 0x56, 0x3f,
+    ];
+
+    // The type bit maps of the example zone.
+    const APEX: &[u8] = &[0x00, 0x07, 0x22, 0x01, 0x00, 0x00, 0x00, 0x02, 0x90];
+    const A_RRSIG: &[u8] = &[0x00, 0x06, 0x40, 0x00, 0x00, 0x00, 0x00, 0x02];
+    const NS_DS_RRSIG: &[u8] = &[0x00, 0x06, 0x20, 0x00, 0x00, 0x00, 0x00, 0x12];
+    const MX_RRSIG: &[u8] = &[0x00, 0x06, 0x00, 0x01, 0x00, 0x00, 0x00, 0x02];
+    const EMPTY: &[u8] = &[];
+
+    fn nsec3(
+        owner: &'static [u8],
+        flags: u8,
+        next: &'static [u8; 20],
+        types: &'static [u8],
+    ) -> DnsAnswer<'static, DnsAType<'static>> {
+        DnsAnswer {
+            name: DnsName::new(owner).unwrap(),
+            rdata: DnsAType::NSEC3(Nsec3 {
+                hash_algorithm: 1,
+                flags,
+                iterations: 12,
+                salt: unsafe { Characters::new_unchecked(&[0xaa, 0xbb, 0xcc, 0xdd]) },
+                next_hashed_owner_name: unsafe { Characters::new_unchecked(next) },
+                type_bit_maps: TypeBitMap::new(types).unwrap(),
+            }),
+            cache_flush: false,
+            aclass: DnsAClass::IN,
+            ttl: 3600,
+        }
+    }
+
+    fn name(bytes: &'static [u8]) -> DnsName<'static> {
+        DnsName::new(bytes).unwrap()
+    }
+
+    #[test]
+    fn hash_label() {
+        let param = Nsec3Param {
+            hash_algorithm: 1,
+            flags: 0,
+            iterations: 12,
+            salt: unsafe { Characters::new_unchecked(&[0xaa, 0xbb, 0xcc, 0xdd]) },
+        };
+
+        let hash = param.hash(&name(b"\x07example\x00"), &Sha1::default()).unwrap();
+        assert_eq!(&hash.label(), b"0p9mhaveqvm6t7vbl5lop2u3t2rp3tom");
+        let hash = param.hash(&name(b"\x01A\x07EXAMPLE\x00"), &Sha1::default()).unwrap();
+        assert_eq!(&hash.label(), b"35mthgpgcu1qg68fab165klnsnk3dpvl");
+        let salt = param.salt.bytes();
+        let wildcard = super::hash(&name(b"\x01w\x07example\x00"), true, 1, salt, 12, &Sha1::default()).unwrap();
+        assert_eq!(&wildcard.label(), b"r53bq7cc2uvmubfu5ocmm6pers9tk9en");
+
+        assert_eq!(Nsec3Hash::from_label(b"2T7B4G4VSA5SMI47K61MV5BV1A22BOJR").unwrap(), Nsec3Hash::new(NEXT_2T7B));
+        assert_eq!(Nsec3Hash::new(NEXT_T644).label(), *b"t644ebqk9bibcna874givr6joj62mlhv");
+        assert!(Nsec3Hash::from_label(b"2t7b4g4vsa5smi47k61mv5bv1a22boj").is_err());
+        assert!(Nsec3Hash::from_label(b"2t7b4g4vsa5smi47k61mv5bv1a22bojw").is_err());
+
+        let param = Nsec3Param { hash_algorithm: 2, ..param };
+        assert_eq!(
+            param.hash(&name(b"\x07example\x00"), &Sha1::default()),
+            Err(DnsMessageError::DnsError(DnsError::InvalidDigest)),
+        );
+    }
+
+    #[test]
+    fn name_error() {
+        // RFC 5155 Appendix B.1, the next closer name c.x.w.example is in an
+        // opt-out span.
+        let records = |flags| [
+            nsec3(b"\x200p9mhaveqvm6t7vbl5lop2u3t2rp3tom\x07example\x00", flags, &NEXT_2T7B, APEX),
+            nsec3(b"\x20b4um86eghhds6nea196smvmlo4ors995\x07example\x00", flags, &NEXT_GJEQ, MX_RRSIG),
+            nsec3(b"\x2035mthgpgcu1qg68fab165klnsnk3dpvl\x07example\x00", flags, &NEXT_B4UM, NS_DS_RRSIG),
+        ];
+        let qname = name(b"\x01a\x01c\x01x\x01w\x07example\x00");
+
+        let records_opt_out = records(OPT_OUT);
+        let proof = Nsec3Proof::new(&records_opt_out, Sha1::default());
+        assert_eq!(proof.name_error(&qname), Ok(DnssecStatus::Insecure(DnssecReason::OptOut)));
+
+        let records = records(0);
+        let proof = Nsec3Proof::new(&records, Sha1::default());
+        assert_eq!(proof.name_error(&qname), Ok(DnssecStatus::Secure));
+
+        // The wildcard *.x.w.example is not covered.
+        let proof = Nsec3Proof::new(&records[..2], Sha1::default());
+        assert_eq!(proof.name_error(&qname), Ok(DnssecStatus::Bogus(DnssecReason::NotCovered)));
+
+        // The closest encloser x.w.example is not proven.
+        let proof = Nsec3Proof::new(&records[..1], Sha1::default());
+        assert_eq!(proof.name_error(&qname), Ok(DnssecStatus::Bogus(DnssecReason::NoClosestEncloser)));
+
+        // The name x.w.example exists.
+        let qname = name(b"\x01x\x01w\x07example\x00");
+        let proof = Nsec3Proof::new(&records, Sha1::default());
+        assert_eq!(proof.name_error(&qname), Ok(DnssecStatus::Bogus(DnssecReason::NoClosestEncloser)));
+    }
+
+    #[test]
+    fn no_data() {
+        // RFC 5155 Appendix B.2.
+        let records = [
+            nsec3(b"\x202t7b4g4vsa5smi47k61mv5bv1a22bojr\x07example\x00", OPT_OUT, &NEXT_2VPT, A_RRSIG),
+        ];
+        let proof = Nsec3Proof::new(&records, Sha1::default());
+        let qname = name(b"\x03ns1\x07example\x00");
+        assert_eq!(proof.no_data(&qname, DnsQType::MX), Ok(DnssecStatus::Secure));
+        assert_eq!(proof.no_data(&qname, DnsQType::A), Ok(DnssecStatus::Bogus(DnssecReason::TypeExists)));
+
+        // RFC 5155 Appendix B.2.1, the empty non-terminal y.w.example.
+        let records = [
+            nsec3(b"\x20ji6neoaepv8b5o6k4ev33abha8ht9fgc\x07example\x00", OPT_OUT, &NEXT_K8UD, EMPTY),
+        ];
+        let proof = Nsec3Proof::new(&records, Sha1::default());
+        assert_eq!(proof.no_data(&name(b"\x01y\x01w\x07example\x00"), DnsQType::A), Ok(DnssecStatus::Secure));
+    }
+
+    #[test]
+    fn no_data_opt_out() {
+        // RFC 5155 Appendix B.3, the unsigned delegation c.example.
+        let records = |flags| [
+            nsec3(b"\x200p9mhaveqvm6t7vbl5lop2u3t2rp3tom\x07example\x00", flags, &NEXT_2T7B, APEX),
+            nsec3(b"\x2035mthgpgcu1qg68fab165klnsnk3dpvl\x07example\x00", flags, &NEXT_B4UM, NS_DS_RRSIG),
+        ];
+        let qname = name(b"\x01c\x07example\x00");
+
+        let records_opt_out = records(OPT_OUT);
+        let proof = Nsec3Proof::new(&records_opt_out, Sha1::default());
+        assert_eq!(proof.no_data(&qname, DnsQType::DS), Ok(DnssecStatus::Insecure(DnssecReason::OptOut)));
+
+        let records = records(0);
+        let proof = Nsec3Proof::new(&records, Sha1::default());
+        assert_eq!(proof.no_data(&qname, DnsQType::DS), Ok(DnssecStatus::Bogus(DnssecReason::NotCovered)));
+    }
+
+    #[test]
+    fn wildcard() {
+        // RFC 5155 Appendix B.4, a.z.w.example is answered by *.w.example.
+        let records = [
+            nsec3(b"\x20q04jkcevqvmu85r014c7dkba38o0ji5r\x07example\x00", OPT_OUT, &NEXT_R53B, A_RRSIG),
+        ];
+        let proof = Nsec3Proof::new(&records, Sha1::default());
+        let qname = name(b"\x01a\x01z\x01w\x07example\x00");
+        assert_eq!(proof.wildcard(&qname, 2), Ok(DnssecStatus::Secure));
+        // The next closer name would be a.z.w.example.
+        assert_eq!(proof.wildcard(&qname, 3), Ok(DnssecStatus::Bogus(DnssecReason::NotCovered)));
+        assert_eq!(proof.wildcard(&qname, 4), Ok(DnssecStatus::Bogus(DnssecReason::Labels)));
+    }
+
+    #[test]
+    fn wildcard_no_data() {
+        // RFC 5155 Appendix B.5, *.w.example has no AAAA records.
+        let records = [
+            nsec3(b"\x20k8udemvp1j2f7eg6jebps17vp3n8i58h\x07example\x00", OPT_OUT, &NEXT_KOHA, EMPTY),
+            nsec3(b"\x20q04jkcevqvmu85r014c7dkba38o0ji5r\x07example\x00", OPT_OUT, &NEXT_R53B, A_RRSIG),
+            nsec3(b"\x20r53bq7cc2uvmubfu5ocmm6pers9tk9en\x07example\x00", OPT_OUT, &NEXT_T644, MX_RRSIG),
+        ];
+        let proof = Nsec3Proof::new(&records, Sha1::default());
+        let qname = name(b"\x01a\x01z\x01w\x07example\x00");
+        assert_eq!(proof.no_data(&qname, DnsQType::AAAA), Ok(DnssecStatus::Secure));
+        assert_eq!(proof.no_data(&qname, DnsQType::MX), Ok(DnssecStatus::Bogus(DnssecReason::TypeExists)));
+
+        let proof = Nsec3Proof::new(&records[..2], Sha1::default());
+        assert_eq!(proof.no_data(&qname, DnsQType::AAAA), Ok(DnssecStatus::Bogus(DnssecReason::MissingDenial)));
+    }
+
+    #[test]
+    fn unusable() {
+        let qname = name(b"\x03ns1\x07example\x00");
+        let proof = Nsec3Proof::new(&[], Sha1::default());
+        assert_eq!(proof.no_data(&qname, DnsQType::MX), Ok(DnssecStatus::Bogus(DnssecReason::MissingDenial)));
+
+        let mut records = [
+            nsec3(b"\x202t7b4g4vsa5smi47k61mv5bv1a22bojr\x07example\x00", OPT_OUT, &NEXT_2VPT, A_RRSIG),
+        ];
+        if let DnsAType::NSEC3(rdata) = &mut records[0].rdata {
+            rdata.iterations = 500;
+        }
+        let proof = Nsec3Proof::new(&records, Sha1::default());
+        assert_eq!(proof.no_data(&qname, DnsQType::MX), Ok(DnssecStatus::Insecure(DnssecReason::Iterations)));
+
+        if let DnsAType::NSEC3(rdata) = &mut records[0].rdata {
+            rdata.hash_algorithm = 2;
+        }
+        let proof = Nsec3Proof::new(&records, Sha1::default());
+        assert_eq!(proof.no_data(&qname, DnsQType::MX), Ok(DnssecStatus::Insecure(DnssecReason::UnsupportedAlgorithm)));
+    }
+}
@@ -0,0 +1,261 @@
+use ::ring::signature::{
+    self,
+    EcdsaVerificationAlgorithm,
+    RsaPublicKeyComponents,
+    UnparsedPublicKey,
+};
+
+use crate::dnssec::{DigestHasher, DnssecAlgorithm, DnssecVerifier};
+
+/// A [`DnssecVerifier`] backed by [ring](https://docs.rs/ring), supporting
+/// RSA/SHA-256, ECDSA P-256/SHA-256, ECDSA P-384/SHA-384 and Ed25519.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct RingVerifier;
+
+impl DnssecVerifier for RingVerifier {
+    #[inline]
+    fn supports(&self, algorithm: DnssecAlgorithm) -> bool {
+        matches!(
+            algorithm,
+            DnssecAlgorithm::RsaSha256
+                | DnssecAlgorithm::EcdsaP256Sha256
+                | DnssecAlgorithm::EcdsaP384Sha384
+                | DnssecAlgorithm::Ed25519
+        )
+    }
+
+    fn verify(
+        &self,
+        algorithm: DnssecAlgorithm,
+        public_key: &[u8],
+        data: &[u8],
+        signature: &[u8],
+    ) -> bool {
+        match algorithm {
+            DnssecAlgorithm::RsaSha256 => verify_rsa(public_key, data, signature),
+            DnssecAlgorithm::EcdsaP256Sha256 => {
+                verify_ecdsa::<65>(&signature::ECDSA_P256_SHA256_FIXED, public_key, data, signature)
+            }
+            DnssecAlgorithm::EcdsaP384Sha384 => {
+                verify_ecdsa::<97>(&signature::ECDSA_P384_SHA384_FIXED, public_key, data, signature)
+            }
+            DnssecAlgorithm::Ed25519 => UnparsedPublicKey::new(&signature::ED25519, public_key)
+                .verify(data, signature)
+                .is_ok(),
+            _ => false,
+        }
+    }
+}
+
+/// A [`DigestHasher`] for DS records, e.g. with
+/// [`SHA1_FOR_LEGACY_USE_ONLY`](::ring::digest::SHA1_FOR_LEGACY_USE_ONLY),
+/// [`SHA256`](::ring::digest::SHA256) or [`SHA384`](::ring::digest::SHA384).
+impl DigestHasher for ::ring::digest::Context {
+    type Digest = ::ring::digest::Digest;
+
+    #[inline]
+    fn update(&mut self, data: &[u8]) {
+        ::ring::digest::Context::update(self, data);
+    }
+
+    #[inline]
+    fn finalize(self) -> Self::Digest {
+        self.finish()
+    }
+}
+
+// The public key is the length of the exponent, the exponent and the
+// modulus, according to RFC 3110 Section 2. Keys of 1024 bits are still in
+// use in DNSSEC.
+fn verify_rsa(public_key: &[u8], data: &[u8], signature: &[u8]) -> bool {
+    let (exponent_len, rest) = match public_key {
+        [0, a, b, rest @ ..] => (u16::from_be_bytes([*a, *b]) as usize, rest),
+        [a, rest @ ..] => (*a as usize, rest),
+        [] => return false,
+    };
+    if exponent_len > rest.len() {
+        return false;
+    }
+
+    let (e, n) = rest.split_at(exponent_len);
+    RsaPublicKeyComponents { n: strip_zeros(n), e: strip_zeros(e) }
+        .verify(&signature::RSA_PKCS1_1024_8192_SHA256_FOR_LEGACY_USE_ONLY, data, signature)
+        .is_ok()
+}
+
+// The public key is the coordinates of the point, according to RFC 6605
+// Section 4, which ring expects as an uncompressed point.
+fn verify_ecdsa<const N: usize>(
+    algorithm: &'static EcdsaVerificationAlgorithm,
+    public_key: &[u8],
+    data: &[u8],
+    signature: &[u8],
+) -> bool {
+    if public_key.len() != N - 1 {
+        return false;
+    }
+
+    let mut point = [0x04; N];
+    point[1..].copy_from_slice(public_key);
+    UnparsedPublicKey::new(algorithm, &point)
+        .verify(data, signature)
+        .is_ok()
+}
+
+#[inline]
+fn strip_zeros(bytes: &[u8]) -> &[u8] {
+    let zeros = bytes.iter().take_while(|&&byte| byte == 0).count();
+
+    &bytes[zeros..]
+}
+
+#[cfg(test)]
+mod test {
+    use arrayvec::ArrayVec;
+    use ::ring::digest::{Context, SHA1_FOR_LEGACY_USE_ONLY};
+    use ::ring::rand::SystemRandom;
+    use ::ring::signature::{EcdsaKeyPair, KeyPair};
+    use crate::answer::{DnsAClass, DnsAnswer};
+    use crate::dnssec::{DnssecReason, DnssecStatus, DnssecValidator, DsDigestType, write_signing_input};
+    use crate::name::DnsName;
+    use crate::rdata::{DnsAType, DnsKey, Ds, Mx, RawBytes, RRSig};
+    use super::*;
+
+    // The Ed25519 example of RFC 8080 Section 6.1.
+    const PUBLIC_KEY: [u8; 32] = [
+        0x97, 0x4d, 0x96, 0xa2, 0x2d, 0x22, 0x4b, 0xc0, 0x1a, 0xdb, 0x91, 0x50, 0x91, 0x47, 0x7d, 0x44,
+        0xcc, 0xd9, 0x1c, 0x9a, 0x41, 0xa1, 0x14, 0x30, 0x01, 0x01, 0x17, 0xd5, 0x2c, 0x59, 0x24, 0x0e,
+    ];
+    const SIGNATURE: [u8; 64] = [
+        0xa0, 0xbf, 0x64, 0xac, 0x9b, 0xa7, 0xef, 0x17, 0xc1, 0x38, 0x85, 0x9c, 0x18, 0x78, 0xbb, 0x99,
+        0xa8, 0x39, 0xfe, 0x17, 0x59, 0xac, 0xa5, 0xb0, 0xd7, 0x98, 0xcf, 0x1a, 0xb1, 0xe9, 0x8d, 0x07,
+        0x91, 0x02, 0xf4, 0xdd, 0xb3, 0x36, 0x8f, 0x0f, 0xe4, 0x0b, 0xb3, 0x77, 0xf1, 0xf0, 0x0e, 0x0c,
+        0xdd, 0xed, 0xb7, 0x99, 0x16, 0x7d, 0x56, 0xb6, 0xe9, 0x32, 0x78, 0x30, 0x72, 0xba, 0x8d, 0x02,
+    ];
+
+    fn zone() -> DnsName<'static> {
+        DnsName::new(b"\x07example\x03com\x00").unwrap()
+    }
+
+    fn rrset() -> [DnsAnswer<'static, DnsAType<'static>>; 1] {
+        [DnsAnswer {
+            name: zone(),
+            rdata: DnsAType::MX(Mx {
+                preference: 10,
+                exchange: DnsName::new(b"\x04mail\x07example\x03com\x00").unwrap(),
+            }),
+            cache_flush: false,
+            aclass: DnsAClass::IN,
+            ttl: 3600,
+        }]
+    }
+
+    fn rrsig(algorithm: DnssecAlgorithm, key_tag: u16, signature: &[u8]) -> RRSig<'_> {
+        RRSig {
+            type_covered: 15,
+            algorithm: algorithm.id(),
+            labels: 2,
+            original_ttl: 3600,
+            signature_expiration: 1440021600,
+            signature_inception: 1438207200,
+            key_tag,
+            signer_name: zone(),
+            signature: RawBytes::new(signature),
+        }
+    }
+
+    #[test]
+    fn ed25519() {
+        let keys = [DnsKey {
+            flags: 257,
+            protocol: 3,
+            algorithm: 15,
+            public_key: RawBytes::new(&PUBLIC_KEY),
+        }];
+        let validator = DnssecValidator::new(zone(), &keys, RingVerifier);
+        let mut buffer = ArrayVec::<u8, 256>::new();
+
+        let rrsig = rrsig(DnssecAlgorithm::Ed25519, 3613, &SIGNATURE);
+        assert_eq!(
+            validator.validate(&rrset(), &[rrsig], 1439000000, &mut buffer).unwrap(),
+            DnssecStatus::Secure,
+        );
+
+        let mut signature = SIGNATURE;
+        signature[63] ^= 1;
+        let rrsig = RRSig { signature: RawBytes::new(&signature), ..rrsig };
+        assert_eq!(
+            validator.validate(&rrset(), &[rrsig], 1439000000, &mut buffer).unwrap(),
+            DnssecStatus::Bogus(DnssecReason::InvalidSignature),
+        );
+    }
+
+    #[test]
+    fn ecdsa_p256() {
+        let random = SystemRandom::new();
+        let algorithm = &signature::ECDSA_P256_SHA256_FIXED_SIGNING;
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(algorithm, &random).unwrap();
+        let key_pair = EcdsaKeyPair::from_pkcs8(algorithm, pkcs8.as_ref(), &random).unwrap();
+        // The DNSKEY record holds the point without the uncompressed prefix.
+        let keys = [DnsKey {
+            flags: 256,
+            protocol: 3,
+            algorithm: DnssecAlgorithm::EcdsaP256Sha256.id(),
+            public_key: RawBytes::new(&key_pair.public_key().as_ref()[1..]),
+        }];
+        let key_tag = keys[0].key_tag();
+
+        let mut data = ArrayVec::<u8, 256>::new();
+        write_signing_input(&rrsig(DnssecAlgorithm::EcdsaP256Sha256, key_tag, &[]), &rrset(), &mut data).unwrap();
+        let signature = key_pair.sign(&random, &data).unwrap();
+
+        let validator = DnssecValidator::new(zone(), &keys, RingVerifier);
+        let rrsig = rrsig(DnssecAlgorithm::EcdsaP256Sha256, key_tag, signature.as_ref());
+        assert_eq!(
+            validator.validate(&rrset(), &[rrsig], 1439000000, &mut data).unwrap(),
+            DnssecStatus::Secure,
+        );
+    }
+
+    #[test]
+    fn unsupported() {
+        assert!(!RingVerifier.supports(DnssecAlgorithm::RsaSha1));
+        assert!(!RingVerifier.verify(DnssecAlgorithm::RsaSha256, &[], &[], &[]));
+        assert!(!RingVerifier.verify(DnssecAlgorithm::EcdsaP384Sha384, &[0; 64], &[], &[]));
+    }
+
+    // The DNSKEY of dskey.example.com from RFC 4034 Section 5.4.
+    const DSKEY: [u8; 130] = [
+        0x01, 0x03, 0x9e, 0x8a, 0x24, 0x74, 0x18, 0xe3, 0x18, 0x90, 0x3b, 0x21, 0x5a, 0x84, 0x8a, 0xcf,
+        0xd5, 0xf3, 0x7f, 0x02, 0x6b, 0xd4, 0x06, 0x2d, 0xb2, 0x6c, 0x77, 0x4c, 0x69, 0x09, 0x68, 0xd5,
+        0xd5, 0x6d, 0xf8, 0xbf, 0xda, 0x91, 0xe6, 0xf3, 0x6d, 0x9a, 0x27, 0x98, 0x88, 0xf4, 0x13, 0x33,
+        0x35, 0x7c, 0x5e, 0x60, 0x29, 0x99, 0x0d, 0x10, 0xfd, 0xf5, 0x66, 0x30, 0x62, 0xa5, 0x12, 0x76,
+        0x33, 0x26, 0x98, 0x0a, 0x61, 0x5d, 0xdb, 0xf1, 0x7a, 0x05, 0xdd, 0xfc, 0xce, 0x7e, 0x5f, 0xb3,
+        0xab, 0xcc, 0xa0, 0x5a, 0x31, 0xb0, 0x95, 0x74, 0x52, 0xd4, 0x52, 0x1e, 0x83, 0x87, 0x07, 0x89,
+        0x06, 0x31, 0x15, 0xbf, 0x97, 0xf6, 0xc3, 0x08, 0xcc, 0xf5, 0x7c, 0xdc, 0x9c, 0xe7, 0xfe, 0x10,
+        0xf6, 0xed, 0x1b, 0xd0, 0xcc, 0x06, 0x60, 0x03, 0x8c, 0x50, 0xdc, 0xdb, 0x0f, 0xeb, 0x96, 0x3c,
+        0x2f, 0x17,
+    ];
+
+    #[test]
+    fn ds_sha1() {
+        let key = DnsKey {
+            flags: 256,
+            protocol: 3,
+            algorithm: 5,
+            public_key: RawBytes::new(&DSKEY),
+        };
+        let owner = DnsName::new(b"\x05dskey\x07example\x03com\x00").unwrap();
+        let mut buffer = [0; 20];
+        let hasher = Context::new(&SHA1_FOR_LEGACY_USE_ONLY);
+        let ds = Ds::build(owner, &key, DsDigestType::Sha1, hasher, &mut buffer).unwrap();
+
+        assert_eq!(ds.key_tag, 60485);
+        assert_eq!(ds.algorithm, 5);
+        assert_eq!(ds.digest_type, 1);
+        assert_eq!(ds.digest.bytes(), &[
+            0x2b, 0xb1, 0x83, 0xaf, 0x5f, 0x22, 0x58, 0x81, 0x79, 0xa5,
+            0x3b, 0x0a, 0x98, 0x63, 0x1f, 0xad, 0x1a, 0x29, 0x21, 0x18,
+        ]);
+    }
+}
@@ -0,0 +1,436 @@
+use crate::{Buffer, DnsError, DnsMessageError, MutBuffer};
+use crate::answer::DnsAnswer;
+use crate::dnssec::canonical::{label_count, write_signing_input};
+use crate::name::DnsName;
+use crate::rdata::{DnsAType, DnsKey, RRSig};
+
+// The DNSKEY protocol field is always 3.
+const DNSKEY_PROTOCOL: u8 = 3;
+const ZONE_KEY_FLAG: u16 = 0x0100;
+const REVOKE_FLAG: u16 = 0x0080;
+
+/// A DNSSEC algorithm number, as used in the DNSKEY, RRSIG and DS records.
+///
+/// According to the [IANA registry](https://www.iana.org/assignments/dns-sec-alg-numbers/dns-sec-alg-numbers.xhtml).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum DnssecAlgorithm {
+    RsaMd5,
+    Dsa,
+    RsaSha1,
+    DsaNsec3Sha1,
+    RsaSha1Nsec3Sha1,
+    RsaSha256,
+    RsaSha512,
+    EccGost,
+    EcdsaP256Sha256,
+    EcdsaP384Sha384,
+    Ed25519,
+    Ed448,
+    /// An algorithm without a name in this crate, with its number.
+    Unknown(u8),
+}
+
+impl DnssecAlgorithm {
+    /// Create a new algorithm from its number.
+    #[inline(always)]
+    pub fn from_id(id: u8) -> Self {
+        match id {
+            1 => DnssecAlgorithm::RsaMd5,
+            3 => DnssecAlgorithm::Dsa,
+            5 => DnssecAlgorithm::RsaSha1,
+            6 => DnssecAlgorithm::DsaNsec3Sha1,
+            7 => DnssecAlgorithm::RsaSha1Nsec3Sha1,
+            8 => DnssecAlgorithm::RsaSha256,
+            10 => DnssecAlgorithm::RsaSha512,
+            12 => DnssecAlgorithm::EccGost,
+            13 => DnssecAlgorithm::EcdsaP256Sha256,
+            14 => DnssecAlgorithm::EcdsaP384Sha384,
+            15 => DnssecAlgorithm::Ed25519,
+            16 => DnssecAlgorithm::Ed448,
+            id => DnssecAlgorithm::Unknown(id),
+        }
+    }
+
+    /// Get the number of the algorithm.
+    #[inline(always)]
+    pub fn id(&self) -> u8 {
+        match self {
+            DnssecAlgorithm::RsaMd5 => 1,
+            DnssecAlgorithm::Dsa => 3,
+            DnssecAlgorithm::RsaSha1 => 5,
+            DnssecAlgorithm::DsaNsec3Sha1 => 6,
+            DnssecAlgorithm::RsaSha1Nsec3Sha1 => 7,
+            DnssecAlgorithm::RsaSha256 => 8,
+            DnssecAlgorithm::RsaSha512 => 10,
+            DnssecAlgorithm::EccGost => 12,
+            DnssecAlgorithm::EcdsaP256Sha256 => 13,
+            DnssecAlgorithm::EcdsaP384Sha384 => 14,
+            DnssecAlgorithm::Ed25519 => 15,
+            DnssecAlgorithm::Ed448 => 16,
+            DnssecAlgorithm::Unknown(id) => *id,
+        }
+    }
+}
+
+/// The cryptographic backend used to verify signatures.
+pub trait DnssecVerifier {
+    /// Return whether signatures of the algorithm can be verified.
+    fn supports(&self, algorithm: DnssecAlgorithm) -> bool;
+
+    /// Return whether the signature of the data matches the public key. The
+    /// public key and the signature are in the format of the DNSKEY and
+    /// RRSIG records for the algorithm, e.g. RFC 3110 for RSA, RFC 6605 for
+    /// ECDSA and RFC 8080 for Ed25519.
+    fn verify(
+        &self,
+        algorithm: DnssecAlgorithm,
+        public_key: &[u8],
+        data: &[u8],
+        signature: &[u8],
+    ) -> bool;
+}
+
+/// The result of validating an RRset.
+///
+/// According to [RFC 4035 Section 4.3](https://tools.ietf.org/rfc/rfc4035#section-4.3).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum DnssecStatus {
+    /// A signature of the RRset is valid.
+    Secure,
+    /// The RRset can not be validated, e.g. because no algorithm of its
+    /// signatures is supported.
+    Insecure(DnssecReason),
+    /// The RRset should be signed, but no signature is valid.
+    Bogus(DnssecReason),
+}
+
+/// The reason an RRset is not [`DnssecStatus::Secure`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum DnssecReason {
+    /// No signature covers the RRset.
+    MissingSignature,
+    /// The algorithm of the signature is not supported by the verifier.
+    UnsupportedAlgorithm,
+    /// The signer's name is not the zone of the keys or the owner name of
+    /// the RRset is not within the zone.
+    SignerName,
+    /// The signature has more labels than the owner name of the RRset.
+    Labels,
+    /// The signature is expired.
+    Expired,
+    /// The signature is not yet valid.
+    NotYetValid,
+    /// No key of the zone matches the key tag and algorithm of the signature.
+    NoMatchingKey,
+    /// The signature does not match the RRset.
+    InvalidSignature,
+    /// No NSEC or NSEC3 record which can be used to prove the denial.
+    MissingDenial,
+    /// No NSEC3 record proves the closest encloser of the name.
+    NoClosestEncloser,
+    /// A name which must not exist is not covered by any record.
+    NotCovered,
+    /// The type bit map of the matching record contains the type.
+    TypeExists,
+    /// The name may be an unsigned delegation in an opt-out span.
+    OptOut,
+    /// The NSEC3 records use more iterations than are accepted.
+    Iterations,
+}
+
+/// A validator of the RRsets of a zone, with the DNSKEY records of the zone.
+///
+/// According to [RFC 4035 Section 5.3](https://tools.ietf.org/rfc/rfc4035#section-5.3).
+pub struct DnssecValidator<'a, V> {
+    zone: DnsName<'a>,
+    keys: &'a [DnsKey<'a>],
+    verifier: V,
+}
+
+impl<'a, V: DnssecVerifier> DnssecValidator<'a, V> {
+    /// Create a new validator for the zone with the given name and keys.
+    /// Only zone keys which are not revoked are used.
+    #[inline(always)]
+    pub fn new(zone: DnsName<'a>, keys: &'a [DnsKey<'a>], verifier: V) -> Self {
+        Self { zone, keys, verifier }
+    }
+
+    /// Validate the RRset with the given signatures. Signatures which do not
+    /// cover the type of the RRset are ignored. The RRset is secure if any
+    /// signature is valid, bogus for the reason of the first invalid signature
+    /// and insecure if no algorithm of the signatures is supported.
+    /// The buffer is used to write the signed data and restored afterwards.
+    pub fn validate<B: MutBuffer + Buffer>(
+        &self,
+        rrset: &[DnsAnswer<DnsAType>],
+        rrsigs: &[RRSig],
+        now: u32,
+        buffer: &mut B,
+    ) -> Result<DnssecStatus, DnsMessageError> {
+        let first = rrset.first()
+            .ok_or(DnsMessageError::DnsError(DnsError::InvalidRRSet))?;
+
+        let mut insecure = None;
+        let mut bogus = None;
+        for rrsig in rrsigs.iter().filter(|rrsig| rrsig.type_covered == first.rdata.id()) {
+            match self.validate_rrsig(rrset, rrsig, now, buffer)? {
+                DnssecStatus::Secure => return Ok(DnssecStatus::Secure),
+                DnssecStatus::Insecure(reason) => insecure = insecure.or(Some(reason)),
+                DnssecStatus::Bogus(reason) => bogus = bogus.or(Some(reason)),
+            }
+        }
+
+        if let Some(reason) = bogus {
+            Ok(DnssecStatus::Bogus(reason))
+        } else if let Some(reason) = insecure {
+            Ok(DnssecStatus::Insecure(reason))
+        } else {
+            Ok(DnssecStatus::Bogus(DnssecReason::MissingSignature))
+        }
+    }
+
+    /// Validate the RRset with a single signature. The signature must cover
+    /// the type of the RRset.
+    pub fn validate_rrsig<B: MutBuffer + Buffer>(
+        &self,
+        rrset: &[DnsAnswer<DnsAType>],
+        rrsig: &RRSig,
+        now: u32,
+        buffer: &mut B,
+    ) -> Result<DnssecStatus, DnsMessageError> {
+        let first = rrset.first()
+            .ok_or(DnsMessageError::DnsError(DnsError::InvalidRRSet))?;
+        let algorithm = DnssecAlgorithm::from_id(rrsig.algorithm);
+
+        if !self.verifier.supports(algorithm) {
+            return Ok(DnssecStatus::Insecure(DnssecReason::UnsupportedAlgorithm));
+        }
+        if rrsig.signer_name != self.zone || !first.name.is_subdomain_of(&self.zone) {
+            return Ok(DnssecStatus::Bogus(DnssecReason::SignerName));
+        }
+        if rrsig.labels > label_count(&first.name)? {
+            return Ok(DnssecStatus::Bogus(DnssecReason::Labels));
+        }
+        // The times are compared using serial number arithmetic.
+        if (now.wrapping_sub(rrsig.signature_expiration) as i32) > 0 {
+            return Ok(DnssecStatus::Bogus(DnssecReason::Expired));
+        }
+        if (rrsig.signature_inception.wrapping_sub(now) as i32) > 0 {
+            return Ok(DnssecStatus::Bogus(DnssecReason::NotYetValid));
+        }
+
+        let mut keys = self.keys.iter()
+            .filter(|key| key.protocol == DNSKEY_PROTOCOL
+                && key.flags & ZONE_KEY_FLAG != 0
+                && key.flags & REVOKE_FLAG == 0
+                && key.algorithm == rrsig.algorithm
+                && key.key_tag() == rrsig.key_tag)
+            .peekable();
+        if keys.peek().is_none() {
+            return Ok(DnssecStatus::Bogus(DnssecReason::NoMatchingKey));
+        }
+
+        let start = buffer.len();
+        let valid = write_signing_input(rrsig, rrset, buffer).and_then(|length| {
+            let data = buffer.read_bytes_at(start, length)?;
+            // Keys may share a key tag, any of them may have created the
+            // signature.
+            Ok(keys.any(|key| self.verifier.verify(
+                algorithm,
+                key.public_key.bytes(),
+                data,
+                rrsig.signature.bytes(),
+            )))
+        });
+        // The buffer is restored even if the signed data did not fit.
+        buffer.truncate(start)?;
+
+        if valid? {
+            Ok(DnssecStatus::Secure)
+        } else {
+            Ok(DnssecStatus::Bogus(DnssecReason::InvalidSignature))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use arrayvec::ArrayVec;
+    use crate::BufferError;
+    use crate::answer::DnsAClass;
+    use crate::question::DnsQType;
+    use crate::rdata::{A, RawBytes};
+    use super::*;
+
+    const INCEPTION: u32 = 1703824768;
+    const EXPIRATION: u32 = INCEPTION + 86400;
+
+    // Not a signature, but sensitive to every byte of the signed data and
+    // the public key.
+    struct TestVerifier;
+
+    impl TestVerifier {
+        fn sign(public_key: &[u8], data: &[u8]) -> [u8; 8] {
+            let mut signature = [0u8; 8];
+            for (i, &byte) in public_key.iter().chain(data).enumerate() {
+                signature[i % 8] = signature[i % 8].wrapping_mul(31).wrapping_add(byte);
+            }
+
+            signature
+        }
+    }
+
+    impl DnssecVerifier for TestVerifier {
+        fn supports(&self, algorithm: DnssecAlgorithm) -> bool {
+            algorithm == DnssecAlgorithm::Ed25519
+        }
+
+        fn verify(&self, _: DnssecAlgorithm, public_key: &[u8], data: &[u8], signature: &[u8]) -> bool {
+            Self::sign(public_key, data) == signature
+        }
+    }
+
+    const KEYS: [DnsKey; 2] = [
+        DnsKey { flags: 257, protocol: 3, algorithm: 15, public_key: RawBytes::new(&[1; 32]) },
+        DnsKey { flags: 256, protocol: 3, algorithm: 15, public_key: RawBytes::new(&[2; 32]) },
+    ];
+
+    fn zone() -> DnsName<'static> {
+        DnsName::new(b"\x07example\x00").unwrap()
+    }
+
+    fn rrset() -> [DnsAnswer<'static, DnsAType<'static>>; 2] {
+        let a = |address| DnsAnswer {
+            name: DnsName::new(b"\x03www\x07example\x00").unwrap(),
+            rdata: DnsAType::A(A { address }),
+            cache_flush: false,
+            aclass: DnsAClass::IN,
+            ttl: 3600,
+        };
+
+        [a([192, 0, 2, 1]), a([192, 0, 2, 2])]
+    }
+
+    // Sign the RRset with the key, the signature is written to the storage.
+    fn rrsig<'a>(key: &DnsKey, storage: &'a mut [u8; 8]) -> RRSig<'a> {
+        let rrsig = RRSig {
+            type_covered: DnsQType::A.id(),
+            algorithm: key.algorithm,
+            labels: 2,
+            original_ttl: 3600,
+            signature_expiration: EXPIRATION,
+            signature_inception: INCEPTION,
+            key_tag: key.key_tag(),
+            signer_name: DnsName::new(b"\x07EXAMPLE\x00").unwrap(),
+            signature: RawBytes::new(&[]),
+        };
+
+        let mut data = ArrayVec::<u8, 256>::new();
+        write_signing_input(&rrsig, &rrset(), &mut data).unwrap();
+        *storage = TestVerifier::sign(key.public_key.bytes(), &data);
+
+        RRSig { signature: RawBytes::new(storage), ..rrsig }
+    }
+
+    fn validate(rrsigs: &[RRSig], now: u32) -> DnssecStatus {
+        let validator = DnssecValidator::new(zone(), &KEYS, TestVerifier);
+        let mut buffer = ArrayVec::<u8, 256>::new();
+        buffer.push(0xFF);
+        let status = validator.validate(&rrset(), rrsigs, now, &mut buffer).unwrap();
+        assert_eq!(buffer.as_slice(), &[0xFF]);
+
+        status
+    }
+
+    #[test]
+    fn secure() {
+        let mut storage = [0; 8];
+        let rrsig = rrsig(&KEYS[1], &mut storage);
+
+        assert_eq!(validate(&[rrsig], INCEPTION), DnssecStatus::Secure);
+        assert_eq!(validate(&[rrsig], EXPIRATION), DnssecStatus::Secure);
+    }
+
+    #[test]
+    fn time() {
+        let mut storage = [0; 8];
+        let rrsig = rrsig(&KEYS[1], &mut storage);
+
+        assert_eq!(validate(&[rrsig], EXPIRATION + 1), DnssecStatus::Bogus(DnssecReason::Expired));
+        assert_eq!(validate(&[rrsig], INCEPTION - 1), DnssecStatus::Bogus(DnssecReason::NotYetValid));
+    }
+
+    #[test]
+    fn bogus() {
+        let mut storage = [0; 8];
+        let rrsig = rrsig(&KEYS[1], &mut storage);
+
+        let mut signature = [0; 8];
+        signature.copy_from_slice(rrsig.signature.bytes());
+        signature[0] ^= 1;
+        let tampered = RRSig { signature: RawBytes::new(&signature), ..rrsig };
+        assert_eq!(validate(&[tampered], INCEPTION), DnssecStatus::Bogus(DnssecReason::InvalidSignature));
+
+        let wrong_key = RRSig { key_tag: rrsig.key_tag + 1, ..rrsig };
+        assert_eq!(validate(&[wrong_key], INCEPTION), DnssecStatus::Bogus(DnssecReason::NoMatchingKey));
+
+        let signer_name = RRSig { signer_name: DnsName::new(b"\x03com\x00").unwrap(), ..rrsig };
+        assert_eq!(validate(&[signer_name], INCEPTION), DnssecStatus::Bogus(DnssecReason::SignerName));
+
+        let labels = RRSig { labels: 3, ..rrsig };
+        assert_eq!(validate(&[labels], INCEPTION), DnssecStatus::Bogus(DnssecReason::Labels));
+
+        assert_eq!(validate(&[], INCEPTION), DnssecStatus::Bogus(DnssecReason::MissingSignature));
+        let other_type = RRSig { type_covered: DnsQType::AAAA.id(), ..rrsig };
+        assert_eq!(validate(&[other_type], INCEPTION), DnssecStatus::Bogus(DnssecReason::MissingSignature));
+    }
+
+    #[test]
+    fn insecure() {
+        let mut storage = [0; 8];
+        let rrsig = rrsig(&KEYS[1], &mut storage);
+        let unsupported = RRSig { algorithm: DnssecAlgorithm::RsaSha256.id(), ..rrsig };
+
+        assert_eq!(validate(&[unsupported], INCEPTION), DnssecStatus::Insecure(DnssecReason::UnsupportedAlgorithm));
+    }
+
+    #[test]
+    fn any_signature() {
+        let mut storage = [0; 8];
+        let rrsig = rrsig(&KEYS[1], &mut storage);
+        let unsupported = RRSig { algorithm: DnssecAlgorithm::RsaSha256.id(), ..rrsig };
+        let expired = RRSig { signature_expiration: INCEPTION, ..rrsig };
+
+        assert_eq!(validate(&[unsupported, expired, rrsig], EXPIRATION), DnssecStatus::Secure);
+        assert_eq!(validate(&[unsupported, expired], EXPIRATION), DnssecStatus::Bogus(DnssecReason::Expired));
+    }
+
+    #[test]
+    fn buffer_too_small() {
+        let mut storage = [0; 8];
+        let rrsig = rrsig(&KEYS[1], &mut storage);
+        let validator = DnssecValidator::new(zone(), &KEYS, TestVerifier);
+        let mut buffer = ArrayVec::<u8, 32>::new();
+        buffer.push(0xFF);
+
+        assert_eq!(
+            validator.validate(&rrset(), &[rrsig], INCEPTION, &mut buffer),
+            Err(DnsMessageError::BufferError(BufferError::LengthOutOfBounds)),
+        );
+        assert_eq!(buffer.as_slice(), &[0xFF]);
+    }
+
+    #[test]
+    fn revoked_key() {
+        let keys = [DnsKey { flags: KEYS[1].flags | REVOKE_FLAG, ..KEYS[1] }];
+        let mut storage = [0; 8];
+        let rrsig = rrsig(&keys[0], &mut storage);
+        let validator = DnssecValidator::new(zone(), &keys, TestVerifier);
+        let mut buffer = ArrayVec::<u8, 256>::new();
+
+        assert_eq!(
+            validator.validate(&rrset(), &[rrsig], INCEPTION, &mut buffer).unwrap(),
+            DnssecStatus::Bogus(DnssecReason::NoMatchingKey),
+        );
+    }
+}
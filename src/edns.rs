@@ -0,0 +1,540 @@
+use crate::{Buffer, DnsError, DnsMessage, DnsMessageError, MutBuffer};
+use crate::header::{DnsHeader, DnsHeaderResponseCode};
+use crate::name::DnsName;
+use crate::parse::{Parse, ParseBytes};
+use crate::question::DnsQType;
+use crate::rdata::{DnsAType, Opt, OptIterator, RData};
+use crate::write::WriteBytes;
+
+mod option;
+mod client_subnet;
+mod cookie;
+mod extended_error;
+mod padding;
+
+pub use option::{EdnsOption, KeyTags, KeyTagsIterator};
+pub use client_subnet::ClientSubnet;
+pub use cookie::{Cookie, CookieHasher, CookieStatus, ServerCookie};
+pub use extended_error::{ExtendedDnsError, ExtendedError, ExtendedErrorIterator};
+pub use padding::PaddingPolicy;
+
+const DNSSEC_OK_MASK: u16 = 0b1000_0000_0000_0000;
+
+/// The EDNS(0) view of the OPT pseudo-record in the additionals section.
+/// The OPT record reuses the CLASS field for the requestor's UDP payload size
+/// and the TTL field for the extended response code, the version and the
+/// flags.
+///
+/// According to [RFC 6891 Section 6.1](https://tools.ietf.org/rfc/rfc6891#section-6.1).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Edns<'a> {
+    /// The largest UDP payload size the sender is able to reassemble.
+    pub udp_payload_size: u16,
+    /// The upper 8 bits of the 12 bit extended response code.
+    pub extended_rcode: u8,
+    /// The EDNS version of the sender.
+    pub version: u8,
+    /// The EDNS flags, the most significant bit is the DNSSEC OK bit.
+    pub flags: u16,
+    /// The options of the record.
+    pub options: Opt<'a>,
+}
+
+impl<'a> Edns<'a> {
+    /// Create a new EDNS(0) record with the given UDP payload size, no flags
+    /// and no options.
+    #[inline(always)]
+    pub const fn new(udp_payload_size: u16) -> Self {
+        Self {
+            udp_payload_size,
+            extended_rcode: 0,
+            version: 0,
+            flags: 0,
+            options: Opt::empty(),
+        }
+    }
+
+    /// Whether the DNSSEC OK (DO) bit is set.
+    #[inline(always)]
+    pub fn dnssec_ok(&self) -> bool {
+        self.flags & DNSSEC_OK_MASK != 0
+    }
+
+    /// Set whether the DNSSEC OK (DO) bit is set.
+    #[inline(always)]
+    pub fn set_dnssec_ok(&mut self, dnssec_ok: bool) {
+        if dnssec_ok {
+            self.flags |= DNSSEC_OK_MASK;
+        } else {
+            self.flags &= !DNSSEC_OK_MASK;
+        }
+    }
+
+    /// The full response code, merged from the lower 4 bits in the header
+    /// and the upper 8 bits in this record.
+    #[inline(always)]
+    pub fn response_code(&self, header: &DnsHeader) -> DnsHeaderResponseCode {
+        let lower = u16::from(header.response_code()) & 0b0000_1111;
+
+        DnsHeaderResponseCode::from(((self.extended_rcode as u16) << 4) | lower)
+    }
+
+    /// Set the full response code, the lower 4 bits are written to the header
+    /// and the upper 8 bits to this record.
+    #[inline(always)]
+    pub fn set_response_code(&mut self, header: &mut DnsHeader, response_code: DnsHeaderResponseCode) {
+        let response_code = u16::from(response_code);
+        self.extended_rcode = (response_code >> 4) as u8;
+        header.set_response_code(DnsHeaderResponseCode::from(response_code & 0b0000_1111));
+    }
+
+    /// Return the client subnet option of the record, if there is one.
+    pub fn client_subnet(&self) -> Result<Option<ClientSubnet>, DnsMessageError> {
+        for option in self.iter() {
+            if let EdnsOption::ClientSubnet(client_subnet) = option? {
+                return Ok(Some(client_subnet));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Return the cookie option of the record, if there is one.
+    pub fn cookie(&self) -> Result<Option<Cookie<'a>>, DnsMessageError> {
+        for option in self.iter() {
+            if let EdnsOption::Cookie(cookie) = option? {
+                return Ok(Some(cookie));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Return an iterator over the extended DNS errors of the record.
+    #[inline(always)]
+    pub fn extended_errors(&self) -> ExtendedErrorIterator<'a> {
+        ExtendedErrorIterator {
+            options: self.iter(),
+        }
+    }
+
+    /// Return an iterator over the options of the record.
+    #[inline(always)]
+    pub fn iter(&self) -> OptIterator<'a> {
+        self.options.iter()
+    }
+}
+
+impl<'a> ParseBytes<'a> for Edns<'a> {
+    fn parse_bytes(bytes: &'a [u8], i: &mut usize) -> Result<Self, DnsMessageError> {
+        let name = DnsName::parse(bytes, i)?;
+        if name.iter().next().is_some() {
+            // The owner of an OPT record must be the root domain.
+            return Err(DnsMessageError::DnsError(DnsError::InvalidAdditional));
+        }
+
+        if u16::parse(bytes, i)? != DnsQType::OPT.id() {
+            return Err(DnsMessageError::DnsError(DnsError::InvalidAdditional));
+        }
+
+        let udp_payload_size = u16::parse(bytes, i)?;
+        let extended_rcode = u8::parse(bytes, i)?;
+        let version = u8::parse(bytes, i)?;
+        let flags = u16::parse(bytes, i)?;
+        let options = match RData::parse(bytes, i, DnsQType::OPT)?.into_parsed()? {
+            DnsAType::OPT(options) => options,
+            _ => return Err(DnsMessageError::DnsError(DnsError::InvalidAdditional)),
+        };
+
+        Ok(Self {
+            udp_payload_size,
+            extended_rcode,
+            version,
+            flags,
+            options,
+        })
+    }
+}
+
+impl<'a> WriteBytes for Edns<'a> {
+    fn write<
+        const PTR_STORAGE: usize,
+        const DNS_SECTION: usize,
+        B: MutBuffer + Buffer,
+    >(&self, message: &mut DnsMessage<PTR_STORAGE, DNS_SECTION, B>) -> Result<usize, DnsMessageError> {
+        let options_len = self.options.bytes().len();
+        if options_len > u16::MAX as usize {
+            return Err(DnsMessageError::DnsError(DnsError::RDataLongerThanMessage));
+        }
+
+        let mut bytes = 0;
+        // The owner is always the root domain.
+        bytes += 0u8.write(message)?;
+        bytes += DnsQType::OPT.id().write(message)?;
+        bytes += self.udp_payload_size.write(message)?;
+        bytes += self.extended_rcode.write(message)?;
+        bytes += self.version.write(message)?;
+        bytes += self.flags.write(message)?;
+        bytes += (options_len as u16).write(message)?;
+        bytes += self.options.write(message)?;
+
+        Ok(bytes)
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+#[cfg(test)]
+mod test {
+    use arrayvec::ArrayVec;
+    use crate::BufferError;
+    use crate::header::DnsHeaderKind;
+    use crate::question::{DnsQClass, DnsQuestion};
+    use super::*;
+
+    const QUERY: [u8; 52] = [
+        0x8f, 0x1e, // ID
+        0x01, 0x20, // Flags
+        0x00, 0x01, // Question count
+        0x00, 0x00, // Answer count
+        0x00, 0x00, // Authority count
+        0x00, 0x01, // Additional count
+        0x07, b'e', b'x', b'a', b'm', b'p', b'l', b'e', // Name
+        0x03, b'c', b'o', b'm', // Name
+        0x00, // Name
+        0x00, 0x01, // Type
+        0x00, 0x01, // Class
+        0x00, // Name
+        0x00, 0x29, // Type
+        0x04, 0xd0, // UDP payload size
+        0x00, // Extended response code
+        0x00, // Version
+        0x80, 0x00, // Flags
+        0x00, 0x0c, // Data length
+        0x00, 0x0a, // Option code
+        0x00, 0x08, // Option length
+        0xd5, 0xa7, 0xe3, 0x00, 0x4d, 0x79, 0x05, 0x1e, // Option data
+    ];
+
+    #[test]
+    fn parse_query() {
+        let message: DnsMessage<8, 0, _> = DnsMessage::new(QUERY.as_slice()).unwrap();
+        let message = message.questions().complete().unwrap();
+        let message = message.answers().complete().unwrap();
+        let message = message.name_servers().complete().unwrap();
+        let additionals = message.additionals();
+        let edns = additionals.edns().unwrap().unwrap();
+        assert_eq!(edns.udp_payload_size, 1232);
+        assert_eq!(edns.extended_rcode, 0);
+        assert_eq!(edns.version, 0);
+        assert!(edns.dnssec_ok());
+        let mut options = edns.iter();
+        assert_eq!(
+            options.next(),
+            Some(Ok(EdnsOption::Cookie(Cookie {
+                client: [0xd5, 0xa7, 0xe3, 0x00, 0x4d, 0x79, 0x05, 0x1e],
+                server: &[],
+            })))
+        );
+        assert!(options.next().is_none());
+        additionals.complete().unwrap();
+    }
+
+    #[test]
+    fn write_query() {
+        let mut message: DnsMessage<8, 0, _> = DnsMessage::new_mut(ArrayVec::<u8, 512>::new()).unwrap();
+        message.header_mut().unwrap().set_id(0x8f1e);
+        message.header_mut().unwrap().set_recursion_desired(true);
+        let mut questions = message.questions();
+        questions.append(DnsQuestion {
+            name: DnsName::new(b"\x07example\x03com\x00").unwrap(),
+            qtype: DnsQType::A,
+            unicast_response: false,
+            qclass: DnsQClass::IN,
+        }).unwrap();
+        let message = questions.complete().unwrap();
+        let message = message.answers().complete().unwrap();
+        let message = message.name_servers().complete().unwrap();
+        let mut additionals = message.additionals();
+        let mut edns = Edns::new(1232);
+        edns.set_dnssec_ok(true);
+        additionals.append_edns(edns).unwrap();
+        additionals.append_edns_option(EdnsOption::Cookie(Cookie {
+            client: [0xd5, 0xa7, 0xe3, 0x00, 0x4d, 0x79, 0x05, 0x1e],
+            server: &[],
+        })).unwrap();
+        let message = additionals.complete().unwrap();
+        let mut buffer = message.abort().unwrap();
+        // The AD bit is not exposed by the header.
+        buffer[3] |= 0x20;
+
+        assert_eq!(buffer.as_slice(), QUERY.as_slice());
+    }
+
+    #[test]
+    fn write_raw_options() {
+        let mut message: DnsMessage<8, 3, _> = DnsMessage::new_mut(ArrayVec::<u8, 512>::new()).unwrap();
+        message.header_mut().unwrap().set_id(0x8f1e);
+        message.header_mut().unwrap().set_recursion_desired(true);
+        let mut additionals = message.additionals();
+        let mut edns = Edns::new(1232);
+        edns.set_dnssec_ok(true);
+        edns.options = Opt::new(&QUERY[40..]).unwrap();
+        additionals.append_edns(edns).unwrap();
+        let buffer = additionals.complete().unwrap().abort().unwrap();
+
+        assert_eq!(&buffer[12..], &QUERY[29..]);
+    }
+
+    #[cfg(feature = "vec")]
+    #[test]
+    fn options_too_long() {
+        extern crate alloc;
+
+        // Two padding options, one more byte than the record data may hold.
+        let mut options = alloc::vec![0; 65539];
+        options[..4].copy_from_slice(&[0x00, 0x0c, 0xff, 0xfb]);
+        options[65535..].copy_from_slice(&[0x00, 0x0c, 0x00, 0x00]);
+        let mut edns = Edns::new(1232);
+        edns.options = Opt::new(&options).unwrap();
+
+        let message: DnsMessage<8, 3, _> = DnsMessage::new_mut(alloc::vec::Vec::new()).unwrap();
+        let mut additionals = message.additionals();
+        assert_eq!(
+            additionals.append_edns(edns),
+            Err(DnsMessageError::DnsError(DnsError::RDataLongerThanMessage))
+        );
+    }
+
+    #[cfg(feature = "vec")]
+    #[test]
+    fn option_too_long() {
+        extern crate alloc;
+
+        let message: DnsMessage<8, 3, _> = DnsMessage::new_mut(alloc::vec::Vec::new()).unwrap();
+        let mut additionals = message.additionals();
+        additionals.append_edns(Edns::new(1232)).unwrap();
+        additionals.append_edns_option(EdnsOption::Padding(65531)).unwrap();
+        assert_eq!(
+            additionals.append_edns_option(EdnsOption::Padding(0)),
+            Err(DnsMessageError::DnsError(DnsError::RDataLongerThanMessage))
+        );
+
+        let buffer = additionals.complete().unwrap().abort().unwrap();
+        assert_eq!(buffer.len(), 12 + 11 + 65535);
+        assert_eq!(&buffer[21..27], &[0xff, 0xff, 0x00, 0x0c, 0xff, 0xfb]);
+    }
+
+    #[test]
+    fn option_out_of_memory() {
+        let message: DnsMessage<8, 3, _> = DnsMessage::new_mut(ArrayVec::<u8, 32>::new()).unwrap();
+        let mut additionals = message.additionals();
+        additionals.append_edns(Edns::new(1232)).unwrap();
+        assert_eq!(
+            additionals.append_edns_option(EdnsOption::Padding(16)),
+            Err(DnsMessageError::BufferError(BufferError::LengthOutOfBounds))
+        );
+
+        // The partial option is removed.
+        let buffer = additionals.complete().unwrap().abort().unwrap();
+        assert_eq!(buffer.len(), 12 + 11);
+        assert_eq!(&buffer[21..23], &[0x00, 0x00]);
+    }
+
+    #[test]
+    fn option_without_edns() {
+        let message: DnsMessage<8, 3, _> = DnsMessage::new_mut(ArrayVec::<u8, 512>::new()).unwrap();
+        let mut additionals = message.additionals();
+        assert_eq!(
+            additionals.append_edns_option(EdnsOption::Padding(4)),
+            Err(DnsMessageError::DnsError(DnsError::MissingEdnsRecord))
+        );
+    }
+
+    #[test]
+    fn extended_response_code() {
+        let mut message: DnsMessage<8, 0, _> = DnsMessage::new_mut(ArrayVec::<u8, 512>::new()).unwrap();
+        let header = message.header_mut().unwrap();
+        header.set_kind(DnsHeaderKind::Response);
+
+        let mut edns = Edns::new(512);
+        edns.set_response_code(header, DnsHeaderResponseCode::BadCookie);
+        assert_eq!(edns.extended_rcode, 1);
+        assert_eq!(header.response_code(), DnsHeaderResponseCode::ExistentRrSet);
+        assert_eq!(edns.response_code(header), DnsHeaderResponseCode::BadCookie);
+
+        edns.set_response_code(header, DnsHeaderResponseCode::BadOptVersionOrBadSignature);
+        assert_eq!(edns.extended_rcode, 1);
+        assert_eq!(header.response_code(), DnsHeaderResponseCode::NoError);
+        assert_eq!(edns.response_code(header), DnsHeaderResponseCode::BadOptVersionOrBadSignature);
+    }
+
+    #[test]
+    fn relay_client_subnet() {
+        let message: DnsMessage<8, 3, _> = DnsMessage::new_mut(ArrayVec::<u8, 512>::new()).unwrap();
+        let mut additionals = message.additionals();
+        additionals.append_edns(Edns::new(1232)).unwrap();
+        additionals.append_edns_option(EdnsOption::ClientSubnet(
+            ClientSubnet::from_ipv4([192, 0, 2, 129], 32).unwrap()
+        )).unwrap();
+        additionals.append_edns_option(EdnsOption::Padding(2)).unwrap();
+        let incoming = additionals.complete().unwrap().abort().unwrap();
+
+        let message: DnsMessage<8, 3, _> = DnsMessage::new(incoming.as_slice()).unwrap();
+        let additionals = message.additionals();
+        let edns = additionals.edns().unwrap().unwrap();
+        let mut client_subnet = edns.client_subnet().unwrap().unwrap();
+        assert_eq!(client_subnet.address(), &[192, 0, 2, 129]);
+        client_subnet.clamp(24);
+
+        let message: DnsMessage<8, 3, _> = DnsMessage::new_mut(ArrayVec::<u8, 512>::new()).unwrap();
+        let mut relayed = message.additionals();
+        relayed.append_edns_filtered(edns, |option| match option {
+            EdnsOption::ClientSubnet(_) => None,
+            option => Some(option),
+        }).unwrap();
+        relayed.append_edns_option(EdnsOption::ClientSubnet(client_subnet)).unwrap();
+        let outgoing = relayed.complete().unwrap().abort().unwrap();
+
+        assert_eq!(
+            &outgoing[12..],
+            b"\x00\x00\x29\x04\xd0\x00\x00\x00\x00\x00\x11\
+              \x00\x0c\x00\x02\x00\x00\
+              \x00\x08\x00\x07\x00\x01\x18\x00\xc0\x00\x02".as_slice()
+        );
+    }
+
+    #[test]
+    fn relay_invalid_option() {
+        let mut edns = Edns::new(1232);
+        // A client cookie must be 8 bytes long.
+        edns.options = Opt::new(b"\x00\x0c\x00\x00\x00\x0a\x00\x03\x01\x02\x03").unwrap();
+
+        let message: DnsMessage<8, 3, _> = DnsMessage::new_mut(ArrayVec::<u8, 512>::new()).unwrap();
+        let mut relayed = message.additionals();
+        assert!(relayed.append_edns_filtered(edns, Some).is_err());
+        assert_eq!(
+            relayed.append_edns_option(EdnsOption::Padding(0)),
+            Err(DnsMessageError::DnsError(DnsError::MissingEdnsRecord))
+        );
+        let buffer = relayed.complete().unwrap().abort().unwrap();
+        assert_eq!(buffer.len(), 12);
+        assert_eq!(&buffer[10..12], &[0x00, 0x00]);
+    }
+
+    #[test]
+    fn bad_cookie() {
+        let message: DnsMessage<8, 0, _> = DnsMessage::new(QUERY.as_slice()).unwrap();
+        let message = message.questions().complete().unwrap();
+        let message = message.answers().complete().unwrap();
+        let message = message.name_servers().complete().unwrap();
+        let additionals = message.additionals();
+        let edns = additionals.edns().unwrap().unwrap();
+        let cookie = edns.cookie().unwrap().unwrap();
+        assert_eq!(cookie.client, [0xd5, 0xa7, 0xe3, 0x00, 0x4d, 0x79, 0x05, 0x1e]);
+
+        let server = [0x01, 0x00, 0x00, 0x00, 0x5c, 0xf7, 0x9f, 0x11, 0x1f, 0x81, 0x30, 0xc3, 0xee, 0xe2, 0x94, 0x80];
+        let mut message: DnsMessage<8, 3, _> = DnsMessage::new_mut(ArrayVec::<u8, 512>::new()).unwrap();
+        message.header_mut().unwrap().set_kind(DnsHeaderKind::Response);
+        let mut additionals = message.additionals();
+        additionals.append_bad_cookie(Edns::new(1232), Cookie {
+            server: &server,
+            ..cookie
+        }).unwrap();
+        let buffer = additionals.complete().unwrap().abort().unwrap();
+
+        let message: DnsMessage<8, 3, _> = DnsMessage::new(buffer.as_slice()).unwrap();
+        let header = *message.header().unwrap();
+        let additionals = message.additionals();
+        let edns = additionals.edns().unwrap().unwrap();
+        assert_eq!(edns.response_code(&header), DnsHeaderResponseCode::BadCookie);
+        assert_eq!(edns.cookie(), Ok(Some(Cookie {
+            client: cookie.client,
+            server: &server,
+        })));
+    }
+
+    #[test]
+    fn extended_errors() {
+        let mut message: DnsMessage<8, 0, _> = DnsMessage::new_mut(ArrayVec::<u8, 512>::new()).unwrap();
+        message.header_mut().unwrap().set_kind(DnsHeaderKind::Response);
+        message.header_mut().unwrap().set_response_code(DnsHeaderResponseCode::ServerFailure);
+        let message = message.questions().complete().unwrap();
+        let message = message.answers().complete().unwrap();
+        let message = message.name_servers().complete().unwrap();
+        let mut additionals = message.additionals();
+        additionals.append_edns(Edns::new(1232)).unwrap();
+        additionals.append_edns_option(EdnsOption::ExtendedError(ExtendedError {
+            info_code: ExtendedDnsError::DnssecBogus,
+            extra_text: "no valid signature for example.com",
+        })).unwrap();
+        additionals.append_edns_option(EdnsOption::Padding(4)).unwrap();
+        additionals.append_edns_option(EdnsOption::ExtendedError(
+            ExtendedError::new(ExtendedDnsError::RrSigsMissing)
+        )).unwrap();
+        let buffer = additionals.complete().unwrap().abort().unwrap();
+
+        let message: DnsMessage<8, 3, _> = DnsMessage::new(buffer.as_slice()).unwrap();
+        let additionals = message.additionals();
+        let edns = additionals.edns().unwrap().unwrap();
+        let mut errors = edns.extended_errors();
+        assert_eq!(errors.next(), Some(Ok(ExtendedError {
+            info_code: ExtendedDnsError::DnssecBogus,
+            extra_text: "no valid signature for example.com",
+        })));
+        assert_eq!(errors.next(), Some(Ok(ExtendedError::new(ExtendedDnsError::RrSigsMissing))));
+        assert_eq!(errors.next(), None);
+    }
+
+    #[test]
+    fn padding() {
+        let message: DnsMessage<8, 0, _> = DnsMessage::new_mut(ArrayVec::<u8, 512>::new()).unwrap();
+        let mut questions = message.questions();
+        questions.append(DnsQuestion {
+            name: DnsName::new(b"\x07example\x03com\x00").unwrap(),
+            qtype: DnsQType::A,
+            unicast_response: false,
+            qclass: DnsQClass::IN,
+        }).unwrap();
+        let message = questions.complete().unwrap();
+        let message = message.answers().complete().unwrap();
+        let message = message.name_servers().complete().unwrap();
+        let mut additionals = message.additionals();
+        additionals.set_padding(PaddingPolicy::QUERY).unwrap();
+        additionals.append_edns(Edns::new(1232)).unwrap();
+        additionals.append_edns_option(EdnsOption::Nsid(b"")).unwrap();
+        let buffer = additionals.complete().unwrap().abort().unwrap();
+        assert_eq!(buffer.len(), 128);
+
+        let message: DnsMessage<8, 0, _> = DnsMessage::new(buffer.as_slice()).unwrap();
+        let message = message.questions().complete().unwrap();
+        let message = message.answers().complete().unwrap();
+        let message = message.name_servers().complete().unwrap();
+        let additionals = message.additionals();
+        let edns = additionals.edns().unwrap().unwrap();
+        let mut options = edns.iter();
+        // The padding is kept last and resized for the options appended.
+        assert_eq!(options.next(), Some(Ok(EdnsOption::Nsid(b""))));
+        assert_eq!(options.next(), Some(Ok(EdnsOption::Padding(80))));
+        assert!(options.next().is_none());
+    }
+
+    #[test]
+    fn padding_without_edns() {
+        let message: DnsMessage<8, 3, _> = DnsMessage::new_mut(ArrayVec::<u8, 512>::new()).unwrap();
+        let mut additionals = message.additionals();
+        additionals.set_padding(PaddingPolicy::RESPONSE).unwrap();
+        let buffer = additionals.complete().unwrap().abort().unwrap();
+        assert_eq!(buffer.len(), 12);
+    }
+
+    #[test]
+    fn missing() {
+        let mut query = QUERY;
+        query[11] = 0x00; // Additional count
+        let message: DnsMessage<8, 0, _> = DnsMessage::new(&query[..29]).unwrap();
+        let message = message.questions().complete().unwrap();
+        let message = message.answers().complete().unwrap();
+        let message = message.name_servers().complete().unwrap();
+        assert_eq!(message.additionals().edns(), Ok(None));
+    }
+}
@@ -0,0 +1,346 @@
+use crate::{Buffer, DnsError, DnsMessage, DnsMessageError, MutBuffer};
+use crate::edns::option::EdnsOptionParse;
+use crate::parse::Parse;
+use crate::write::WriteBytes;
+
+const FAMILY_IPV4: u16 = 1;
+const FAMILY_IPV6: u16 = 2;
+const MAX_ADDRESS_LENGTH: usize = 16;
+
+/// # Client subnet option
+/// This option is used to convey the network of the original client to
+/// authoritative servers. Only the significant bytes of the address, as given
+/// by the source prefix length, are sent on the wire.
+///
+/// According to [RFC 7871 Section 6](https://tools.ietf.org/rfc/rfc7871#section-6).
+///
+/// # Example
+/// ```
+/// use flex_dns::edns::ClientSubnet;
+///
+/// let client_subnet = ClientSubnet::from_ipv4([192, 0, 2, 129], 24).unwrap();
+/// assert_eq!(client_subnet.address(), &[192, 0, 2]);
+/// assert_eq!(client_subnet.ipv4(), Some([192, 0, 2, 0]));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ClientSubnet {
+    /// The address family, 1 for IPv4 and 2 for IPv6.
+    pub family: u16,
+    /// The number of significant bits of the address in the query.
+    pub source_prefix_length: u8,
+    /// The number of significant bits of the address the response covers.
+    pub scope_prefix_length: u8,
+    /// The address, the bytes after the source prefix length are not sent.
+    pub address: [u8; MAX_ADDRESS_LENGTH],
+}
+
+impl ClientSubnet {
+    /// Create a new client subnet option for an IPv4 address. The address is
+    /// truncated to the source prefix length.
+    #[inline]
+    pub fn from_ipv4(address: [u8; 4], source_prefix_length: u8) -> Result<Self, DnsMessageError> {
+        let mut bytes = [0; MAX_ADDRESS_LENGTH];
+        bytes[..4].copy_from_slice(&address);
+
+        Self::from_family(FAMILY_IPV4, bytes, source_prefix_length)
+    }
+
+    /// Create a new client subnet option for an IPv6 address. The address is
+    /// truncated to the source prefix length.
+    #[inline]
+    pub fn from_ipv6(address: [u8; 16], source_prefix_length: u8) -> Result<Self, DnsMessageError> {
+        Self::from_family(FAMILY_IPV6, address, source_prefix_length)
+    }
+
+    #[inline(always)]
+    fn from_family(
+        family: u16,
+        address: [u8; MAX_ADDRESS_LENGTH],
+        source_prefix_length: u8,
+    ) -> Result<Self, DnsMessageError> {
+        let mut client_subnet = Self {
+            family,
+            source_prefix_length: 0,
+            scope_prefix_length: 0,
+            address,
+        };
+        if source_prefix_length > client_subnet.max_prefix_length()? {
+            return Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption));
+        }
+        client_subnet.source_prefix_length = source_prefix_length;
+        client_subnet.address = truncate(address, source_prefix_length);
+
+        Ok(client_subnet)
+    }
+
+    /// The significant bytes of the address, as sent on the wire.
+    #[inline(always)]
+    pub fn address(&self) -> &[u8] {
+        &self.address[..address_length(self.source_prefix_length).min(MAX_ADDRESS_LENGTH)]
+    }
+
+    /// The address as IPv4 address, if the family is IPv4.
+    #[inline(always)]
+    pub fn ipv4(&self) -> Option<[u8; 4]> {
+        if self.family != FAMILY_IPV4 {
+            return None;
+        }
+
+        let mut address = [0; 4];
+        address.copy_from_slice(&self.address[..4]);
+
+        Some(address)
+    }
+
+    /// The address as IPv6 address, if the family is IPv6.
+    #[inline(always)]
+    pub fn ipv6(&self) -> Option<[u8; 16]> {
+        if self.family != FAMILY_IPV6 {
+            return None;
+        }
+
+        Some(self.address)
+    }
+
+    /// The prefix length the response covers. A scope prefix length shorter
+    /// than the source prefix length means the answer is valid for a larger
+    /// network, a longer one means it is only valid for a smaller network.
+    #[inline(always)]
+    pub fn scope(&self) -> u8 {
+        self.scope_prefix_length
+    }
+
+    /// Reduce the source prefix length to at most the given length and clear
+    /// the address bits after it. Forwarders use this to limit the
+    /// information sent upstream.
+    #[inline]
+    pub fn clamp(&mut self, max_source_prefix_length: u8) {
+        if self.source_prefix_length > max_source_prefix_length {
+            self.source_prefix_length = max_source_prefix_length;
+            self.address = truncate(self.address, max_source_prefix_length);
+        }
+        if self.scope_prefix_length > self.source_prefix_length {
+            self.scope_prefix_length = self.source_prefix_length;
+        }
+    }
+
+    /// Check that the option is valid. The family must be IPv4 or IPv6, the
+    /// prefix lengths must not exceed the address length and all address bits
+    /// after the source prefix length must be zero.
+    ///
+    /// According to [RFC 7871 Section 7.1.1](https://tools.ietf.org/rfc/rfc7871#section-7.1.1).
+    pub fn validate(&self) -> Result<(), DnsMessageError> {
+        let max_prefix_length = self.max_prefix_length()?;
+        if self.source_prefix_length > max_prefix_length
+            || self.scope_prefix_length > max_prefix_length {
+            return Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption));
+        }
+
+        if truncate(self.address, self.source_prefix_length) != self.address {
+            return Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption));
+        }
+
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn max_prefix_length(&self) -> Result<u8, DnsMessageError> {
+        match self.family {
+            FAMILY_IPV4 => Ok(32),
+            FAMILY_IPV6 => Ok(128),
+            _ => Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption)),
+        }
+    }
+}
+
+#[inline(always)]
+fn address_length(prefix_length: u8) -> usize {
+    (prefix_length as usize + 7) >> 3
+}
+
+#[inline(always)]
+fn truncate(mut address: [u8; MAX_ADDRESS_LENGTH], prefix_length: u8) -> [u8; MAX_ADDRESS_LENGTH] {
+    let prefix_length = prefix_length as usize;
+    for (index, byte) in address.iter_mut().enumerate() {
+        let bits = prefix_length.saturating_sub(index * 8);
+        if bits == 0 {
+            *byte = 0;
+        } else if bits < 8 {
+            *byte &= 0xff << (8 - bits);
+        }
+    }
+
+    address
+}
+
+impl<'a> EdnsOptionParse<'a> for ClientSubnet {
+    #[inline]
+    fn parse(data: &'a [u8]) -> Result<Self, DnsMessageError> {
+        let mut i = 0;
+        let family = u16::parse(data, &mut i)
+            .map_err(|_| DnsMessageError::DnsError(DnsError::InvalidEdnsOption))?;
+        let source_prefix_length = u8::parse(data, &mut i)
+            .map_err(|_| DnsMessageError::DnsError(DnsError::InvalidEdnsOption))?;
+        let scope_prefix_length = u8::parse(data, &mut i)
+            .map_err(|_| DnsMessageError::DnsError(DnsError::InvalidEdnsOption))?;
+
+        // The address must be truncated to the source prefix length.
+        let length = data.len() - i;
+        if length != address_length(source_prefix_length) || length > MAX_ADDRESS_LENGTH {
+            return Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption));
+        }
+
+        let mut address = [0; MAX_ADDRESS_LENGTH];
+        address[..length].copy_from_slice(&data[i..]);
+
+        Ok(Self {
+            family,
+            source_prefix_length,
+            scope_prefix_length,
+            address,
+        })
+    }
+}
+
+impl WriteBytes for ClientSubnet {
+    #[inline]
+    fn write<
+        const PTR_STORAGE: usize,
+        const DNS_SECTION: usize,
+        B: MutBuffer + Buffer,
+    >(&self, message: &mut DnsMessage<PTR_STORAGE, DNS_SECTION, B>) -> Result<usize, DnsMessageError> {
+        let mut bytes = 0;
+
+        bytes += self.family.write(message)?;
+        bytes += self.source_prefix_length.write(message)?;
+        bytes += self.scope_prefix_length.write(message)?;
+        bytes += message.write_bytes(self.address())?;
+
+        Ok(bytes)
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+#[cfg(test)]
+mod test {
+    use crate::edns::EdnsOption;
+    use crate::edns::option::test::parse_write_option;
+
+    use super::*;
+
+    #[test]
+    fn parse_write() {
+        parse_write_option(
+            b"\x00\x08\x00\x07\x00\x01\x18\x00\xc0\x00\x02",
+            EdnsOption::ClientSubnet(ClientSubnet {
+                family: 1,
+                source_prefix_length: 24,
+                scope_prefix_length: 0,
+                address: [192, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            }),
+        );
+        parse_write_option(
+            b"\x00\x08\x00\x0b\x00\x02\x38\x30\x20\x01\x0d\xb8\xab\xcd\x12",
+            EdnsOption::ClientSubnet(ClientSubnet {
+                family: 2,
+                source_prefix_length: 56,
+                scope_prefix_length: 48,
+                address: [0x20, 0x01, 0x0d, 0xb8, 0xab, 0xcd, 0x12, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            }),
+        );
+    }
+
+    #[test]
+    fn too_short() {
+        assert_eq!(
+            EdnsOption::parse(8, b"\x00\x01\x18"),
+            Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption))
+        );
+    }
+
+    #[test]
+    fn address_not_truncated() {
+        assert_eq!(
+            EdnsOption::parse(8, b"\x00\x01\x18\x00\xc0\x00\x02\x01"),
+            Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption))
+        );
+    }
+
+    #[test]
+    fn from_ipv4() {
+        let client_subnet = ClientSubnet::from_ipv4([192, 0, 2, 129], 25).unwrap();
+        assert_eq!(client_subnet.address(), &[192, 0, 2, 128]);
+        assert_eq!(client_subnet.ipv4(), Some([192, 0, 2, 128]));
+        assert_eq!(client_subnet.ipv6(), None);
+        assert_eq!(client_subnet.validate(), Ok(()));
+
+        let client_subnet = ClientSubnet::from_ipv4([192, 0, 2, 129], 0).unwrap();
+        assert_eq!(client_subnet.address(), &[]);
+        assert_eq!(client_subnet.ipv4(), Some([0, 0, 0, 0]));
+
+        assert_eq!(
+            ClientSubnet::from_ipv4([192, 0, 2, 129], 33),
+            Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption))
+        );
+    }
+
+    #[test]
+    fn from_ipv6() {
+        let client_subnet = ClientSubnet::from_ipv6(
+            [0x20, 0x01, 0x0d, 0xb8, 0xab, 0xcd, 0x12, 0x34, 0, 0, 0, 0, 0, 0, 0, 1],
+            52,
+        ).unwrap();
+        assert_eq!(client_subnet.address(), &[0x20, 0x01, 0x0d, 0xb8, 0xab, 0xcd, 0x10]);
+        assert_eq!(client_subnet.validate(), Ok(()));
+
+        assert_eq!(
+            ClientSubnet::from_ipv6([0; 16], 129),
+            Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption))
+        );
+    }
+
+    #[test]
+    fn validate() {
+        let client_subnet = match EdnsOption::parse(8, b"\x00\x01\x17\x00\xc0\x00\x03").unwrap() {
+            EdnsOption::ClientSubnet(client_subnet) => client_subnet,
+            _ => panic!("Expected client subnet option"),
+        };
+        // The last bit of the address is outside of the source prefix.
+        assert_eq!(
+            client_subnet.validate(),
+            Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption))
+        );
+
+        let client_subnet = match EdnsOption::parse(8, b"\x00\x03\x00\x00").unwrap() {
+            EdnsOption::ClientSubnet(client_subnet) => client_subnet,
+            _ => panic!("Expected client subnet option"),
+        };
+        assert_eq!(
+            client_subnet.validate(),
+            Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption))
+        );
+
+        let client_subnet = match EdnsOption::parse(8, b"\x00\x01\x18\x21\xc0\x00\x02").unwrap() {
+            EdnsOption::ClientSubnet(client_subnet) => client_subnet,
+            _ => panic!("Expected client subnet option"),
+        };
+        assert_eq!(
+            client_subnet.validate(),
+            Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption))
+        );
+    }
+
+    #[test]
+    fn clamp() {
+        let mut client_subnet = ClientSubnet::from_ipv4([192, 0, 2, 129], 32).unwrap();
+        client_subnet.scope_prefix_length = 32;
+        client_subnet.clamp(24);
+        assert_eq!(client_subnet.source_prefix_length, 24);
+        assert_eq!(client_subnet.scope(), 24);
+        assert_eq!(client_subnet.address(), &[192, 0, 2]);
+        assert_eq!(client_subnet.validate(), Ok(()));
+
+        client_subnet.clamp(28);
+        assert_eq!(client_subnet.source_prefix_length, 24);
+    }
+}
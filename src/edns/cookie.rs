@@ -0,0 +1,398 @@
+use crate::{Buffer, DnsError, DnsMessage, DnsMessageError, MutBuffer};
+use crate::edns::option::EdnsOptionParse;
+use crate::parse::Parse;
+use crate::write::WriteBytes;
+
+const CLIENT_COOKIE_LENGTH: usize = 8;
+const MIN_SERVER_COOKIE_LENGTH: usize = 8;
+const MAX_SERVER_COOKIE_LENGTH: usize = 32;
+const SERVER_COOKIE_LENGTH: usize = 16;
+const SERVER_COOKIE_VERSION: u8 = 1;
+const MAX_ADDRESS_LENGTH: usize = 16;
+// A server cookie is valid for one hour and may be up to five minutes in the
+// future, according to RFC 9018 Section 4.3.
+const MAX_COOKIE_AGE: i32 = 3600;
+const MAX_COOKIE_SKEW: i32 = 300;
+// A server cookie older than half an hour should be replaced.
+const REFRESH_COOKIE_AGE: i32 = 1800;
+
+/// # Cookie option
+/// This option is used to protect clients and servers against off-path
+/// spoofing and amplification attacks.
+///
+/// According to [RFC 7873 Section 4](https://tools.ietf.org/rfc/rfc7873#section-4).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Cookie<'a> {
+    /// The client cookie.
+    pub client: [u8; CLIENT_COOKIE_LENGTH],
+    /// The server cookie, empty if the client does not know one yet.
+    pub server: &'a [u8],
+}
+
+/// A keyed hash used to generate and verify cookies. The key is the client
+/// or server secret and is held by the implementation.
+///
+/// For server cookies [RFC 9018](https://tools.ietf.org/rfc/rfc9018) requires
+/// SipHash-2-4 with a 128 bit server secret for interoperability.
+pub trait CookieHasher {
+    /// Return the 64 bit keyed hash of the given data.
+    fn hash(&self, data: &[u8]) -> [u8; 8];
+}
+
+/// The result of verifying the server cookie of a request.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CookieStatus {
+    /// The request only contains a client cookie.
+    Missing,
+    /// The server cookie is valid.
+    Valid,
+    /// The server cookie is valid, but the response should contain a new one.
+    Stale,
+    /// The server cookie was not generated by this server for this client,
+    /// or is expired.
+    Invalid,
+}
+
+impl<'a> Cookie<'a> {
+    /// Generate a client cookie from the client and server IP address. The
+    /// hasher is keyed with the client secret.
+    ///
+    /// According to [RFC 7873 Appendix A.2](https://tools.ietf.org/rfc/rfc7873#appendix-A.2).
+    pub fn client_cookie<H: CookieHasher>(
+        hasher: &H,
+        client_address: &[u8],
+        server_address: &[u8],
+    ) -> Result<[u8; CLIENT_COOKIE_LENGTH], DnsMessageError> {
+        if client_address.len() > MAX_ADDRESS_LENGTH || server_address.len() > MAX_ADDRESS_LENGTH {
+            return Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption));
+        }
+
+        let mut data = [0; MAX_ADDRESS_LENGTH * 2];
+        data[..client_address.len()].copy_from_slice(client_address);
+        data[client_address.len()..client_address.len() + server_address.len()]
+            .copy_from_slice(server_address);
+
+        Ok(hasher.hash(&data[..client_address.len() + server_address.len()]))
+    }
+
+    /// Verify the server cookie of a request. The hasher is keyed with the
+    /// server secret and `now` is the current time in seconds since the epoch,
+    /// truncated to 32 bits.
+    ///
+    /// According to [RFC 9018 Section 4.3](https://tools.ietf.org/rfc/rfc9018#section-4.3).
+    pub fn verify<H: CookieHasher>(
+        &self,
+        hasher: &H,
+        client_address: &[u8],
+        now: u32,
+    ) -> CookieStatus {
+        if self.server.is_empty() {
+            return CookieStatus::Missing;
+        }
+
+        let server = match ServerCookie::from_bytes(self.server) {
+            Ok(server) => server,
+            Err(_) => return CookieStatus::Invalid,
+        };
+
+        // Serial number arithmetic, the timestamp wraps around in 2106.
+        let age = now.wrapping_sub(server.timestamp) as i32;
+        if !(-MAX_COOKIE_SKEW..=MAX_COOKIE_AGE).contains(&age) {
+            return CookieStatus::Invalid;
+        }
+
+        match ServerCookie::new(hasher, &self.client, server.timestamp, client_address) {
+            Ok(expected) if expected == server => {}
+            _ => return CookieStatus::Invalid,
+        }
+
+        if age > REFRESH_COOKIE_AGE {
+            CookieStatus::Stale
+        } else {
+            CookieStatus::Valid
+        }
+    }
+
+    /// Generate the server cookie for the response to this request. The
+    /// hasher is keyed with the server secret.
+    #[inline]
+    pub fn server_cookie<H: CookieHasher>(
+        &self,
+        hasher: &H,
+        client_address: &[u8],
+        now: u32,
+    ) -> Result<ServerCookie, DnsMessageError> {
+        ServerCookie::new(hasher, &self.client, now, client_address)
+    }
+}
+
+/// # Interoperable server cookie
+/// A server cookie that can be verified by all servers of an anycast set
+/// sharing the same secret.
+///
+/// According to [RFC 9018 Section 4](https://tools.ietf.org/rfc/rfc9018#section-4).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ServerCookie {
+    /// The version of the cookie format, currently 1.
+    pub version: u8,
+    /// Reserved bytes, must be zero.
+    pub reserved: [u8; 3],
+    /// The time the cookie was generated, in seconds since the epoch.
+    pub timestamp: u32,
+    /// The keyed hash over the client cookie, the fields above and the
+    /// client IP address.
+    pub hash: [u8; 8],
+}
+
+impl ServerCookie {
+    /// Generate a new server cookie for the given client cookie and client
+    /// IP address. The hasher is keyed with the server secret.
+    pub fn new<H: CookieHasher>(
+        hasher: &H,
+        client: &[u8; CLIENT_COOKIE_LENGTH],
+        timestamp: u32,
+        client_address: &[u8],
+    ) -> Result<Self, DnsMessageError> {
+        if client_address.len() > MAX_ADDRESS_LENGTH {
+            return Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption));
+        }
+
+        let mut data = [0; CLIENT_COOKIE_LENGTH + 8 + MAX_ADDRESS_LENGTH];
+        data[..CLIENT_COOKIE_LENGTH].copy_from_slice(client);
+        data[CLIENT_COOKIE_LENGTH] = SERVER_COOKIE_VERSION;
+        data[CLIENT_COOKIE_LENGTH + 4..CLIENT_COOKIE_LENGTH + 8].copy_from_slice(&timestamp.to_be_bytes());
+        data[CLIENT_COOKIE_LENGTH + 8..CLIENT_COOKIE_LENGTH + 8 + client_address.len()]
+            .copy_from_slice(client_address);
+
+        Ok(Self {
+            version: SERVER_COOKIE_VERSION,
+            reserved: [0; 3],
+            timestamp,
+            hash: hasher.hash(&data[..CLIENT_COOKIE_LENGTH + 8 + client_address.len()]),
+        })
+    }
+
+    /// Parse a server cookie from the server part of a cookie option.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DnsMessageError> {
+        if bytes.len() != SERVER_COOKIE_LENGTH || bytes[0] != SERVER_COOKIE_VERSION {
+            return Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption));
+        }
+
+        let mut i = 1;
+        Ok(Self {
+            version: bytes[0],
+            reserved: <[u8; 3]>::parse(bytes, &mut i)?,
+            timestamp: u32::parse(bytes, &mut i)?,
+            hash: <[u8; 8]>::parse(bytes, &mut i)?,
+        })
+    }
+
+    /// Return the wire format of the server cookie, to be used as server part
+    /// of a cookie option.
+    pub fn to_bytes(&self) -> [u8; SERVER_COOKIE_LENGTH] {
+        let mut bytes = [0; SERVER_COOKIE_LENGTH];
+        bytes[0] = self.version;
+        bytes[1..4].copy_from_slice(&self.reserved);
+        bytes[4..8].copy_from_slice(&self.timestamp.to_be_bytes());
+        bytes[8..].copy_from_slice(&self.hash);
+
+        bytes
+    }
+}
+
+impl<'a> EdnsOptionParse<'a> for Cookie<'a> {
+    #[inline]
+    fn parse(data: &'a [u8]) -> Result<Self, DnsMessageError> {
+        let mut i = 0;
+        let client = <[u8; CLIENT_COOKIE_LENGTH]>::parse(data, &mut i)
+            .map_err(|_| DnsMessageError::DnsError(DnsError::InvalidEdnsOption))?;
+        let server = &data[i..];
+
+        if !server.is_empty() && !(MIN_SERVER_COOKIE_LENGTH..=MAX_SERVER_COOKIE_LENGTH).contains(&server.len()) {
+            return Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption));
+        }
+
+        Ok(Self {
+            client,
+            server,
+        })
+    }
+}
+
+impl<'a> WriteBytes for Cookie<'a> {
+    #[inline]
+    fn write<
+        const PTR_STORAGE: usize,
+        const DNS_SECTION: usize,
+        B: MutBuffer + Buffer,
+    >(&self, message: &mut DnsMessage<PTR_STORAGE, DNS_SECTION, B>) -> Result<usize, DnsMessageError> {
+        let mut bytes = 0;
+
+        bytes += self.client.write(message)?;
+        bytes += message.write_bytes(self.server)?;
+
+        Ok(bytes)
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+#[cfg(test)]
+mod test {
+    use crate::edns::EdnsOption;
+    use crate::edns::option::test::parse_write_option;
+
+    use super::*;
+
+    #[test]
+    fn client_only() {
+        parse_write_option(
+            b"\x00\x0a\x00\x08\xd5\xa7\xe3\x00\x4d\x79\x05\x1e",
+            EdnsOption::Cookie(Cookie {
+                client: [0xd5, 0xa7, 0xe3, 0x00, 0x4d, 0x79, 0x05, 0x1e],
+                server: &[],
+            }),
+        );
+    }
+
+    #[test]
+    fn client_and_server() {
+        parse_write_option(
+            b"\x00\x0a\x00\x18\
+              \xd5\xa7\xe3\x00\x4d\x79\x05\x1e\
+              \x01\x00\x00\x00\x5c\x0a\x4b\x1e\xe3\x50\x5b\x22\x51\x9e\x8c\x8a",
+            EdnsOption::Cookie(Cookie {
+                client: [0xd5, 0xa7, 0xe3, 0x00, 0x4d, 0x79, 0x05, 0x1e],
+                server: &[
+                    0x01, 0x00, 0x00, 0x00, 0x5c, 0x0a, 0x4b, 0x1e,
+                    0xe3, 0x50, 0x5b, 0x22, 0x51, 0x9e, 0x8c, 0x8a,
+                ],
+            }),
+        );
+    }
+
+    /// SipHash-2-4, as required by RFC 9018 for server cookies.
+    struct SipHasher([u8; 16]);
+
+    impl CookieHasher for SipHasher {
+        fn hash(&self, data: &[u8]) -> [u8; 8] {
+            fn round(v: &mut [u64; 4]) {
+                v[0] = v[0].wrapping_add(v[1]); v[1] = v[1].rotate_left(13); v[1] ^= v[0]; v[0] = v[0].rotate_left(32);
+                v[2] = v[2].wrapping_add(v[3]); v[3] = v[3].rotate_left(16); v[3] ^= v[2];
+                v[0] = v[0].wrapping_add(v[3]); v[3] = v[3].rotate_left(21); v[3] ^= v[0];
+                v[2] = v[2].wrapping_add(v[1]); v[1] = v[1].rotate_left(17); v[1] ^= v[2]; v[2] = v[2].rotate_left(32);
+            }
+
+            let k0 = u64::from_le_bytes(self.0[..8].try_into().unwrap());
+            let k1 = u64::from_le_bytes(self.0[8..].try_into().unwrap());
+            let mut v = [
+                k0 ^ 0x736f6d6570736575,
+                k1 ^ 0x646f72616e646f6d,
+                k0 ^ 0x6c7967656e657261,
+                k1 ^ 0x7465646279746573,
+            ];
+
+            let mut chunks = data.chunks_exact(8);
+            for chunk in &mut chunks {
+                let m = u64::from_le_bytes(chunk.try_into().unwrap());
+                v[3] ^= m;
+                round(&mut v);
+                round(&mut v);
+                v[0] ^= m;
+            }
+
+            let mut last = [0; 8];
+            last[..chunks.remainder().len()].copy_from_slice(chunks.remainder());
+            last[7] = data.len() as u8;
+            let m = u64::from_le_bytes(last);
+            v[3] ^= m;
+            round(&mut v);
+            round(&mut v);
+            v[0] ^= m;
+
+            v[2] ^= 0xff;
+            for _ in 0..4 {
+                round(&mut v);
+            }
+
+            (v[0] ^ v[1] ^ v[2] ^ v[3]).to_le_bytes()
+        }
+    }
+
+    const SECRET: [u8; 16] = [
+        0xe5, 0xe9, 0x73, 0xe5, 0xa6, 0xb2, 0xa4, 0x3f,
+        0x48, 0xe7, 0xdc, 0x84, 0x9e, 0x37, 0xbf, 0xcf,
+    ];
+    const CLIENT: [u8; 8] = [0x24, 0x64, 0xc4, 0xab, 0xcf, 0x10, 0xc9, 0x57];
+    const CLIENT_ADDRESS: [u8; 4] = [198, 51, 100, 100];
+    const TIMESTAMP: u32 = 1559731985;
+    const SERVER: [u8; 16] = [
+        0x01, 0x00, 0x00, 0x00, 0x5c, 0xf7, 0x9f, 0x11,
+        0x1f, 0x81, 0x30, 0xc3, 0xee, 0xe2, 0x94, 0x80,
+    ];
+
+    #[test]
+    fn server_cookie() {
+        // Test vector from RFC 9018 Appendix A.1.
+        let cookie = Cookie {
+            client: CLIENT,
+            server: &[],
+        };
+        let server = cookie.server_cookie(&SipHasher(SECRET), &CLIENT_ADDRESS, TIMESTAMP).unwrap();
+        assert_eq!(server.to_bytes(), SERVER);
+        assert_eq!(ServerCookie::from_bytes(&SERVER), Ok(server));
+    }
+
+    #[test]
+    fn verify() {
+        let hasher = SipHasher(SECRET);
+        let cookie = Cookie {
+            client: CLIENT,
+            server: &SERVER,
+        };
+        assert_eq!(cookie.verify(&hasher, &CLIENT_ADDRESS, TIMESTAMP), CookieStatus::Valid);
+        assert_eq!(cookie.verify(&hasher, &CLIENT_ADDRESS, TIMESTAMP - 300), CookieStatus::Valid);
+        assert_eq!(cookie.verify(&hasher, &CLIENT_ADDRESS, TIMESTAMP - 301), CookieStatus::Invalid);
+        assert_eq!(cookie.verify(&hasher, &CLIENT_ADDRESS, TIMESTAMP + 1800), CookieStatus::Valid);
+        assert_eq!(cookie.verify(&hasher, &CLIENT_ADDRESS, TIMESTAMP + 1801), CookieStatus::Stale);
+        assert_eq!(cookie.verify(&hasher, &CLIENT_ADDRESS, TIMESTAMP + 3600), CookieStatus::Stale);
+        assert_eq!(cookie.verify(&hasher, &CLIENT_ADDRESS, TIMESTAMP + 3601), CookieStatus::Invalid);
+        assert_eq!(cookie.verify(&hasher, &[198, 51, 100, 101], TIMESTAMP), CookieStatus::Invalid);
+        assert_eq!(cookie.verify(&SipHasher([0; 16]), &CLIENT_ADDRESS, TIMESTAMP), CookieStatus::Invalid);
+
+        let cookie = Cookie {
+            client: CLIENT,
+            server: &SERVER[..8],
+        };
+        assert_eq!(cookie.verify(&hasher, &CLIENT_ADDRESS, TIMESTAMP), CookieStatus::Invalid);
+
+        let cookie = Cookie {
+            client: CLIENT,
+            server: &[],
+        };
+        assert_eq!(cookie.verify(&hasher, &CLIENT_ADDRESS, TIMESTAMP), CookieStatus::Missing);
+    }
+
+    #[test]
+    fn client_cookie() {
+        let hasher = SipHasher(SECRET);
+        let client = Cookie::client_cookie(&hasher, &CLIENT_ADDRESS, &[192, 0, 2, 53]).unwrap();
+        assert_eq!(client, hasher.hash(&[198, 51, 100, 100, 192, 0, 2, 53]));
+        assert_ne!(client, Cookie::client_cookie(&hasher, &CLIENT_ADDRESS, &[192, 0, 2, 54]).unwrap());
+        assert_eq!(
+            Cookie::client_cookie(&hasher, &[0; 17], &[192, 0, 2, 53]),
+            Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption))
+        );
+    }
+
+    #[test]
+    fn invalid_length() {
+        assert_eq!(
+            EdnsOption::parse(10, b"\x01\x02\x03\x04\x05\x06\x07"),
+            Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption))
+        );
+        assert_eq!(
+            EdnsOption::parse(10, b"\x01\x02\x03\x04\x05\x06\x07\x08\x09"),
+            Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption))
+        );
+    }
+}
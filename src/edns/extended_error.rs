@@ -0,0 +1,314 @@
+use core::fmt::Display;
+use crate::{Buffer, DnsError, DnsMessage, DnsMessageError, MutBuffer};
+use crate::edns::EdnsOption;
+use crate::edns::option::EdnsOptionParse;
+use crate::parse::Parse;
+use crate::rdata::OptIterator;
+use crate::write::WriteBytes;
+
+/// # Extended DNS error option
+/// This option is used to return additional information about the cause of
+/// a DNS error. A response may contain more than one of these options.
+///
+/// According to [RFC 8914 Section 2](https://tools.ietf.org/rfc/rfc8914#section-2).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ExtendedError<'a> {
+    /// The info code of the error.
+    pub info_code: ExtendedDnsError,
+    /// An optional UTF-8 encoded text with further information.
+    pub extra_text: &'a str,
+}
+
+impl<'a> ExtendedError<'a> {
+    /// Create a new extended error without extra text.
+    #[inline(always)]
+    pub const fn new(info_code: ExtendedDnsError) -> Self {
+        Self {
+            info_code,
+            extra_text: "",
+        }
+    }
+}
+
+impl Display for ExtendedError<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.extra_text.is_empty() {
+            write!(f, "{}", self.info_code)
+        } else {
+            write!(f, "{}: {}", self.info_code, self.extra_text)
+        }
+    }
+}
+
+/// The info code of an extended DNS error.
+///
+/// According to the [IANA registry](https://www.iana.org/assignments/dns-parameters/dns-parameters.xhtml#extended-dns-error-codes).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ExtendedDnsError {
+    Other,
+    UnsupportedDnsKeyAlgorithm,
+    UnsupportedDsDigestType,
+    StaleAnswer,
+    ForgedAnswer,
+    DnssecIndeterminate,
+    DnssecBogus,
+    SignatureExpired,
+    SignatureNotYetValid,
+    DnsKeyMissing,
+    RrSigsMissing,
+    NoZoneKeyBitSet,
+    NsecMissing,
+    CachedError,
+    NotReady,
+    Blocked,
+    Censored,
+    Filtered,
+    Prohibited,
+    StaleNxDomainAnswer,
+    NotAuthoritative,
+    NotSupported,
+    NoReachableAuthority,
+    NetworkError,
+    InvalidData,
+    SignatureExpiredBeforeValid,
+    TooEarly,
+    UnsupportedNsec3IterationsValue,
+    UnableToConformToPolicy,
+    Synthesized,
+    InvalidQueryType,
+    Unknown(u16),
+}
+
+impl From<ExtendedDnsError> for u16 {
+    fn from(e: ExtendedDnsError) -> Self {
+        match e {
+            ExtendedDnsError::Other => 0,
+            ExtendedDnsError::UnsupportedDnsKeyAlgorithm => 1,
+            ExtendedDnsError::UnsupportedDsDigestType => 2,
+            ExtendedDnsError::StaleAnswer => 3,
+            ExtendedDnsError::ForgedAnswer => 4,
+            ExtendedDnsError::DnssecIndeterminate => 5,
+            ExtendedDnsError::DnssecBogus => 6,
+            ExtendedDnsError::SignatureExpired => 7,
+            ExtendedDnsError::SignatureNotYetValid => 8,
+            ExtendedDnsError::DnsKeyMissing => 9,
+            ExtendedDnsError::RrSigsMissing => 10,
+            ExtendedDnsError::NoZoneKeyBitSet => 11,
+            ExtendedDnsError::NsecMissing => 12,
+            ExtendedDnsError::CachedError => 13,
+            ExtendedDnsError::NotReady => 14,
+            ExtendedDnsError::Blocked => 15,
+            ExtendedDnsError::Censored => 16,
+            ExtendedDnsError::Filtered => 17,
+            ExtendedDnsError::Prohibited => 18,
+            ExtendedDnsError::StaleNxDomainAnswer => 19,
+            ExtendedDnsError::NotAuthoritative => 20,
+            ExtendedDnsError::NotSupported => 21,
+            ExtendedDnsError::NoReachableAuthority => 22,
+            ExtendedDnsError::NetworkError => 23,
+            ExtendedDnsError::InvalidData => 24,
+            ExtendedDnsError::SignatureExpiredBeforeValid => 25,
+            ExtendedDnsError::TooEarly => 26,
+            ExtendedDnsError::UnsupportedNsec3IterationsValue => 27,
+            ExtendedDnsError::UnableToConformToPolicy => 28,
+            ExtendedDnsError::Synthesized => 29,
+            ExtendedDnsError::InvalidQueryType => 30,
+            ExtendedDnsError::Unknown(n) => n,
+        }
+    }
+}
+
+impl From<u16> for ExtendedDnsError {
+    fn from(n: u16) -> Self {
+        match n {
+            0 => ExtendedDnsError::Other,
+            1 => ExtendedDnsError::UnsupportedDnsKeyAlgorithm,
+            2 => ExtendedDnsError::UnsupportedDsDigestType,
+            3 => ExtendedDnsError::StaleAnswer,
+            4 => ExtendedDnsError::ForgedAnswer,
+            5 => ExtendedDnsError::DnssecIndeterminate,
+            6 => ExtendedDnsError::DnssecBogus,
+            7 => ExtendedDnsError::SignatureExpired,
+            8 => ExtendedDnsError::SignatureNotYetValid,
+            9 => ExtendedDnsError::DnsKeyMissing,
+            10 => ExtendedDnsError::RrSigsMissing,
+            11 => ExtendedDnsError::NoZoneKeyBitSet,
+            12 => ExtendedDnsError::NsecMissing,
+            13 => ExtendedDnsError::CachedError,
+            14 => ExtendedDnsError::NotReady,
+            15 => ExtendedDnsError::Blocked,
+            16 => ExtendedDnsError::Censored,
+            17 => ExtendedDnsError::Filtered,
+            18 => ExtendedDnsError::Prohibited,
+            19 => ExtendedDnsError::StaleNxDomainAnswer,
+            20 => ExtendedDnsError::NotAuthoritative,
+            21 => ExtendedDnsError::NotSupported,
+            22 => ExtendedDnsError::NoReachableAuthority,
+            23 => ExtendedDnsError::NetworkError,
+            24 => ExtendedDnsError::InvalidData,
+            25 => ExtendedDnsError::SignatureExpiredBeforeValid,
+            26 => ExtendedDnsError::TooEarly,
+            27 => ExtendedDnsError::UnsupportedNsec3IterationsValue,
+            28 => ExtendedDnsError::UnableToConformToPolicy,
+            29 => ExtendedDnsError::Synthesized,
+            30 => ExtendedDnsError::InvalidQueryType,
+            n => ExtendedDnsError::Unknown(n),
+        }
+    }
+}
+
+impl Display for ExtendedDnsError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let purpose = match self {
+            ExtendedDnsError::Other => "Other Error",
+            ExtendedDnsError::UnsupportedDnsKeyAlgorithm => "Unsupported DNSKEY Algorithm",
+            ExtendedDnsError::UnsupportedDsDigestType => "Unsupported DS Digest Type",
+            ExtendedDnsError::StaleAnswer => "Stale Answer",
+            ExtendedDnsError::ForgedAnswer => "Forged Answer",
+            ExtendedDnsError::DnssecIndeterminate => "DNSSEC Indeterminate",
+            ExtendedDnsError::DnssecBogus => "DNSSEC Bogus",
+            ExtendedDnsError::SignatureExpired => "Signature Expired",
+            ExtendedDnsError::SignatureNotYetValid => "Signature Not Yet Valid",
+            ExtendedDnsError::DnsKeyMissing => "DNSKEY Missing",
+            ExtendedDnsError::RrSigsMissing => "RRSIGs Missing",
+            ExtendedDnsError::NoZoneKeyBitSet => "No Zone Key Bit Set",
+            ExtendedDnsError::NsecMissing => "NSEC Missing",
+            ExtendedDnsError::CachedError => "Cached Error",
+            ExtendedDnsError::NotReady => "Not Ready",
+            ExtendedDnsError::Blocked => "Blocked",
+            ExtendedDnsError::Censored => "Censored",
+            ExtendedDnsError::Filtered => "Filtered",
+            ExtendedDnsError::Prohibited => "Prohibited",
+            ExtendedDnsError::StaleNxDomainAnswer => "Stale NXDOMAIN Answer",
+            ExtendedDnsError::NotAuthoritative => "Not Authoritative",
+            ExtendedDnsError::NotSupported => "Not Supported",
+            ExtendedDnsError::NoReachableAuthority => "No Reachable Authority",
+            ExtendedDnsError::NetworkError => "Network Error",
+            ExtendedDnsError::InvalidData => "Invalid Data",
+            ExtendedDnsError::SignatureExpiredBeforeValid => "Signature Expired before Valid",
+            ExtendedDnsError::TooEarly => "Too Early",
+            ExtendedDnsError::UnsupportedNsec3IterationsValue => "Unsupported NSEC3 Iterations Value",
+            ExtendedDnsError::UnableToConformToPolicy => "Unable to conform to policy",
+            ExtendedDnsError::Synthesized => "Synthesized",
+            ExtendedDnsError::InvalidQueryType => "Invalid Query Type",
+            ExtendedDnsError::Unknown(n) => return write!(f, "Unknown Error {}", n),
+        };
+
+        f.write_str(purpose)
+    }
+}
+
+impl<'a> EdnsOptionParse<'a> for ExtendedError<'a> {
+    #[inline]
+    fn parse(data: &'a [u8]) -> Result<Self, DnsMessageError> {
+        let mut i = 0;
+        let info_code = u16::parse(data, &mut i)
+            .map_err(|_| DnsMessageError::DnsError(DnsError::InvalidEdnsOption))?;
+        let extra_text = core::str::from_utf8(&data[i..])
+            .map_err(|_| DnsMessageError::DnsError(DnsError::InvalidEdnsOption))?;
+
+        Ok(Self {
+            info_code: info_code.into(),
+            extra_text,
+        })
+    }
+}
+
+impl<'a> WriteBytes for ExtendedError<'a> {
+    #[inline]
+    fn write<
+        const PTR_STORAGE: usize,
+        const DNS_SECTION: usize,
+        B: MutBuffer + Buffer,
+    >(&self, message: &mut DnsMessage<PTR_STORAGE, DNS_SECTION, B>) -> Result<usize, DnsMessageError> {
+        let mut bytes = 0;
+
+        bytes += u16::from(self.info_code).write(message)?;
+        bytes += message.write_bytes(self.extra_text.as_bytes())?;
+
+        Ok(bytes)
+    }
+}
+
+/// An iterator over the extended DNS errors of an OPT record.
+#[derive(Clone, Debug)]
+pub struct ExtendedErrorIterator<'a> {
+    pub(crate) options: OptIterator<'a>,
+}
+
+impl<'a> Iterator for ExtendedErrorIterator<'a> {
+    type Item = Result<ExtendedError<'a>, DnsMessageError>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        for option in self.options.by_ref() {
+            match option {
+                Ok(EdnsOption::ExtendedError(error)) => return Some(Ok(error)),
+                Ok(_) => continue,
+                Err(err) => return Some(Err(err)),
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+#[cfg(test)]
+mod test {
+    use crate::edns::option::test::parse_write_option;
+
+    use super::*;
+
+    #[test]
+    fn parse_write() {
+        parse_write_option(
+            b"\x00\x0f\x00\x0c\x00\x06validation",
+            EdnsOption::ExtendedError(ExtendedError {
+                info_code: ExtendedDnsError::DnssecBogus,
+                extra_text: "validation",
+            }),
+        );
+        parse_write_option(
+            b"\x00\x0f\x00\x02\x00\x12",
+            EdnsOption::ExtendedError(ExtendedError::new(ExtendedDnsError::Prohibited)),
+        );
+        parse_write_option(
+            b"\x00\x0f\x00\x02\x01\x00",
+            EdnsOption::ExtendedError(ExtendedError::new(ExtendedDnsError::Unknown(256))),
+        );
+    }
+
+    #[test]
+    fn invalid_text() {
+        assert_eq!(
+            EdnsOption::parse(15, b"\x00\x06\xff"),
+            Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption))
+        );
+    }
+
+    #[test]
+    fn info_code() {
+        for n in 0..=u8::MAX as u16 {
+            assert_eq!(u16::from(ExtendedDnsError::from(n)), n);
+        }
+        assert_eq!(ExtendedDnsError::from(15), ExtendedDnsError::Blocked);
+        assert_eq!(ExtendedDnsError::from(31), ExtendedDnsError::Unknown(31));
+    }
+
+    #[test]
+    fn display() {
+        use core::fmt::Write;
+
+        let mut text = arrayvec::ArrayString::<64>::new();
+        write!(text, "{}", ExtendedError {
+            info_code: ExtendedDnsError::Blocked,
+            extra_text: "malware",
+        }).unwrap();
+        assert_eq!(text.as_str(), "Blocked: malware");
+
+        text.clear();
+        write!(text, "{}", ExtendedError::new(ExtendedDnsError::Unknown(49152))).unwrap();
+        assert_eq!(text.as_str(), "Unknown Error 49152");
+    }
+}
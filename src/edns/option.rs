@@ -0,0 +1,352 @@
+use crate::{Buffer, DnsError, DnsMessage, DnsMessageError, MutBuffer};
+use crate::edns::{ClientSubnet, Cookie, ExtendedError};
+use crate::name::DnsName;
+use crate::parse::Parse;
+use crate::write::WriteBytes;
+
+const NSID: u16 = 3;
+const DAU: u16 = 5;
+const DHU: u16 = 6;
+const N3U: u16 = 7;
+const CLIENT_SUBNET: u16 = 8;
+const EXPIRE: u16 = 9;
+const COOKIE: u16 = 10;
+const TCP_KEEPALIVE: u16 = 11;
+const PADDING: u16 = 12;
+const CHAIN: u16 = 13;
+const KEY_TAG: u16 = 14;
+const EXTENDED_ERROR: u16 = 15;
+
+pub(crate) trait EdnsOptionParse<'a>: Sized {
+    fn parse(data: &'a [u8]) -> Result<Self, DnsMessageError>;
+}
+
+/// An EDNS(0) option.
+///
+/// According to the [IANA EDNS0 option code registry](https://www.iana.org/assignments/dns-parameters/dns-parameters.xhtml#dns-parameters-11).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum EdnsOption<'a> {
+    /// Name server identifier, [RFC 5001](https://tools.ietf.org/rfc/rfc5001).
+    /// Empty in queries.
+    Nsid(&'a [u8]),
+    /// DNSSEC algorithms understood, [RFC 6975](https://tools.ietf.org/rfc/rfc6975).
+    Dau(&'a [u8]),
+    /// DS hash algorithms understood, [RFC 6975](https://tools.ietf.org/rfc/rfc6975).
+    Dhu(&'a [u8]),
+    /// NSEC3 hash algorithms understood, [RFC 6975](https://tools.ietf.org/rfc/rfc6975).
+    N3u(&'a [u8]),
+    /// Client subnet, [RFC 7871](https://tools.ietf.org/rfc/rfc7871).
+    ClientSubnet(ClientSubnet),
+    /// Zone expire timer, [RFC 7314](https://tools.ietf.org/rfc/rfc7314).
+    /// Empty in queries.
+    Expire(Option<u32>),
+    /// DNS cookie, [RFC 7873](https://tools.ietf.org/rfc/rfc7873).
+    Cookie(Cookie<'a>),
+    /// TCP keepalive timeout in units of 100 milliseconds,
+    /// [RFC 7828](https://tools.ietf.org/rfc/rfc7828). Empty in queries.
+    TcpKeepalive(Option<u16>),
+    /// Padding of the given number of zero bytes,
+    /// [RFC 7830](https://tools.ietf.org/rfc/rfc7830).
+    Padding(u16),
+    /// Closest trust point of a requested chain query,
+    /// [RFC 7901](https://tools.ietf.org/rfc/rfc7901).
+    Chain(DnsName<'a>),
+    /// DNSSEC trust anchor key tags, [RFC 8145](https://tools.ietf.org/rfc/rfc8145).
+    KeyTag(KeyTags<'a>),
+    /// Extended DNS error, [RFC 8914](https://tools.ietf.org/rfc/rfc8914).
+    ExtendedError(ExtendedError<'a>),
+    /// An option which is not known to this library.
+    Unknown {
+        /// The option code.
+        code: u16,
+        /// The option data.
+        data: &'a [u8],
+    },
+}
+
+impl<'a> EdnsOption<'a> {
+    /// Parse the option with the given code from the given option data.
+    pub fn parse(code: u16, data: &'a [u8]) -> Result<Self, DnsMessageError> {
+        Ok(match code {
+            NSID => EdnsOption::Nsid(data),
+            DAU => EdnsOption::Dau(data),
+            DHU => EdnsOption::Dhu(data),
+            N3U => EdnsOption::N3u(data),
+            CLIENT_SUBNET => EdnsOption::ClientSubnet(ClientSubnet::parse(data)?),
+            EXPIRE => EdnsOption::Expire(match data.len() {
+                0 => None,
+                4 => Some(u32::parse(data, &mut 0)?),
+                _ => return Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption)),
+            }),
+            COOKIE => EdnsOption::Cookie(Cookie::parse(data)?),
+            TCP_KEEPALIVE => EdnsOption::TcpKeepalive(match data.len() {
+                0 => None,
+                2 => Some(u16::parse(data, &mut 0)?),
+                _ => return Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption)),
+            }),
+            PADDING => EdnsOption::Padding(data.len() as u16),
+            CHAIN => EdnsOption::Chain(parse_uncompressed_name(data)?),
+            KEY_TAG => EdnsOption::KeyTag(KeyTags::new(data)?),
+            EXTENDED_ERROR => EdnsOption::ExtendedError(ExtendedError::parse(data)?),
+            code => EdnsOption::Unknown { code, data },
+        })
+    }
+
+    /// The option code.
+    pub fn code(&self) -> u16 {
+        match self {
+            EdnsOption::Nsid(_) => NSID,
+            EdnsOption::Dau(_) => DAU,
+            EdnsOption::Dhu(_) => DHU,
+            EdnsOption::N3u(_) => N3U,
+            EdnsOption::ClientSubnet(_) => CLIENT_SUBNET,
+            EdnsOption::Expire(_) => EXPIRE,
+            EdnsOption::Cookie(_) => COOKIE,
+            EdnsOption::TcpKeepalive(_) => TCP_KEEPALIVE,
+            EdnsOption::Padding(_) => PADDING,
+            EdnsOption::Chain(_) => CHAIN,
+            EdnsOption::KeyTag(_) => KEY_TAG,
+            EdnsOption::ExtendedError(_) => EXTENDED_ERROR,
+            EdnsOption::Unknown { code, .. } => *code,
+        }
+    }
+}
+
+fn parse_uncompressed_name(data: &[u8]) -> Result<DnsName<'_>, DnsMessageError> {
+    let mut i = 0;
+    loop {
+        let len = u8::parse(data, &mut i)
+            .map_err(|_| DnsMessageError::DnsError(DnsError::InvalidEdnsOption))? as usize;
+        if len == 0 {
+            break;
+        }
+
+        // Compression is not allowed in option data.
+        if len > 63 || i + len > data.len() {
+            return Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption));
+        }
+
+        i += len;
+    }
+
+    if i != data.len() || i > 255 {
+        return Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption));
+    }
+
+    Ok(unsafe { DnsName::new_unchecked(data) })
+}
+
+impl<'a> WriteBytes for EdnsOption<'a> {
+    fn write<
+        const PTR_STORAGE: usize,
+        const DNS_SECTION: usize,
+        B: MutBuffer + Buffer,
+    >(&self, message: &mut DnsMessage<PTR_STORAGE, DNS_SECTION, B>) -> Result<usize, DnsMessageError> {
+        let mut bytes = 0;
+
+        bytes += self.code().write(message)?;
+        let length_placeholder = message.write_placeholder::<2>()?;
+        let length = match self {
+            EdnsOption::Nsid(data)
+            | EdnsOption::Dau(data)
+            | EdnsOption::Dhu(data)
+            | EdnsOption::N3u(data)
+            | EdnsOption::Unknown { data, .. } => message.write_bytes(data)?,
+            EdnsOption::ClientSubnet(client_subnet) => client_subnet.write(message)?,
+            EdnsOption::Expire(expire) => match expire {
+                Some(expire) => expire.write(message)?,
+                None => 0,
+            },
+            EdnsOption::Cookie(cookie) => cookie.write(message)?,
+            EdnsOption::TcpKeepalive(timeout) => match timeout {
+                Some(timeout) => timeout.write(message)?,
+                None => 0,
+            },
+            EdnsOption::Padding(length) => {
+                let mut remaining = *length as usize;
+                while remaining > 0 {
+                    let chunk = core::cmp::min(remaining, 32);
+                    message.write_bytes(&[0; 32][..chunk])?;
+                    remaining -= chunk;
+                }
+
+                *length as usize
+            }
+            EdnsOption::Chain(name) => message.write_name_uncompressed(*name)?,
+            EdnsOption::KeyTag(key_tags) => message.write_bytes(key_tags.data)?,
+            EdnsOption::ExtendedError(error) => error.write(message)?,
+        };
+        if length > u16::MAX as usize {
+            return Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption));
+        }
+
+        bytes += length;
+        bytes += length_placeholder(message, (length as u16).to_be_bytes());
+
+        Ok(bytes)
+    }
+}
+
+/// A list of DNSSEC key tags, as sent in the key tag option.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct KeyTags<'a> {
+    /// The key tags in wire format
+    data: &'a [u8],
+}
+
+impl<'a> KeyTags<'a> {
+    /// Creates a new list of key tags from wire format. The data must consist
+    /// of 16 bit key tags.
+    #[inline(always)]
+    pub fn new(data: &'a [u8]) -> Result<Self, DnsMessageError> {
+        if data.len() & 1 != 0 {
+            return Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption));
+        }
+
+        Ok(Self {
+            data,
+        })
+    }
+
+    /// Returns an iterator over the key tags.
+    #[inline(always)]
+    pub fn iter(&self) -> KeyTagsIterator<'a> {
+        KeyTagsIterator {
+            data: self.data,
+            pos: 0,
+        }
+    }
+}
+
+/// An iterator over a list of key tags.
+#[derive(Copy, Clone, Debug)]
+pub struct KeyTagsIterator<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Iterator for KeyTagsIterator<'a> {
+    type Item = u16;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        u16::parse(self.data, &mut self.pos).ok()
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+
+    pub(crate) fn parse_write_option<'a, const N: usize>(bytes: &'a [u8; N], expected: EdnsOption<'a>) {
+        let code = u16::from_be_bytes([bytes[0], bytes[1]]);
+        let length = u16::from_be_bytes([bytes[2], bytes[3]]) as usize;
+        assert_eq!(length, N - 4);
+        assert_eq!(EdnsOption::parse(code, &bytes[4..]), Ok(expected));
+
+        let mut message: DnsMessage<0, 0, _> = DnsMessage::new_mut(
+            arrayvec::ArrayVec::<u8, 512>::new()
+        ).unwrap();
+        assert_eq!(expected.write(&mut message), Ok(N));
+        let buffer = message.abort().unwrap();
+        assert_eq!(&buffer[crate::DNS_HEADER_SIZE..], bytes.as_slice());
+    }
+
+    #[test]
+    fn nsid() {
+        parse_write_option(b"\x00\x03\x00\x04ns01", EdnsOption::Nsid(b"ns01"));
+        parse_write_option(b"\x00\x03\x00\x00", EdnsOption::Nsid(b""));
+    }
+
+    #[test]
+    fn algorithms_understood() {
+        parse_write_option(b"\x00\x05\x00\x03\x08\x0d\x0f", EdnsOption::Dau(&[8, 13, 15]));
+        parse_write_option(b"\x00\x06\x00\x01\x02", EdnsOption::Dhu(&[2]));
+        parse_write_option(b"\x00\x07\x00\x01\x01", EdnsOption::N3u(&[1]));
+    }
+
+    #[test]
+    fn expire() {
+        parse_write_option(b"\x00\x09\x00\x00", EdnsOption::Expire(None));
+        parse_write_option(b"\x00\x09\x00\x04\x00\x09\x3a\x80", EdnsOption::Expire(Some(604800)));
+        assert_eq!(
+            EdnsOption::parse(9, b"\x00\x00"),
+            Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption))
+        );
+    }
+
+    #[test]
+    fn tcp_keepalive() {
+        parse_write_option(b"\x00\x0b\x00\x00", EdnsOption::TcpKeepalive(None));
+        parse_write_option(b"\x00\x0b\x00\x02\x01\x2c", EdnsOption::TcpKeepalive(Some(300)));
+        assert_eq!(
+            EdnsOption::parse(11, b"\x00"),
+            Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption))
+        );
+    }
+
+    #[test]
+    fn padding() {
+        parse_write_option(b"\x00\x0c\x00\x05\x00\x00\x00\x00\x00", EdnsOption::Padding(5));
+        parse_write_option(b"\x00\x0c\x00\x00", EdnsOption::Padding(0));
+    }
+
+    #[cfg(feature = "vec")]
+    #[test]
+    fn too_long() {
+        extern crate alloc;
+
+        let data = alloc::vec![0; 65536];
+        let mut message: DnsMessage<0, 0, _> = DnsMessage::new_mut(alloc::vec::Vec::new()).unwrap();
+        assert_eq!(
+            EdnsOption::Unknown { code: 65001, data: &data }.write(&mut message),
+            Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption))
+        );
+    }
+
+    #[test]
+    fn chain() {
+        parse_write_option(
+            b"\x00\x0d\x00\x0d\x07example\x03com\x00",
+            EdnsOption::Chain(DnsName::new(b"\x07example\x03com\x00").unwrap()),
+        );
+        assert_eq!(
+            EdnsOption::parse(13, b"\x07example\xc0\x00"),
+            Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption))
+        );
+        assert_eq!(
+            EdnsOption::parse(13, b"\x07example\x00\x00"),
+            Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption))
+        );
+    }
+
+    #[test]
+    fn key_tag() {
+        let option = EdnsOption::parse(14, b"\x4f\x66\x9e\x1b").unwrap();
+        match option {
+            EdnsOption::KeyTag(key_tags) => {
+                let mut iter = key_tags.iter();
+                assert_eq!(iter.next(), Some(20326));
+                assert_eq!(iter.next(), Some(40475));
+                assert_eq!(iter.next(), None);
+            }
+            _ => panic!("Expected key tag option"),
+        }
+        parse_write_option(
+            b"\x00\x0e\x00\x04\x4f\x66\x9e\x1b",
+            EdnsOption::KeyTag(KeyTags::new(b"\x4f\x66\x9e\x1b").unwrap()),
+        );
+        assert_eq!(
+            EdnsOption::parse(14, b"\x4f\x66\x9e"),
+            Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption))
+        );
+    }
+
+    #[test]
+    fn unknown() {
+        parse_write_option(
+            b"\xfd\xe9\x00\x03abc",
+            EdnsOption::Unknown { code: 65001, data: b"abc" },
+        );
+    }
+}
@@ -0,0 +1,54 @@
+/// # Padding policy
+/// How to pad a message with the padding option, to hide its size on
+/// encrypted transports. The policy is set with
+/// [`DnsAdditionals::set_padding`](crate::additional::DnsAdditionals::set_padding).
+///
+/// According to [RFC 8467](https://tools.ietf.org/rfc/rfc8467).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PaddingPolicy {
+    /// Pad the message to a multiple of the block length.
+    BlockLength(u16),
+    /// Pad the message to exactly the given length, if it is shorter.
+    Maximal(u16),
+}
+
+impl PaddingPolicy {
+    /// The recommended policy for queries, a block length of 128 bytes.
+    pub const QUERY: Self = PaddingPolicy::BlockLength(128);
+    /// The recommended policy for responses, a block length of 468 bytes.
+    pub const RESPONSE: Self = PaddingPolicy::BlockLength(468);
+
+    /// The length of the padding option data for a message of the given
+    /// length, without the padding option.
+    #[inline]
+    pub fn padding_length(&self, message_length: usize) -> u16 {
+        // The padding option itself has a 4 byte header.
+        let length = message_length + 4;
+        let padding = match *self {
+            PaddingPolicy::BlockLength(0) => 0,
+            PaddingPolicy::BlockLength(block_length) => {
+                let block_length = block_length as usize;
+                (block_length - length % block_length) % block_length
+            }
+            PaddingPolicy::Maximal(max_length) => (max_length as usize).saturating_sub(length),
+        };
+
+        padding as u16
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn padding_length() {
+        assert_eq!(PaddingPolicy::QUERY.padding_length(52), 72);
+        assert_eq!(PaddingPolicy::QUERY.padding_length(124), 0);
+        assert_eq!(PaddingPolicy::QUERY.padding_length(125), 127);
+        assert_eq!(PaddingPolicy::RESPONSE.padding_length(500), 432);
+        assert_eq!(PaddingPolicy::BlockLength(0).padding_length(500), 0);
+        assert_eq!(PaddingPolicy::Maximal(1232).padding_length(500), 728);
+        assert_eq!(PaddingPolicy::Maximal(512).padding_length(510), 0);
+    }
+}
@@ -166,6 +166,30 @@ impl DnsHeader {
         self.flags[1] |= u8::from(response_code) & 0b00001111;
     }
 
+    /// A view of the header with the flags of LLMNR.
+    #[inline(always)]
+    pub fn llmnr(&self) -> &LlmnrHeader {
+        unsafe { & *(self as *const Self as *const LlmnrHeader) }
+    }
+
+    /// A mutable view of the header with the flags of LLMNR.
+    #[inline(always)]
+    pub fn llmnr_mut(&mut self) -> &mut LlmnrHeader {
+        unsafe { &mut *(self as *mut Self as *mut LlmnrHeader) }
+    }
+
+    /// A view of the header with the flags of NBNS.
+    #[inline(always)]
+    pub fn nbns(&self) -> &NbnsHeader {
+        unsafe { & *(self as *const Self as *const NbnsHeader) }
+    }
+
+    /// A mutable view of the header with the flags of NBNS.
+    #[inline(always)]
+    pub fn nbns_mut(&mut self) -> &mut NbnsHeader {
+        unsafe { &mut *(self as *mut Self as *mut NbnsHeader) }
+    }
+
     #[inline(always)]
     pub(crate) fn set_question_count(&mut self, question_count: u16) {
         self.question_count = question_count.to_be_bytes();
@@ -206,6 +230,113 @@ impl core::fmt::Debug for DnsHeader {
     }
 }
 
+/// A DNS header of an LLMNR message, in which the AA bit is the conflict bit
+/// and the RD bit is the tentative bit. Recursion is not used. The other
+/// fields are those of the [`DnsHeader`].
+///
+/// According to [RFC 4795 Section 2.1.1](https://tools.ietf.org/rfc/rfc4795#section-2.1.1).
+#[repr(transparent)]
+pub struct LlmnrHeader(DnsHeader);
+
+impl LlmnrHeader {
+    /// Whether the sender of a query detected a conflict for the name, or
+    /// whether the responder is not known to own the name uniquely.
+    #[inline(always)]
+    pub fn conflict(&self) -> bool {
+        (self.0.flags[0] & 0b00000100) != 0
+    }
+
+    /// Whether the responder is still verifying that it owns the name
+    /// uniquely.
+    #[inline(always)]
+    pub fn tentative(&self) -> bool {
+        (self.0.flags[0] & 0b00000001) != 0
+    }
+
+    /// Set whether a conflict was detected.
+    #[inline(always)]
+    pub fn set_conflict(&mut self, conflict: bool) {
+        if conflict {
+            self.0.flags[0] |= 0b00000100;
+        } else {
+            self.0.flags[0] &= 0b11111011;
+        }
+    }
+
+    /// Set whether the responder is still verifying the name.
+    #[inline(always)]
+    pub fn set_tentative(&mut self, tentative: bool) {
+        if tentative {
+            self.0.flags[0] |= 0b00000001;
+        } else {
+            self.0.flags[0] &= 0b11111110;
+        }
+    }
+}
+
+impl core::fmt::Debug for LlmnrHeader {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("LlmnrHeader")
+            .field("id", &self.0.id())
+            .field("kind", &self.0.kind())
+            .field("opcode", &self.0.opcode())
+            .field("conflict", &self.conflict())
+            .field("truncated", &self.0.truncated())
+            .field("tentative", &self.tentative())
+            .field("response_code", &self.0.response_code())
+            .field("question_count", &self.0.question_count())
+            .field("answer_count", &self.0.answer_count())
+            .field("name_server_count", &self.0.name_server_count())
+            .field("additional_records_count", &self.0.additional_records_count())
+            .finish()
+    }
+}
+
+/// A DNS header of an NBNS message, which has the broadcast bit in place of
+/// the CD bit. The other fields are those of the [`DnsHeader`].
+///
+/// According to [RFC 1002 Section 4.2.1.1](https://tools.ietf.org/rfc/rfc1002#section-4.2.1.1).
+#[repr(transparent)]
+pub struct NbnsHeader(DnsHeader);
+
+impl NbnsHeader {
+    /// Whether the message is broadcast.
+    #[inline(always)]
+    pub fn broadcast(&self) -> bool {
+        (self.0.flags[1] & 0b00010000) != 0
+    }
+
+    /// Set whether the message is broadcast.
+    #[inline(always)]
+    pub fn set_broadcast(&mut self, broadcast: bool) {
+        if broadcast {
+            self.0.flags[1] |= 0b00010000;
+        } else {
+            self.0.flags[1] &= 0b11101111;
+        }
+    }
+}
+
+impl core::fmt::Debug for NbnsHeader {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("NbnsHeader")
+            .field("id", &self.0.id())
+            .field("kind", &self.0.kind())
+            .field("opcode", &self.0.opcode())
+            .field("authoritative_answer", &self.0.authoritative_answer())
+            .field("truncated", &self.0.truncated())
+            .field("recursion_desired", &self.0.recursion_desired())
+            .field("recursion_available", &self.0.recursion_available())
+            .field("broadcast", &self.broadcast())
+            .field("response_code", &self.0.response_code())
+            .field("question_count", &self.0.question_count())
+            .field("answer_count", &self.0.answer_count())
+            .field("name_server_count", &self.0.name_server_count())
+            .field("additional_records_count", &self.0.additional_records_count())
+            .finish()
+    }
+}
+
 /// The kind of a DNS header.
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum DnsHeaderKind {
@@ -272,10 +403,12 @@ pub enum DnsHeaderResponseCode {
     BadMode,
     BadName,
     BadAlg,
-    Reserved(u8),
+    BadTrunc,
+    BadCookie,
+    Reserved(u16),
 }
 
-impl From<DnsHeaderResponseCode> for u8 {
+impl From<DnsHeaderResponseCode> for u16 {
     fn from(r: DnsHeaderResponseCode) -> Self {
         match r {
             DnsHeaderResponseCode::NoError => 0,
@@ -295,13 +428,15 @@ impl From<DnsHeaderResponseCode> for u8 {
             DnsHeaderResponseCode::BadMode => 19,
             DnsHeaderResponseCode::BadName => 20,
             DnsHeaderResponseCode::BadAlg => 21,
+            DnsHeaderResponseCode::BadTrunc => 22,
+            DnsHeaderResponseCode::BadCookie => 23,
             DnsHeaderResponseCode::Reserved(n) => n,
         }
     }
 }
 
-impl From<u8> for DnsHeaderResponseCode {
-    fn from(n: u8) -> Self {
+impl From<u16> for DnsHeaderResponseCode {
+    fn from(n: u16) -> Self {
         match n {
             0 => DnsHeaderResponseCode::NoError,
             1 => DnsHeaderResponseCode::FormatError,
@@ -320,7 +455,22 @@ impl From<u8> for DnsHeaderResponseCode {
             19 => DnsHeaderResponseCode::BadMode,
             20 => DnsHeaderResponseCode::BadName,
             21 => DnsHeaderResponseCode::BadAlg,
+            22 => DnsHeaderResponseCode::BadTrunc,
+            23 => DnsHeaderResponseCode::BadCookie,
             n => DnsHeaderResponseCode::Reserved(n),
         }
     }
-}
\ No newline at end of file
+}
+
+impl From<DnsHeaderResponseCode> for u8 {
+    /// Only the lower 8 bits of extended response codes are kept.
+    fn from(r: DnsHeaderResponseCode) -> Self {
+        u16::from(r) as u8
+    }
+}
+
+impl From<u8> for DnsHeaderResponseCode {
+    fn from(n: u8) -> Self {
+        DnsHeaderResponseCode::from(n as u16)
+    }
+}
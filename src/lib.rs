@@ -1,3 +1,11 @@
+//! A no alloc no std DNS parser and serializer.
+//!
+//! Signing, verification and hashing are done through traits, e.g.
+//! [`tsig::TSigHmac`], [`sig0::Sig0Signer`], [`dnssec::DnssecVerifier`],
+//! [`dnssec::DigestHasher`] and [`edns::CookieHasher`], which are implemented
+//! by the caller, so that this crate does not depend on any cryptographic
+//! implementation.
+
 #![no_std]
 #![feature(generic_const_exprs)]
 
@@ -19,6 +27,14 @@ pub mod additional;
 pub mod answer;
 pub mod rdata;
 pub mod buffer;
+pub mod edns;
+pub mod tsig;
+pub mod sig0;
+pub mod dnssec;
+pub mod dnssd;
+pub mod llmnr;
+pub mod mdns;
+pub mod nbns;
 mod parse;
 mod write;
 
@@ -59,6 +75,16 @@ pub enum DnsError {
     RDataLongerThanMessage,
     UnexpectedEndOfBuffer,
     InvalidTxtRecord,
+    InvalidEdnsOption,
+    MissingEdnsRecord,
+    RDataTrailingBytes,
+    RDataOverrun,
+    InvalidTypeBitMap,
+    InvalidSvcParam,
+    InvalidRRSet,
+    InvalidDigest,
+    InvalidNetbiosName,
+    InvalidNetbiosRecord,
 }
 
 #[derive(Debug, PartialEq)]
@@ -255,6 +281,36 @@ impl<
 
         Ok(bytes_written)
     }
+
+    pub(crate) fn write_name_uncompressed(
+        &mut self,
+        name: DnsName,
+    ) -> Result<usize, DnsMessageError> {
+        let mut bytes_written = 0;
+        for label in name.iter() {
+            let label = label?;
+            bytes_written += self.write_bytes(&[label.len() as u8])?;
+            bytes_written += self.write_bytes(label)?;
+        }
+        bytes_written += self.write_bytes(&[0])?; // Null terminator
+
+        Ok(bytes_written)
+    }
+}
+
+impl<B: MutBuffer + Buffer> DnsMessage<0, 0, B> {
+    /// Creates a message that appends to the end of the buffer, without a
+    /// header and without name compression, to write records outside of a
+    /// DNS message.
+    #[inline(always)]
+    pub(crate) fn raw(buffer: B) -> Self {
+        Self {
+            position: buffer.len(),
+            buffer,
+            ptr_storage: [],
+            ptr_len: 0,
+        }
+    }
 }
 
 impl<
@@ -350,13 +406,152 @@ impl<
     }
 }
 
+#[cfg(test)]
+mod testutils {
+    use arrayvec::ArrayVec;
+    use crate::DnsMessage;
+    use crate::additional::DnsAdditionals;
+    use crate::name::DnsName;
+    use crate::question::{DnsQClass, DnsQType, DnsQuestion};
+    use crate::sig0::{Sig0Signer, Sig0Verifier};
+    use crate::tsig::TSigHmac;
+
+    // The query for the A record of example.com, written by `query`.
+    pub(crate) const QUERY: [u8; 29] = [
+        0x12, 0x34, // ID
+        0x01, 0x00, // Flags
+        0x00, 0x01, // Question count
+        0x00, 0x00, // Answer count
+        0x00, 0x00, // Authority count
+        0x00, 0x00, // Additional count
+        0x07, b'e', b'x', b'a', b'm', b'p', b'l', b'e', // Name
+        0x03, b'c', b'o', b'm', // Name
+        0x00, // Name
+        0x00, 0x01, // Type
+        0x00, 0x01, // Class
+    ];
+
+    // Write `QUERY` and pass its additionals section to the given function,
+    // e.g. to sign the message.
+    pub(crate) fn query<F>(f: F) -> ArrayVec<u8, 512>
+    where
+        F: FnOnce(&mut DnsAdditionals<8, ArrayVec<u8, 512>>),
+    {
+        let mut message: DnsMessage<8, 0, _> = DnsMessage::new_mut(ArrayVec::<u8, 512>::new()).unwrap();
+        message.header_mut().unwrap().set_id(0x1234);
+        message.header_mut().unwrap().set_recursion_desired(true);
+        let mut questions = message.questions();
+        questions.append(DnsQuestion {
+            name: DnsName::new(b"\x07example\x03com\x00").unwrap(),
+            qtype: DnsQType::A,
+            unicast_response: false,
+            qclass: DnsQClass::IN,
+        }).unwrap();
+        let message = questions.complete().unwrap();
+        let message = message.answers().complete().unwrap();
+        let message = message.name_servers().complete().unwrap();
+        let mut additionals = message.additionals();
+        f(&mut additionals);
+
+        additionals.complete().unwrap().abort().unwrap()
+    }
+
+    pub(crate) fn additionals(bytes: &[u8]) -> DnsAdditionals<8, &[u8]> {
+        let message: DnsMessage<8, 0, _> = DnsMessage::new(bytes).unwrap();
+        let message = message.questions().complete().unwrap();
+        let message = message.answers().complete().unwrap();
+        let message = message.name_servers().complete().unwrap();
+
+        message.additionals()
+    }
+
+    // Neither an HMAC nor a signature, but keyed and sensitive to every byte
+    // of its input, which is also recorded.
+    pub(crate) struct TestMac<'a, const N: usize> {
+        key: u8,
+        mac: [u8; N],
+        length: usize,
+        input: Option<&'a mut ArrayVec<u8, 512>>,
+    }
+
+    impl<'a, const N: usize> TestMac<'a, N> {
+        pub(crate) fn new(key: u8) -> Self {
+            Self { key, mac: [key; N], length: 0, input: None }
+        }
+
+        // Record the input to the given buffer.
+        pub(crate) fn recording(key: u8, input: &'a mut ArrayVec<u8, 512>) -> Self {
+            Self { input: Some(input), ..Self::new(key) }
+        }
+
+        fn add(&mut self, data: &[u8]) {
+            for &byte in data {
+                let i = self.length % N;
+                self.mac[i] = self.mac[i].wrapping_mul(31).wrapping_add(byte ^ self.key);
+                self.length += 1;
+            }
+            if let Some(input) = self.input.as_mut() {
+                input.try_extend_from_slice(data).unwrap();
+            }
+        }
+
+        // Return the MAC of the given data.
+        pub(crate) fn mac(mut self, data: &[u8]) -> [u8; N] {
+            self.add(data);
+            self.mac
+        }
+    }
+
+    impl<const N: usize> TSigHmac for TestMac<'_, N> {
+        type Mac = [u8; N];
+
+        fn mac_length(&self) -> usize {
+            N
+        }
+
+        fn update(&mut self, data: &[u8]) {
+            self.add(data);
+        }
+
+        fn finalize(self) -> Self::Mac {
+            self.mac
+        }
+    }
+
+    impl<const N: usize> Sig0Signer for TestMac<'_, N> {
+        type Signature = [u8; N];
+
+        fn signature_length(&self) -> usize {
+            N
+        }
+
+        fn update(&mut self, data: &[u8]) {
+            self.add(data);
+        }
+
+        fn finalize(self) -> Self::Signature {
+            self.mac
+        }
+    }
+
+    impl<const N: usize> Sig0Verifier for TestMac<'_, N> {
+        fn update(&mut self, data: &[u8]) {
+            self.add(data);
+        }
+
+        fn verify(self, signature: &[u8]) -> bool {
+            self.mac.as_slice() == signature
+        }
+    }
+}
+
 #[cfg(any(feature = "heapless", feature = "arrayvec", feature = "vec"))]
 #[cfg(test)]
 mod test {
     use super::*;
 
     mod question {
-        use crate::header::{DnsHeaderOpcode, DnsHeaderResponseCode};
+        use crate::header::{DnsHeaderKind, DnsHeaderOpcode, DnsHeaderResponseCode};
         use crate::question::{DnsQClass, DnsQType, DnsQuestion};
         use super::*;
 
@@ -475,26 +670,31 @@ mod test {
                 questions.append(DnsQuestion {
                     name: DnsName::new(b"\x03www\x07example\x03com\x00").unwrap(),
                     qtype: DnsQType::A,
+                    unicast_response: false,
                     qclass: DnsQClass::IN,
                 }).unwrap();
                 questions.append(DnsQuestion {
                     name: DnsName::new(b"\x03www\x07example\x03com\x00").unwrap(),
                     qtype: DnsQType::AAAA,
+                    unicast_response: false,
                     qclass: DnsQClass::IN,
                 }).unwrap();
                 questions.append(DnsQuestion {
                     name: DnsName::new(b"\x03www\x07example\x03com\x00").unwrap(),
                     qtype: DnsQType::MX,
+                    unicast_response: false,
                     qclass: DnsQClass::IN,
                 }).unwrap();
                 questions.append(DnsQuestion {
                     name: DnsName::new(b"\x03www\x08examples\x03com\x00").unwrap(),
                     qtype: DnsQType::TXT,
+                    unicast_response: false,
                     qclass: DnsQClass::IN,
                 }).unwrap();
                 questions.append(DnsQuestion {
                     name: DnsName::new(b"\x08examples\x03com\x00").unwrap(),
                     qtype: DnsQType::CERT,
+                    unicast_response: false,
                     qclass: DnsQClass::IN,
                 }).unwrap();
                 let message = questions.complete().unwrap();
@@ -565,6 +765,70 @@ mod test {
                 assert_eq!(question.qclass, DnsQClass::IN);
                 assert!(question_iter.next().is_none());
             }
+
+            #[test]
+            fn unknown_type_and_class() {
+                use crate::answer::{DnsAClass, DnsAnswer};
+                use crate::rdata::{DnsAType, Unknown};
+
+                let buffer = ArrayVec::<u8, 512>::from_iter([
+                    0x00, 0x04, // ID
+                    0x81, 0x00, // Flags
+                    0x00, 0x01, // Question count
+                    0x00, 0x01, // Answer count
+                    0x00, 0x00, // Authority count
+                    0x00, 0x00, // Additional count
+                    0x07, b'e', b'x', b'a', b'm', b'p', b'l', b'e', // Name
+                    0x00, // Name
+                    0x01, 0x04, // Type (AMTRELAY)
+                    0x00, 0xfe, // Class (NONE)
+                    0xC0, 0x0C, // Name Pointer (0x0C = 12)
+                    0x01, 0x04, // Type (AMTRELAY)
+                    0x00, 0xfe, // Class (NONE)
+                    0x00, 0x00, 0x0e, 0x10, // TTL
+                    0x00, 0x03, // Data length
+                    0x0a, 0x01, 0x00, // Data
+                ]);
+                let message: DnsMessage<8, 0, _> = DnsMessage::new(buffer.clone()).unwrap();
+                let mut questions = message.questions();
+                let question = questions.iter().unwrap().next().unwrap().unwrap();
+                assert_eq!(question.qtype, DnsQType::Unknown(260));
+                assert_eq!(question.qclass, DnsQClass::Unknown(254));
+                let (qtype, qclass) = (question.qtype, question.qclass);
+                let message = questions.complete().unwrap();
+                let mut answers = message.answers();
+                let answer = answers.iter().unwrap().next().unwrap().unwrap().into_parsed().unwrap();
+                assert_eq!(answer.aclass, DnsAClass::Unknown(254));
+                assert_eq!(answer.rdata, DnsAType::Unknown(Unknown {
+                    type_: 260,
+                    data: &[0x0a, 0x01, 0x00],
+                }));
+                let (aclass, rdata) = (answer.aclass, answer.rdata);
+
+                // Write the records again and check that nothing is lost
+                let mut message: DnsMessage<8, 0, _> = DnsMessage::new_mut(ArrayVec::<u8, 512>::new()).unwrap();
+                message.header_mut().unwrap().set_id(0x0004);
+                message.header_mut().unwrap().set_kind(DnsHeaderKind::Response);
+                message.header_mut().unwrap().set_recursion_desired(true);
+                let mut questions = message.questions();
+                questions.append(DnsQuestion {
+                    name: DnsName::new(b"\x07example\x00").unwrap(),
+                    qtype,
+                    unicast_response: false,
+                    qclass,
+                }).unwrap();
+                let mut answers = questions.complete().unwrap().answers();
+                answers.append(DnsAnswer {
+                    name: DnsName::new(b"\x07example\x00").unwrap(),
+                    rdata,
+                    cache_flush: false,
+                    aclass,
+                    ttl: 3600,
+                }).unwrap();
+                let written = answers.complete().unwrap().abort().unwrap();
+
+                assert_eq!(written, buffer);
+            }
         }
 
         #[cfg(feature = "vec")]
@@ -598,6 +862,7 @@ mod test {
             questions.append(DnsQuestion {
                 name: DnsName::new(b"\x03www\x07example\x03com\x00").unwrap(),
                 qtype: DnsQType::A,
+                unicast_response: false,
                 qclass: DnsQClass::IN,
             }).unwrap();
             let message = questions.complete().unwrap();
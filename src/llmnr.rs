@@ -0,0 +1,479 @@
+use crate::{Buffer, DnsError, DnsMessage, DnsMessageError, MutBuffer};
+use crate::answer::DnsAnswer;
+use crate::header::{DnsHeaderKind, DnsHeaderOpcode, DnsHeaderResponseCode};
+use crate::name::DnsName;
+use crate::question::{DnsQClass, DnsQType, DnsQuestion};
+use crate::rdata::DnsAType;
+
+// The time to wait for responses, in milliseconds, and the number of
+// uniqueness verification queries.
+// According to RFC 4795 Sections 4.1 and 7.
+const LLMNR_TIMEOUT: u64 = 1000;
+const VERIFY_COUNT: u8 = 3;
+const PTR_STORAGE: usize = 8;
+
+/// Write an LLMNR query for the name. The query has a single question, and
+/// the conflict bit is set if the sender received conflicting responses for
+/// a unique name.
+///
+/// According to [RFC 4795 Sections 2.1.1 and 4.2](https://tools.ietf.org/rfc/rfc4795#section-2.1.1).
+pub fn query<B: MutBuffer + Buffer>(
+    id: u16,
+    name: DnsName,
+    qtype: DnsQType,
+    conflict: bool,
+    buffer: B,
+) -> Result<B, DnsMessageError> {
+    let mut message: DnsMessage<PTR_STORAGE, 0, _> = DnsMessage::new_mut(buffer)?;
+    let header = message.header_mut()?;
+    header.set_id(id);
+    header.llmnr_mut().set_conflict(conflict);
+    let mut questions = message.questions();
+    questions.append(DnsQuestion {
+        name,
+        qtype,
+        unicast_response: false,
+        qclass: DnsQClass::IN,
+    })?;
+
+    questions.complete()?.abort()
+}
+
+/// The state of an LLMNR responder.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum LlmnrState {
+    /// The name is verified to be unique, with the number of queries sent.
+    /// Queries are answered with the tentative bit set.
+    Verifying(u8),
+    /// Queries are answered.
+    Running,
+    /// Another host owns the name. The responder must be recreated with
+    /// another name.
+    Conflict,
+}
+
+/// A sans-IO LLMNR responder for the records of a unique name, e.g. the
+/// host name, on one interface.
+///
+/// The responder does not read the clock or the network. Times are passed in
+/// milliseconds and [`LlmnrResponder::poll`] must be called at the time
+/// returned by [`LlmnrResponder::timeout`]. It writes the uniqueness
+/// verification queries, which are sent to the LLMNR multicast address. The
+/// received messages are passed to [`LlmnrResponder::handle`] with their
+/// source address, and the responses it writes are sent back to the source.
+/// The buffer is cleared before a message is written.
+///
+/// According to [RFC 4795](https://tools.ietf.org/rfc/rfc4795).
+pub struct LlmnrResponder<'a> {
+    name: DnsName<'a>,
+    records: &'a [DnsAnswer<'a, DnsAType<'a>>],
+    address: &'a [u8],
+    id: u16,
+    state: LlmnrState,
+    timeout: Option<u64>,
+}
+
+impl<'a> LlmnrResponder<'a> {
+    /// Create a new responder for the records of the name, which starts
+    /// verifying the name. Records of other names are ignored. The address
+    /// is the address of the interface, and the id is used for the
+    /// verification queries.
+    pub fn new(
+        name: DnsName<'a>,
+        records: &'a [DnsAnswer<'a, DnsAType<'a>>],
+        address: &'a [u8],
+        id: u16,
+        now: u64,
+    ) -> Self {
+        Self {
+            name,
+            records,
+            address,
+            id,
+            state: LlmnrState::Verifying(0),
+            timeout: Some(now),
+        }
+    }
+
+    /// The current state.
+    #[inline(always)]
+    pub fn state(&self) -> LlmnrState {
+        self.state
+    }
+
+    /// The time at which [`LlmnrResponder::poll`] must be called next, if
+    /// any.
+    #[inline(always)]
+    pub fn timeout(&self) -> Option<u64> {
+        self.timeout
+    }
+
+    /// Send the next verification query, if it is due. Return whether a
+    /// query was written to the buffer.
+    ///
+    /// According to [RFC 4795 Section 4.1](https://tools.ietf.org/rfc/rfc4795#section-4.1).
+    pub fn poll<B: MutBuffer + Buffer>(&mut self, now: u64, buffer: &mut B) -> Result<bool, DnsMessageError> {
+        if !self.timeout.is_some_and(|timeout| timeout <= now) {
+            return Ok(false);
+        }
+
+        match self.state {
+            LlmnrState::Verifying(count) if count < VERIFY_COUNT => {
+                buffer.truncate(0)?;
+                query(self.id, self.name, DnsQType::ALL, false, &mut *buffer)?;
+                self.state = LlmnrState::Verifying(count + 1);
+                self.timeout = Some(now + LLMNR_TIMEOUT);
+
+                Ok(true)
+            }
+            LlmnrState::Verifying(_) => {
+                // No other host responded.
+                self.state = LlmnrState::Running;
+                self.timeout = None;
+
+                Ok(false)
+            }
+            _ => {
+                self.timeout = None;
+
+                Ok(false)
+            }
+        }
+    }
+
+    /// Handle a received message from the given source address. Queries for
+    /// the name are answered, and responses to the verification queries are
+    /// checked for conflicts. Return whether a response was written to the
+    /// buffer.
+    pub fn handle<B: MutBuffer + Buffer>(
+        &mut self,
+        now: u64,
+        packet: &[u8],
+        source: &[u8],
+        buffer: &mut B,
+    ) -> Result<bool, DnsMessageError> {
+        let message: DnsMessage<0, 0, _> = DnsMessage::new(packet)?;
+        let header = *message.header()?;
+        // Queries must have a single question and no records, and
+        // responses must repeat it. Other messages are discarded.
+        // According to RFC 4795 Section 2.1.1.
+        if header.opcode() != DnsHeaderOpcode::Query
+            || header.question_count() != 1 {
+            return Ok(false);
+        }
+
+        let mut questions = message.questions();
+        let question = questions.iter()?.next()
+            .ok_or(DnsMessageError::DnsError(DnsError::InvalidQuestion))??;
+        if question.name != self.name {
+            return Ok(false);
+        }
+
+        match header.kind() {
+            DnsHeaderKind::Query => {
+                if header.answer_count() != 0 || header.name_server_count() != 0 {
+                    return Ok(false);
+                }
+
+                // The sender received conflicting responses, the name must be
+                // verified again without answering.
+                // According to RFC 4795 Section 4.2.
+                if header.llmnr().conflict() {
+                    if self.state == LlmnrState::Running {
+                        self.state = LlmnrState::Verifying(0);
+                        self.timeout = Some(now);
+                    }
+
+                    return Ok(false);
+                }
+
+                if self.state == LlmnrState::Conflict {
+                    return Ok(false);
+                }
+
+                self.answer(header.id(), &question, buffer)
+            }
+            DnsHeaderKind::Response => {
+                if !matches!(self.state, LlmnrState::Verifying(_))
+                    || header.id() != self.id
+                    || header.response_code() != DnsHeaderResponseCode::NoError
+                    || header.answer_count() == 0 {
+                    return Ok(false);
+                }
+
+                // If the other responder is verifying the name too, the one
+                // with the lower address keeps it.
+                // According to RFC 4795 Section 4.1.
+                if !header.llmnr().tentative() || source < self.address {
+                    self.state = LlmnrState::Conflict;
+                    self.timeout = None;
+                }
+
+                Ok(false)
+            }
+        }
+    }
+
+    // Answer the question with the matching records, if any. The question
+    // is repeated and recursion is not available.
+    //
+    // According to RFC 4795 Section 2.1.1.
+    fn answer<B: MutBuffer + Buffer>(
+        &self,
+        id: u16,
+        question: &DnsQuestion,
+        buffer: &mut B,
+    ) -> Result<bool, DnsMessageError> {
+        let matches = |record: &&DnsAnswer<DnsAType>| record.name == self.name
+            && (question.qtype == DnsQType::ALL || question.qtype.id() == record.rdata.id())
+            && (question.qclass == DnsQClass::ANY || question.qclass.id() == record.aclass.id());
+        if !self.records.iter().any(|record| matches(&record)) {
+            return Ok(false);
+        }
+
+        buffer.truncate(0)?;
+        let mut message: DnsMessage<PTR_STORAGE, 0, _> = DnsMessage::new_mut(&mut *buffer)?;
+        let header = message.header_mut()?;
+        header.set_id(id);
+        header.set_kind(DnsHeaderKind::Response);
+        header.llmnr_mut().set_tentative(matches!(self.state, LlmnrState::Verifying(_)));
+        let mut questions = message.questions();
+        questions.append(DnsQuestion {
+            name: question.name,
+            qtype: question.qtype,
+            unicast_response: false,
+            qclass: question.qclass,
+        })?;
+        let message = questions.complete()?;
+        let mut answers = message.answers();
+        for record in self.records.iter().filter(matches) {
+            answers.append(DnsAnswer {
+                name: record.name,
+                rdata: record.rdata,
+                cache_flush: false,
+                aclass: record.aclass,
+                ttl: record.ttl,
+            })?;
+        }
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use arrayvec::ArrayVec;
+    use crate::answer::DnsAClass;
+    use crate::rdata::{A, Aaaa};
+    use super::*;
+
+    const ADDRESS: [u8; 4] = [192, 168, 1, 20];
+
+    fn host() -> DnsName<'static> {
+        DnsName::new(b"\x04host\x00").unwrap()
+    }
+
+    fn records() -> [DnsAnswer<'static, DnsAType<'static>>; 2] {
+        [
+            DnsAnswer {
+                name: host(),
+                rdata: DnsAType::A(A { address: ADDRESS }),
+                cache_flush: false,
+                aclass: DnsAClass::IN,
+                ttl: 30,
+            },
+            DnsAnswer {
+                name: host(),
+                rdata: DnsAType::AAAA(Aaaa { address: [0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1] }),
+                cache_flush: false,
+                aclass: DnsAClass::IN,
+                ttl: 30,
+            },
+        ]
+    }
+
+    fn running<'a>(records: &'a [DnsAnswer<'a, DnsAType<'a>>]) -> LlmnrResponder<'a> {
+        let mut responder = LlmnrResponder::new(host(), records, &ADDRESS, 0x1234, 0);
+        let mut buffer = ArrayVec::<u8, 512>::new();
+        while let Some(timeout) = responder.timeout() {
+            responder.poll(timeout, &mut buffer).unwrap();
+        }
+        assert_eq!(responder.state(), LlmnrState::Running);
+
+        responder
+    }
+
+    fn response(id: u16, tentative: bool) -> ArrayVec<u8, 512> {
+        let mut message: DnsMessage<8, 0, _> = DnsMessage::new_mut(ArrayVec::<u8, 512>::new()).unwrap();
+        let header = message.header_mut().unwrap();
+        header.set_id(id);
+        header.set_kind(DnsHeaderKind::Response);
+        header.llmnr_mut().set_tentative(tentative);
+        let mut questions = message.questions();
+        questions.append(DnsQuestion {
+            name: host(),
+            qtype: DnsQType::ALL,
+            unicast_response: false,
+            qclass: DnsQClass::IN,
+        }).unwrap();
+        let message = questions.complete().unwrap();
+        let mut answers = message.answers();
+        answers.append(DnsAnswer {
+            name: host(),
+            rdata: DnsAType::A(A { address: [192, 168, 1, 21] }),
+            cache_flush: false,
+            aclass: DnsAClass::IN,
+            ttl: 30,
+        }).unwrap();
+
+        answers.complete().unwrap().abort().unwrap()
+    }
+
+    #[test]
+    fn header() {
+        let mut bytes = super::query(0x1234, host(), DnsQType::A, true, ArrayVec::<u8, 512>::new()).unwrap();
+        bytes[2] |= 0b00000001;
+        let message: DnsMessage<0, 0, _> = DnsMessage::new(bytes.as_slice()).unwrap();
+        let header = message.header().unwrap();
+        assert!(header.llmnr().conflict());
+        assert!(header.llmnr().tentative());
+        assert!(!header.truncated());
+
+        let mut message: DnsMessage<0, 0, _> = DnsMessage::new_mut(bytes).unwrap();
+        let header = message.header_mut().unwrap().llmnr_mut();
+        header.set_conflict(false);
+        header.set_tentative(false);
+        let bytes = message.abort().unwrap();
+        assert_eq!(bytes[2], 0);
+    }
+
+    #[test]
+    fn query() {
+        let bytes = super::query(0x1234, host(), DnsQType::A, true, ArrayVec::<u8, 512>::new()).unwrap();
+        assert_eq!(
+            bytes.as_slice(),
+            [
+                0x12, 0x34, 0x04, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // Header
+                0x04, b'h', b'o', b's', b't', 0x00, // Name
+                0x00, 0x01, // Type
+                0x00, 0x01, // Class
+            ].as_slice(),
+        );
+    }
+
+    #[test]
+    fn verify() {
+        let records = records();
+        let mut responder = LlmnrResponder::new(host(), &records, &ADDRESS, 0x1234, 0);
+        let mut buffer = ArrayVec::<u8, 512>::new();
+        for count in 1..=3 {
+            let now = responder.timeout().unwrap();
+            assert!(responder.poll(now, &mut buffer).unwrap());
+            assert_eq!(responder.state(), LlmnrState::Verifying(count));
+            assert_eq!(responder.timeout(), Some(now + 1000));
+            assert_eq!(buffer.as_slice(), super::query(0x1234, host(), DnsQType::ALL, false, ArrayVec::<u8, 512>::new()).unwrap().as_slice());
+        }
+        assert!(!responder.poll(3000, &mut buffer).unwrap());
+        assert_eq!(responder.state(), LlmnrState::Running);
+        assert_eq!(responder.timeout(), None);
+    }
+
+    #[test]
+    fn answer() {
+        let records = records();
+        let mut responder = LlmnrResponder::new(host(), &records, &ADDRESS, 0x1234, 0);
+        let mut buffer = ArrayVec::<u8, 512>::new();
+
+        // The answer is tentative while the name is verified.
+        let query = super::query(0x4321, host(), DnsQType::A, false, ArrayVec::<u8, 512>::new()).unwrap();
+        assert!(responder.handle(0, &query, &[192, 168, 1, 2], &mut buffer).unwrap());
+        let message: DnsMessage<8, 0, _> = DnsMessage::new(buffer.as_slice()).unwrap();
+        let header = message.header().unwrap();
+        assert_eq!(header.id(), 0x4321);
+        assert_eq!(header.kind(), DnsHeaderKind::Response);
+        assert!(header.llmnr().tentative());
+        assert!(!header.recursion_available());
+        assert_eq!(header.question_count(), 1);
+        assert_eq!(header.answer_count(), 1);
+
+        let mut responder = running(&records);
+        assert!(responder.handle(0, &query, &[192, 168, 1, 2], &mut buffer).unwrap());
+        let message: DnsMessage<8, 0, _> = DnsMessage::new(buffer.as_slice()).unwrap();
+        let header = message.header().unwrap();
+        assert!(!header.llmnr().tentative());
+        let message = message.questions().complete().unwrap();
+        let mut answers = message.answers();
+        let answer = answers.iter().unwrap().next().unwrap().unwrap().into_parsed().unwrap();
+        assert_eq!(answer, records[0]);
+
+        let query = super::query(0x4321, host(), DnsQType::ALL, false, ArrayVec::<u8, 512>::new()).unwrap();
+        assert!(responder.handle(0, &query, &[192, 168, 1, 2], &mut buffer).unwrap());
+        let message: DnsMessage<8, 0, _> = DnsMessage::new(buffer.as_slice()).unwrap();
+        assert_eq!(message.header().unwrap().answer_count(), 2);
+
+        let query = super::query(0x4321, host(), DnsQType::MX, false, ArrayVec::<u8, 512>::new()).unwrap();
+        assert!(!responder.handle(0, &query, &[192, 168, 1, 2], &mut buffer).unwrap());
+        let other = DnsName::new(b"\x05other\x00").unwrap();
+        let query = super::query(0x4321, other, DnsQType::A, false, ArrayVec::<u8, 512>::new()).unwrap();
+        assert!(!responder.handle(0, &query, &[192, 168, 1, 2], &mut buffer).unwrap());
+    }
+
+    #[test]
+    fn invalid_query() {
+        let records = records();
+        let mut responder = running(&records);
+        let mut buffer = ArrayVec::<u8, 512>::new();
+
+        let message: DnsMessage<8, 0, _> = DnsMessage::new_mut(ArrayVec::<u8, 512>::new()).unwrap();
+        let mut questions = message.questions();
+        for qtype in [DnsQType::A, DnsQType::AAAA] {
+            questions.append(DnsQuestion {
+                name: host(),
+                qtype,
+                unicast_response: false,
+                qclass: DnsQClass::IN,
+            }).unwrap();
+        }
+        let query = questions.complete().unwrap().abort().unwrap();
+        assert!(!responder.handle(0, &query, &[192, 168, 1, 2], &mut buffer).unwrap());
+
+        let mut query = super::query(0x4321, host(), DnsQType::A, false, ArrayVec::<u8, 512>::new()).unwrap();
+        query[2] |= 0b0000_1000;
+        assert!(!responder.handle(0, &query, &[192, 168, 1, 2], &mut buffer).unwrap());
+    }
+
+    #[test]
+    fn conflict_query() {
+        let records = records();
+        let mut responder = running(&records);
+        let mut buffer = ArrayVec::<u8, 512>::new();
+
+        let query = super::query(0x4321, host(), DnsQType::A, true, ArrayVec::<u8, 512>::new()).unwrap();
+        assert!(!responder.handle(5000, &query, &[192, 168, 1, 2], &mut buffer).unwrap());
+        assert_eq!(responder.state(), LlmnrState::Verifying(0));
+        assert_eq!(responder.timeout(), Some(5000));
+    }
+
+    #[test]
+    fn conflict_response() {
+        let records = records();
+        let mut buffer = ArrayVec::<u8, 512>::new();
+
+        let mut responder = LlmnrResponder::new(host(), &records, &ADDRESS, 0x1234, 0);
+        responder.poll(0, &mut buffer).unwrap();
+        // Responses to other queries are ignored.
+        responder.handle(0, &response(0x4321, false), &[192, 168, 1, 21], &mut buffer).unwrap();
+        assert_eq!(responder.state(), LlmnrState::Verifying(1));
+        responder.handle(0, &response(0x1234, false), &[192, 168, 1, 21], &mut buffer).unwrap();
+        assert_eq!(responder.state(), LlmnrState::Conflict);
+        assert_eq!(responder.timeout(), None);
+
+        // The responder with the lower address keeps the name.
+        let mut responder = LlmnrResponder::new(host(), &records, &ADDRESS, 0x1234, 0);
+        responder.poll(0, &mut buffer).unwrap();
+        responder.handle(0, &response(0x1234, true), &[192, 168, 1, 21], &mut buffer).unwrap();
+        assert_eq!(responder.state(), LlmnrState::Verifying(1));
+        responder.handle(0, &response(0x1234, true), &[192, 168, 1, 19], &mut buffer).unwrap();
+        assert_eq!(responder.state(), LlmnrState::Conflict);
+    }
+}
@@ -0,0 +1,1034 @@
+use core::cmp::Ordering;
+use crate::{Buffer, BufferError, DnsError, DnsMessage, DnsMessageError, MutBuffer};
+use crate::answer::DnsAnswer;
+use crate::header::{DnsHeaderKind, DnsHeaderOpcode, DnsHeaderResponseCode};
+use crate::name::DnsName;
+use crate::name_servers::NameServer;
+use crate::question::{DnsQClass, DnsQType, DnsQuestion};
+use crate::rdata::DnsAType;
+use crate::write::WriteBytes;
+
+// The times are in milliseconds.
+// According to RFC 6762 Sections 8.1 to 8.3.
+const PROBE_DELAY: u64 = 250;
+const PROBE_COUNT: u8 = 3;
+const PROBE_DEFER: u64 = 1000;
+const ANNOUNCE_DELAY: u64 = 1000;
+const ANNOUNCE_COUNT: u8 = 2;
+// According to RFC 6762 Section 6.
+const RESPONSE_DELAY_MIN: u64 = 20;
+const RESPONSE_DELAY_MAX: u64 = 120;
+// The records which can be answered in a delayed response.
+const MAX_DELAYED_RECORDS: usize = 64;
+// The records of a name which are compared in a tie break.
+const MAX_TIE_BREAK_RECORDS: usize = 16;
+const PTR_STORAGE: usize = 16;
+
+/// The state of an mDNS responder.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MdnsState {
+    /// The names of the unique records are probed, with the number of probes
+    /// sent.
+    Probing(u8),
+    /// The records are announced, with the number of announcements sent.
+    /// Queries are answered.
+    Announcing(u8),
+    /// Queries are answered.
+    Running,
+    /// Another host uses the name of a unique record. The records must be
+    /// renamed and the responder restarted.
+    Conflict,
+    /// The goodbye was sent.
+    Stopped,
+}
+
+/// Where to send a message written by the responder.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MdnsDestination {
+    /// To the mDNS multicast group, port 5353.
+    Multicast,
+    /// To the address and port the query was received from.
+    Unicast,
+}
+
+/// A sans-IO mDNS responder for a set of records. Records with the
+/// cache-flush bit set are unique to this host and probed before they are
+/// announced, the other records are shared.
+///
+/// The responder does not read the clock or the network. Times are passed in
+/// milliseconds, [`MdnsResponder::poll`] must be called at the time returned
+/// by [`MdnsResponder::timeout`], received packets are passed to
+/// [`MdnsResponder::handle`] and the messages are written to the given
+/// buffer, which is cleared first. Multicast answers with shared records are
+/// delayed and written by [`MdnsResponder::poll`].
+///
+/// According to [RFC 6762](https://tools.ietf.org/rfc/rfc6762).
+pub struct MdnsResponder<'a> {
+    records: &'a [DnsAnswer<'a, DnsAType<'a>>],
+    state: MdnsState,
+    timeout: Option<u64>,
+    // The time of the delayed answer and its records, by index.
+    response_timeout: Option<u64>,
+    response_records: u64,
+    random: u32,
+}
+
+impl<'a> MdnsResponder<'a> {
+    /// Create a new responder, which starts probing after a random delay.
+    /// The seed is used for the random delays.
+    pub fn new(records: &'a [DnsAnswer<'a, DnsAType<'a>>], now: u64, seed: u32) -> Self {
+        let mut responder = Self {
+            records,
+            state: MdnsState::Probing(0),
+            timeout: None,
+            response_timeout: None,
+            response_records: 0,
+            // Xorshift does not work with a state of 0.
+            random: seed | 1,
+        };
+        responder.restart(records, now);
+
+        responder
+    }
+
+    /// Restart probing with the given records, e.g. after they have been
+    /// renamed because of a conflict.
+    pub fn restart(&mut self, records: &'a [DnsAnswer<'a, DnsAType<'a>>], now: u64) {
+        self.records = records;
+        self.state = MdnsState::Probing(0);
+        self.timeout = Some(now + self.random() % PROBE_DELAY);
+        self.cancel_response();
+    }
+
+    /// The current state.
+    #[inline(always)]
+    pub fn state(&self) -> MdnsState {
+        self.state
+    }
+
+    /// The time at which [`MdnsResponder::poll`] must be called next, if any.
+    #[inline(always)]
+    pub fn timeout(&self) -> Option<u64> {
+        self.timeout.into_iter().chain(self.response_timeout).min()
+    }
+
+    /// Send the delayed answer, or the next probe or announcement, if it is
+    /// due. Return where to send the message written to the buffer, if any.
+    /// If both are due, this must be called again.
+    pub fn poll<B: MutBuffer + Buffer>(
+        &mut self,
+        now: u64,
+        buffer: &mut B,
+    ) -> Result<Option<MdnsDestination>, DnsMessageError> {
+        if self.response_timeout.is_some_and(|timeout| timeout <= now) {
+            let records = self.response_records;
+            self.cancel_response();
+            self.write_response(buffer, |i, _| records & 1 << i != 0, false)?;
+
+            return Ok(Some(MdnsDestination::Multicast));
+        }
+        if !self.timeout.is_some_and(|timeout| timeout <= now) {
+            return Ok(None);
+        }
+
+        let has_unique = self.records.iter().any(|record| record.cache_flush);
+        match self.state {
+            MdnsState::Probing(count) if count < PROBE_COUNT && has_unique => {
+                // The first probe asks for a unicast response.
+                self.write_probe(count == 0, buffer)?;
+                self.state = MdnsState::Probing(count + 1);
+                self.timeout = Some(now + PROBE_DELAY);
+            }
+            MdnsState::Probing(_) | MdnsState::Announcing(_) => {
+                let count = match self.state {
+                    MdnsState::Announcing(count) => count + 1,
+                    _ => 1,
+                };
+                self.write_response(buffer, |_, _| true, false)?;
+                if count < ANNOUNCE_COUNT {
+                    self.state = MdnsState::Announcing(count);
+                    self.timeout = Some(now + (ANNOUNCE_DELAY << (count - 1)));
+                } else {
+                    self.state = MdnsState::Running;
+                    self.timeout = None;
+                }
+            }
+            _ => {
+                self.timeout = None;
+                return Ok(None);
+            }
+        }
+
+        Ok(Some(MdnsDestination::Multicast))
+    }
+
+    /// Handle a received packet. Conflicts with the records of other hosts
+    /// are detected and queries are answered, the answer is written to the
+    /// buffer unless it is delayed. Return where to send the answer, if any.
+    pub fn handle<B: MutBuffer + Buffer>(
+        &mut self,
+        now: u64,
+        packet: &[u8],
+        buffer: &mut B,
+    ) -> Result<Option<MdnsDestination>, DnsMessageError> {
+        let message: DnsMessage<0, 0, _> = DnsMessage::new(packet)?;
+        let header = message.header()?;
+        // Messages with another opcode or a response code are ignored.
+        // According to RFC 6762 Sections 18.3 and 18.11.
+        if header.opcode() != DnsHeaderOpcode::Query
+            || header.response_code() != DnsHeaderResponseCode::NoError {
+            return Ok(None);
+        }
+
+        match (header.kind(), self.state) {
+            (DnsHeaderKind::Response, MdnsState::Probing(_)) => {
+                if self.conflicts(packet, true)? {
+                    self.state = MdnsState::Conflict;
+                    self.timeout = None;
+                    self.cancel_response();
+                }
+
+                Ok(None)
+            }
+            (DnsHeaderKind::Response, MdnsState::Announcing(_) | MdnsState::Running) => {
+                // The names must be probed again.
+                // According to RFC 6762 Section 9.
+                if self.conflicts(packet, false)? {
+                    self.state = MdnsState::Probing(0);
+                    self.timeout = Some(now);
+                    self.cancel_response();
+                }
+
+                Ok(None)
+            }
+            (DnsHeaderKind::Query, MdnsState::Probing(_)) => {
+                if self.lost_tie_break(packet, buffer)? {
+                    self.state = MdnsState::Probing(0);
+                    self.timeout = Some(now + PROBE_DEFER);
+                }
+
+                Ok(None)
+            }
+            (DnsHeaderKind::Query, MdnsState::Announcing(_) | MdnsState::Running) => {
+                self.answer(now, packet, buffer)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Write the goodbye, the records with a TTL of 0, and stop the
+    /// responder. Return where to send the message, if the records have
+    /// been announced.
+    pub fn goodbye<B: MutBuffer + Buffer>(
+        &mut self,
+        buffer: &mut B,
+    ) -> Result<Option<MdnsDestination>, DnsMessageError> {
+        let announced = matches!(self.state, MdnsState::Announcing(_) | MdnsState::Running);
+        self.state = MdnsState::Stopped;
+        self.timeout = None;
+        self.cancel_response();
+        if !announced {
+            return Ok(None);
+        }
+
+        self.write_response(buffer, |_, _| true, true)?;
+
+        Ok(Some(MdnsDestination::Multicast))
+    }
+
+    // Xorshift, the delays do not need to be unpredictable.
+    fn random(&mut self) -> u64 {
+        self.random ^= self.random << 13;
+        self.random ^= self.random >> 17;
+        self.random ^= self.random << 5;
+
+        self.random as u64
+    }
+
+    #[inline(always)]
+    fn cancel_response(&mut self) {
+        self.response_timeout = None;
+        self.response_records = 0;
+    }
+
+    // The unique records with a name not used by an earlier unique record.
+    fn unique_names(&self) -> impl Iterator<Item = &DnsAnswer<'a, DnsAType<'a>>> {
+        self.records.iter().enumerate()
+            .filter(|(_, record)| record.cache_flush)
+            .filter(|(i, record)| !self.records[..*i].iter()
+                .any(|earlier| earlier.cache_flush && earlier.name == record.name))
+            .map(|(_, record)| record)
+    }
+
+    // A query for all records of the unique names, with the proposed records
+    // in the authority section.
+    //
+    // According to RFC 6762 Section 8.1.
+    fn write_probe<B: MutBuffer + Buffer>(&self, unicast_response: bool, buffer: &mut B) -> Result<(), DnsMessageError> {
+        buffer.truncate(0)?;
+        let message: DnsMessage<PTR_STORAGE, 0, _> = DnsMessage::new_mut(&mut *buffer)?;
+        let mut questions = message.questions();
+        for record in self.unique_names() {
+            questions.append(DnsQuestion {
+                name: record.name,
+                qtype: DnsQType::ALL,
+                unicast_response,
+                qclass: DnsQClass::from_id(record.aclass.id()),
+            })?;
+        }
+        let message = questions.complete()?;
+        let message = message.answers().complete()?;
+        let mut name_servers = message.name_servers();
+        for record in self.records.iter().filter(|record| record.cache_flush) {
+            name_servers.append(NameServer {
+                name: record.name,
+                rdata: record.rdata,
+                cache_flush: false,
+                aclass: record.aclass,
+                ttl: record.ttl,
+            })?;
+        }
+
+        Ok(())
+    }
+
+    // A response with the records passing the filter, with a TTL of 0 for a
+    // goodbye.
+    fn write_response<B: MutBuffer + Buffer, F: FnMut(usize, &DnsAnswer<DnsAType>) -> bool>(
+        &self,
+        buffer: &mut B,
+        mut filter: F,
+        goodbye: bool,
+    ) -> Result<(), DnsMessageError> {
+        buffer.truncate(0)?;
+        let mut message: DnsMessage<PTR_STORAGE, 0, _> = DnsMessage::new_mut(&mut *buffer)?;
+        let header = message.header_mut()?;
+        header.set_kind(DnsHeaderKind::Response);
+        header.set_authoritative_answer(true);
+        let message = message.questions().complete()?;
+        let mut answers = message.answers();
+        for (_, record) in self.records.iter().enumerate().filter(|(i, record)| filter(*i, record)) {
+            answers.append(DnsAnswer {
+                name: record.name,
+                rdata: record.rdata,
+                cache_flush: record.cache_flush,
+                aclass: record.aclass,
+                ttl: if goodbye { 0 } else { record.ttl },
+            })?;
+        }
+
+        Ok(())
+    }
+
+    // Return whether the answers or additionals of a response contain a
+    // record with the name of a unique record, while probing of any type and
+    // afterwards of the same type and class with other data.
+    //
+    // According to RFC 6762 Sections 8.1 and 9.
+    fn conflicts(&self, packet: &[u8], probing: bool) -> Result<bool, DnsMessageError> {
+        let conflicts = |name: &DnsName, rdata: &DnsAType, aclass| self.records.iter()
+            .filter(|record| record.cache_flush && record.name == *name)
+            .any(|record| probing || (record.rdata.id() == rdata.id()
+                && record.aclass == aclass
+                && record.rdata != *rdata));
+
+        let message: DnsMessage<0, 0, _> = DnsMessage::new(packet)?;
+        let message = message.questions().complete()?;
+        let mut answers = message.answers();
+        for answer in answers.iter()? {
+            let answer = answer?.into_parsed()?;
+            if conflicts(&answer.name, &answer.rdata, answer.aclass) {
+                return Ok(true);
+            }
+        }
+        let message = answers.complete()?;
+        let message = message.name_servers().complete()?;
+        let mut additionals = message.additionals();
+        for additional in additionals.iter()? {
+            let additional = additional?.into_parsed()?;
+            if conflicts(&additional.name, &additional.rdata, additional.aclass) {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    // Return whether a simultaneous probe of another host for a unique name
+    // wins the tie break. The records of both hosts for the name are sorted
+    // and compared pairwise, the host with the lexicographically later
+    // records wins.
+    //
+    // According to RFC 6762 Section 8.2.
+    fn lost_tie_break<B: MutBuffer + Buffer>(&self, packet: &[u8], buffer: &mut B) -> Result<bool, DnsMessageError> {
+        for unique in self.unique_names() {
+            let message: DnsMessage<0, 0, _> = DnsMessage::new(packet)?;
+            let message = message.questions().complete()?;
+            let message = message.answers().complete()?;
+            let mut name_servers = message.name_servers();
+
+            let mut theirs = RecordSet::new();
+            for name_server in name_servers.iter()? {
+                let name_server = name_server?.into_parsed()?;
+                if name_server.name == unique.name {
+                    theirs.insert(name_server.aclass.id(), name_server.rdata, buffer)?;
+                }
+            }
+            if theirs.len == 0 {
+                continue;
+            }
+
+            let mut ours = RecordSet::new();
+            for record in self.records.iter().filter(|record| record.cache_flush && record.name == unique.name) {
+                ours.insert(record.aclass.id(), record.rdata, buffer)?;
+            }
+
+            if ours.compare(&theirs, buffer)? == Ordering::Less {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    // Answer the questions of a query with the matching records, without
+    // the records the querier already knows with at least half of their TTL.
+    // A multicast answer with shared records is delayed by a random time,
+    // as other hosts may answer as well, and merged with the answer already
+    // delayed, if any.
+    //
+    // According to RFC 6762 Sections 6 and 7.1.
+    fn answer<B: MutBuffer + Buffer>(
+        &mut self,
+        now: u64,
+        packet: &[u8],
+        buffer: &mut B,
+    ) -> Result<Option<MdnsDestination>, DnsMessageError> {
+        let mut records = 0u64;
+        let mut any = false;
+        let mut unicast = true;
+        let mut shared = false;
+        let mut delayable = true;
+        for (i, record) in self.records.iter().enumerate() {
+            let (matches, unicast_response) = question_matches(packet, record)?;
+            if matches && !known_answer(packet, record)? {
+                any = true;
+                unicast &= unicast_response;
+                shared |= !record.cache_flush;
+                if i < MAX_DELAYED_RECORDS {
+                    records |= 1 << i;
+                } else {
+                    delayable = false;
+                }
+            }
+        }
+        if !any {
+            return Ok(None);
+        }
+
+        if !unicast && shared && delayable {
+            let delay = RESPONSE_DELAY_MIN + self.random() % (RESPONSE_DELAY_MAX - RESPONSE_DELAY_MIN + 1);
+            let timeout = self.response_timeout.map_or(now + delay, |timeout| timeout.min(now + delay));
+            self.response_timeout = Some(timeout);
+            self.response_records |= records;
+
+            return Ok(None);
+        }
+
+        let mut error = None;
+        self.write_response(buffer, |_, record| {
+            match (question_matches(packet, record), known_answer(packet, record)) {
+                (Ok((matches, _)), Ok(known)) => matches && !known,
+                (Err(e), _) | (_, Err(e)) => {
+                    error = Some(e);
+                    false
+                }
+            }
+        }, false)?;
+        if let Some(error) = error {
+            return Err(error);
+        }
+
+        if unicast {
+            Ok(Some(MdnsDestination::Unicast))
+        } else {
+            Ok(Some(MdnsDestination::Multicast))
+        }
+    }
+}
+
+// Return whether a question of the query matches the record, and whether
+// all matching questions ask for a unicast response.
+fn question_matches(packet: &[u8], record: &DnsAnswer<DnsAType>) -> Result<(bool, bool), DnsMessageError> {
+    let mut matches = false;
+    let mut unicast_response = true;
+
+    let message: DnsMessage<0, 0, _> = DnsMessage::new(packet)?;
+    let mut questions = message.questions();
+    for question in questions.iter()? {
+        let question = question?;
+        if question.name == record.name
+            && (question.qtype == DnsQType::ALL || question.qtype.id() == record.rdata.id())
+            && (question.qclass == DnsQClass::ANY || question.qclass.id() == record.aclass.id()) {
+            matches = true;
+            unicast_response &= question.unicast_response;
+        }
+    }
+
+    Ok((matches, unicast_response))
+}
+
+// Return whether the answers of the query contain the record with at least
+// half of its TTL.
+fn known_answer(packet: &[u8], record: &DnsAnswer<DnsAType>) -> Result<bool, DnsMessageError> {
+    let message: DnsMessage<0, 0, _> = DnsMessage::new(packet)?;
+    let message = message.questions().complete()?;
+    let mut answers = message.answers();
+    for answer in answers.iter()? {
+        let answer = answer?.into_parsed()?;
+        if answer.name == record.name
+            && answer.aclass == record.aclass
+            && answer.rdata == record.rdata
+            && answer.ttl >= record.ttl / 2 {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+/// Rename the first label of the name for a conflict, by adding `-2` or
+/// incrementing its number, e.g. `esp32.local` becomes `esp32-2.local` and
+/// then `esp32-3.local`. The new name is written to the buffer.
+///
+/// According to [RFC 6762 Section 9](https://tools.ietf.org/rfc/rfc6762#section-9).
+pub fn rename<'b>(name: &DnsName, buffer: &'b mut [u8]) -> Result<DnsName<'b>, DnsMessageError> {
+    const MAX_LABEL_LENGTH: usize = 63;
+
+    let mut labels = name.iter();
+    // The root has no label to rename.
+    let first = labels.next()
+        .ok_or(DnsMessageError::DnsError(DnsError::InvalidRRSet))??;
+
+    // Split a number from the end of the label, if it follows a hyphen.
+    let digits = first.iter().rev().take_while(|c| c.is_ascii_digit()).count();
+    let (base, number) = match first.len().checked_sub(digits + 1) {
+        Some(hyphen) if digits > 0 && digits < 10 && first[hyphen] == b'-' => {
+            let number = first[hyphen + 1..].iter()
+                .fold(0u64, |number, c| number * 10 + (c - b'0') as u64);
+            (&first[..hyphen], number + 1)
+        }
+        _ => (first, 2),
+    };
+
+    let mut suffix = [0; 21];
+    suffix[0] = b'-';
+    let mut length = 1;
+    let mut divisor = 1;
+    while number / divisor >= 10 {
+        divisor *= 10;
+    }
+    while divisor > 0 {
+        suffix[length] = b'0' + (number / divisor % 10) as u8;
+        length += 1;
+        divisor /= 10;
+    }
+    let suffix = &suffix[..length];
+
+    let label_length = base.len() + suffix.len();
+    if label_length > MAX_LABEL_LENGTH {
+        return Err(DnsMessageError::DnsError(DnsError::LabelTooLong));
+    }
+
+    let mut writer = Writer { buffer, position: 0 };
+    writer.write(&[label_length as u8])?;
+    writer.write(base)?;
+    writer.write(suffix)?;
+    for label in labels {
+        let label = label?;
+        writer.write(&[label.len() as u8])?;
+        writer.write(label)?;
+    }
+    writer.write(&[0])?;
+    let length = writer.position;
+
+    DnsName::new(&buffer[..length])
+}
+
+struct Writer<'b> {
+    buffer: &'b mut [u8],
+    position: usize,
+}
+
+impl Writer<'_> {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), DnsMessageError> {
+        self.buffer.get_mut(self.position..self.position + bytes.len())
+            .ok_or(DnsMessageError::BufferError(BufferError::OutOfMemory))?
+            .copy_from_slice(bytes);
+        self.position += bytes.len();
+
+        Ok(())
+    }
+}
+
+// A record in a tie break, with its class without the cache-flush bit.
+#[derive(Copy, Clone)]
+struct Record<'r> {
+    aclass: u16,
+    rdata: DnsAType<'r>,
+}
+
+impl Record<'_> {
+    // Compare the class, the type and the uncompressed record data. The
+    // record data is written to the end of the buffer and removed
+    // afterwards.
+    fn compare<B: MutBuffer + Buffer>(&self, other: &Record, buffer: &mut B) -> Result<Ordering, DnsMessageError> {
+        let ordering = self.aclass.cmp(&other.aclass)
+            .then(self.rdata.id().cmp(&other.rdata.id()));
+        if ordering != Ordering::Equal {
+            return Ok(ordering);
+        }
+
+        let start = buffer.len();
+        let ordering = self.rdata.write(&mut DnsMessage::raw(&mut *buffer)).and_then(|length| {
+            let other_length = other.rdata.write(&mut DnsMessage::raw(&mut *buffer))?;
+            Ok(buffer.read_bytes_at(start, length)?
+                .cmp(buffer.read_bytes_at(start + length, other_length)?))
+        });
+        // The buffer is restored even if the record data did not fit.
+        buffer.truncate(start)?;
+
+        ordering
+    }
+}
+
+// The records of a host for a name, in lexicographical order. Only the
+// earliest records are kept, the others are only counted.
+struct RecordSet<'r> {
+    records: [Option<Record<'r>>; MAX_TIE_BREAK_RECORDS],
+    len: usize,
+    count: usize,
+}
+
+impl<'r> RecordSet<'r> {
+    fn new() -> Self {
+        Self {
+            records: [None; MAX_TIE_BREAK_RECORDS],
+            len: 0,
+            count: 0,
+        }
+    }
+
+    fn insert<B: MutBuffer + Buffer>(&mut self, aclass: u16, rdata: DnsAType<'r>, buffer: &mut B) -> Result<(), DnsMessageError> {
+        let record = Record { aclass, rdata };
+        self.count += 1;
+        let mut i = self.len;
+        if let Some(last) = self.records[MAX_TIE_BREAK_RECORDS - 1] {
+            // The set is full, the record replaces the last one if it is
+            // earlier.
+            if last.compare(&record, buffer)? != Ordering::Greater {
+                return Ok(());
+            }
+            i -= 1;
+        } else {
+            self.len += 1;
+        }
+        while let Some(earlier) = i.checked_sub(1).and_then(|i| self.records[i]) {
+            if earlier.compare(&record, buffer)? != Ordering::Greater {
+                break;
+            }
+            self.records[i] = Some(earlier);
+            i -= 1;
+        }
+        self.records[i] = Some(record);
+
+        Ok(())
+    }
+
+    // Compare the records pairwise, a set which runs out first is earlier. If
+    // the kept records are equal, the set with fewer records is earlier.
+    fn compare<B: MutBuffer + Buffer>(&self, other: &RecordSet, buffer: &mut B) -> Result<Ordering, DnsMessageError> {
+        let mut records = self.records[..self.len].iter().flatten();
+        let mut other_records = other.records[..other.len].iter().flatten();
+        loop {
+            match (records.next(), other_records.next()) {
+                (None, None) => return Ok(self.count.cmp(&other.count)),
+                (None, Some(_)) => return Ok(Ordering::Less),
+                (Some(_), None) => return Ok(Ordering::Greater),
+                (Some(a), Some(b)) => match a.compare(b, buffer)? {
+                    Ordering::Equal => {}
+                    ordering => return Ok(ordering),
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use arrayvec::ArrayVec;
+    use crate::answer::DnsAClass;
+    use crate::rdata::{A, Ptr};
+    use super::*;
+
+    fn host() -> DnsName<'static> {
+        DnsName::new(b"\x04host\x05local\x00").unwrap()
+    }
+
+    fn address(address: [u8; 4]) -> DnsAnswer<'static, DnsAType<'static>> {
+        DnsAnswer {
+            name: host(),
+            rdata: DnsAType::A(A { address }),
+            cache_flush: true,
+            aclass: DnsAClass::IN,
+            ttl: 120,
+        }
+    }
+
+    fn records() -> [DnsAnswer<'static, DnsAType<'static>>; 2] {
+        [
+            address([10, 0, 0, 2]),
+            DnsAnswer {
+                name: DnsName::new(b"\x05_http\x04_tcp\x05local\x00").unwrap(),
+                rdata: DnsAType::Ptr(Ptr { name: host() }),
+                cache_flush: false,
+                aclass: DnsAClass::IN,
+                ttl: 4500,
+            },
+        ]
+    }
+
+    fn packet(
+        kind: DnsHeaderKind,
+        question: Option<(DnsQType, bool)>,
+        answer: Option<DnsAnswer<DnsAType>>,
+        authority: Option<DnsAnswer<DnsAType>>,
+    ) -> ArrayVec<u8, 512> {
+        let mut message: DnsMessage<8, 0, _> = DnsMessage::new_mut(ArrayVec::<u8, 512>::new()).unwrap();
+        message.header_mut().unwrap().set_kind(kind);
+        let mut questions = message.questions();
+        if let Some((qtype, unicast_response)) = question {
+            questions.append(DnsQuestion {
+                name: host(),
+                qtype,
+                unicast_response,
+                qclass: DnsQClass::IN,
+            }).unwrap();
+        }
+        let message = questions.complete().unwrap();
+        let mut answers = message.answers();
+        if let Some(answer) = answer {
+            answers.append(answer).unwrap();
+        }
+        let message = answers.complete().unwrap();
+        let mut name_servers = message.name_servers();
+        if let Some(authority) = authority {
+            name_servers.append(NameServer {
+                name: authority.name,
+                rdata: authority.rdata,
+                cache_flush: false,
+                aclass: authority.aclass,
+                ttl: authority.ttl,
+            }).unwrap();
+        }
+
+        name_servers.complete().unwrap().abort().unwrap()
+    }
+
+    fn running<'a>(records: &'a [DnsAnswer<'a, DnsAType<'a>>]) -> MdnsResponder<'a> {
+        let mut responder = MdnsResponder::new(records, 0, 1);
+        let mut buffer = ArrayVec::<u8, 512>::new();
+        while let Some(timeout) = responder.timeout() {
+            responder.poll(timeout, &mut buffer).unwrap();
+        }
+        assert_eq!(responder.state(), MdnsState::Running);
+
+        responder
+    }
+
+    fn assert_answers(bytes: &[u8], expected: &[DnsAnswer<DnsAType>]) {
+        let message: DnsMessage<8, 0, _> = DnsMessage::new(bytes).unwrap();
+        let message = message.questions().complete().unwrap();
+        let mut answers = message.answers();
+        let mut count = 0;
+        for (answer, expected) in answers.iter().unwrap().zip(expected) {
+            assert_eq!(&answer.unwrap().into_parsed().unwrap(), expected);
+            count += 1;
+        }
+        assert_eq!(count, expected.len());
+    }
+
+    #[test]
+    fn probe_and_announce() {
+        let records = records();
+        let mut responder = MdnsResponder::new(&records, 1000, 7);
+        let mut buffer = ArrayVec::<u8, 512>::new();
+        let timeout = responder.timeout().unwrap();
+        assert!((1000..1250).contains(&timeout));
+        assert_eq!(responder.poll(timeout - 1, &mut buffer).unwrap(), None);
+
+        for count in 1..=3 {
+            let now = responder.timeout().unwrap();
+            assert_eq!(responder.poll(now, &mut buffer).unwrap(), Some(MdnsDestination::Multicast));
+            assert_eq!(responder.state(), MdnsState::Probing(count));
+            assert_eq!(responder.timeout(), Some(now + 250));
+
+            let message: DnsMessage<8, 0, _> = DnsMessage::new(buffer.as_slice()).unwrap();
+            let header = message.header().unwrap();
+            assert_eq!(header.kind(), DnsHeaderKind::Query);
+            assert_eq!(header.name_server_count(), 1);
+            let mut questions = message.questions();
+            let question = questions.iter().unwrap().next().unwrap().unwrap();
+            assert_eq!(question.name, host());
+            assert_eq!(question.qtype, DnsQType::ALL);
+            assert_eq!(question.unicast_response, count == 1);
+        }
+
+        let now = responder.timeout().unwrap();
+        assert_eq!(responder.poll(now, &mut buffer).unwrap(), Some(MdnsDestination::Multicast));
+        assert_eq!(responder.state(), MdnsState::Announcing(1));
+        assert_eq!(responder.timeout(), Some(now + 1000));
+        let message: DnsMessage<8, 0, _> = DnsMessage::new(buffer.as_slice()).unwrap();
+        let header = message.header().unwrap();
+        assert_eq!(header.kind(), DnsHeaderKind::Response);
+        assert!(header.authoritative_answer());
+        assert_eq!(header.id(), 0);
+        assert_answers(&buffer, &records);
+
+        assert_eq!(responder.poll(now + 1000, &mut buffer).unwrap(), Some(MdnsDestination::Multicast));
+        assert_eq!(responder.state(), MdnsState::Running);
+        assert_eq!(responder.timeout(), None);
+    }
+
+    #[test]
+    fn shared_only() {
+        let records = &records()[1..];
+        let mut responder = MdnsResponder::new(records, 0, 7);
+        let mut buffer = ArrayVec::<u8, 512>::new();
+        let now = responder.timeout().unwrap();
+        responder.poll(now, &mut buffer).unwrap();
+        assert_eq!(responder.state(), MdnsState::Announcing(1));
+    }
+
+    #[test]
+    fn answer() {
+        let records = records();
+        let mut responder = running(&records);
+        let mut buffer = ArrayVec::<u8, 512>::new();
+
+        let query = packet(DnsHeaderKind::Query, Some((DnsQType::A, false)), None, None);
+        assert_eq!(responder.handle(0, &query, &mut buffer).unwrap(), Some(MdnsDestination::Multicast));
+        assert_answers(&buffer, &records[..1]);
+
+        let query = packet(DnsHeaderKind::Query, Some((DnsQType::ALL, true)), None, None);
+        assert_eq!(responder.handle(0, &query, &mut buffer).unwrap(), Some(MdnsDestination::Unicast));
+        assert_answers(&buffer, &records[..1]);
+
+        let query = packet(DnsHeaderKind::Query, Some((DnsQType::AAAA, false)), None, None);
+        assert_eq!(responder.handle(0, &query, &mut buffer).unwrap(), None);
+    }
+
+    #[test]
+    fn delayed_answer() {
+        let records = records();
+        let mut responder = running(&records);
+        let mut buffer = ArrayVec::<u8, 512>::new();
+        let query = |unicast_response| {
+            let message: DnsMessage<8, 0, _> = DnsMessage::new_mut(ArrayVec::<u8, 512>::new()).unwrap();
+            let mut questions = message.questions();
+            questions.append(DnsQuestion {
+                name: records[1].name,
+                qtype: DnsQType::PTR,
+                unicast_response,
+                qclass: DnsQClass::IN,
+            }).unwrap();
+
+            questions.complete().unwrap().abort().unwrap()
+        };
+
+        // Other hosts may answer for the shared record as well.
+        assert_eq!(responder.handle(1000, &query(false), &mut buffer).unwrap(), None);
+        let timeout = responder.timeout().unwrap();
+        assert!((1020..=1120).contains(&timeout));
+        assert_eq!(responder.poll(timeout - 1, &mut buffer).unwrap(), None);
+
+        // A second query is merged into the delayed answer.
+        let a = packet(DnsHeaderKind::Query, Some((DnsQType::A, false)), None, None);
+        assert_eq!(responder.handle(1010, &a, &mut buffer).unwrap(), Some(MdnsDestination::Multicast));
+        assert_eq!(responder.handle(1010, &query(false), &mut buffer).unwrap(), None);
+        assert_eq!(responder.timeout(), Some(timeout));
+
+        assert_eq!(responder.poll(timeout, &mut buffer).unwrap(), Some(MdnsDestination::Multicast));
+        assert_answers(&buffer, &records[1..]);
+        assert_eq!(responder.timeout(), None);
+
+        // A unicast answer is not delayed.
+        assert_eq!(responder.handle(2000, &query(true), &mut buffer).unwrap(), Some(MdnsDestination::Unicast));
+        assert_answers(&buffer, &records[1..]);
+        assert_eq!(responder.timeout(), None);
+    }
+
+    #[test]
+    fn known_answer_suppression() {
+        let records = records();
+        let mut responder = running(&records);
+        let mut buffer = ArrayVec::<u8, 512>::new();
+
+        let known = DnsAnswer { ttl: 60, ..address([10, 0, 0, 2]) };
+        let query = packet(DnsHeaderKind::Query, Some((DnsQType::A, false)), Some(known), None);
+        assert_eq!(responder.handle(0, &query, &mut buffer).unwrap(), None);
+
+        // Less than half of the TTL is left.
+        let known = DnsAnswer { ttl: 59, ..address([10, 0, 0, 2]) };
+        let query = packet(DnsHeaderKind::Query, Some((DnsQType::A, false)), Some(known), None);
+        assert_eq!(responder.handle(0, &query, &mut buffer).unwrap(), Some(MdnsDestination::Multicast));
+    }
+
+    #[test]
+    fn conflict() {
+        let records = records();
+        let mut responder = MdnsResponder::new(&records, 0, 7);
+        let mut buffer = ArrayVec::<u8, 512>::new();
+        let response = packet(DnsHeaderKind::Response, None, Some(address([10, 0, 0, 9])), None);
+        responder.handle(0, &response, &mut buffer).unwrap();
+        assert_eq!(responder.state(), MdnsState::Conflict);
+        assert_eq!(responder.timeout(), None);
+
+        let mut responder = running(&records);
+        let response = packet(DnsHeaderKind::Response, None, Some(address([10, 0, 0, 2])), None);
+        responder.handle(5000, &response, &mut buffer).unwrap();
+        assert_eq!(responder.state(), MdnsState::Running);
+
+        let response = packet(DnsHeaderKind::Response, None, Some(address([10, 0, 0, 9])), None);
+        responder.handle(5000, &response, &mut buffer).unwrap();
+        assert_eq!(responder.state(), MdnsState::Probing(0));
+        assert_eq!(responder.timeout(), Some(5000));
+    }
+
+    #[test]
+    fn tie_break() {
+        let records = records();
+        let mut responder = MdnsResponder::new(&records, 0, 7);
+        let mut buffer = ArrayVec::<u8, 512>::new();
+        let timeout = responder.timeout();
+
+        let probe = packet(DnsHeaderKind::Query, Some((DnsQType::ALL, true)), None, Some(address([10, 0, 0, 1])));
+        responder.handle(100, &probe, &mut buffer).unwrap();
+        assert_eq!(responder.state(), MdnsState::Probing(0));
+        assert_eq!(responder.timeout(), timeout);
+
+        let probe = packet(DnsHeaderKind::Query, Some((DnsQType::ALL, true)), None, Some(address([10, 0, 0, 3])));
+        responder.handle(100, &probe, &mut buffer).unwrap();
+        assert_eq!(responder.state(), MdnsState::Probing(0));
+        assert_eq!(responder.timeout(), Some(1100));
+        assert!(buffer.is_empty());
+
+        // Our records run out later.
+        let records = [address([10, 0, 0, 2]), address([10, 0, 0, 1])];
+        let mut responder = MdnsResponder::new(&records, 0, 7);
+        let timeout = responder.timeout();
+        let probe = packet(DnsHeaderKind::Query, Some((DnsQType::ALL, true)), None, Some(address([10, 0, 0, 1])));
+        responder.handle(100, &probe, &mut buffer).unwrap();
+        assert_eq!(responder.timeout(), timeout);
+    }
+
+    #[test]
+    fn tie_break_many_records() {
+        let records = records();
+        let mut responder = MdnsResponder::new(&records, 0, 7);
+        let mut buffer = ArrayVec::<u8, 512>::new();
+        let probe = |first: u8| {
+            let message: DnsMessage<8, 0, _> = DnsMessage::new_mut(ArrayVec::<u8, 1024>::new()).unwrap();
+            let mut questions = message.questions();
+            questions.append(DnsQuestion {
+                name: host(),
+                qtype: DnsQType::ALL,
+                unicast_response: true,
+                qclass: DnsQClass::IN,
+            }).unwrap();
+            let message = questions.complete().unwrap();
+            let message = message.answers().complete().unwrap();
+            let mut name_servers = message.name_servers();
+            for i in (0..20).rev() {
+                let address = address([10, 0, 0, first + i]);
+                name_servers.append(NameServer {
+                    name: address.name,
+                    rdata: address.rdata,
+                    cache_flush: false,
+                    aclass: address.aclass,
+                    ttl: address.ttl,
+                }).unwrap();
+            }
+
+            name_servers.complete().unwrap().abort().unwrap()
+        };
+
+        // More records than are kept for the tie break.
+        let timeout = responder.timeout();
+        responder.handle(100, &probe(1), &mut buffer).unwrap();
+        assert_eq!(responder.state(), MdnsState::Probing(0));
+        assert_eq!(responder.timeout(), timeout);
+
+        responder.handle(100, &probe(2), &mut buffer).unwrap();
+        assert_eq!(responder.state(), MdnsState::Probing(0));
+        assert_eq!(responder.timeout(), Some(1100));
+    }
+
+    #[test]
+    fn tie_break_buffer_too_small() {
+        let records = records();
+        let mut responder = MdnsResponder::new(&records, 0, 7);
+        // Room for the record data of only one of the compared records.
+        let mut buffer = ArrayVec::<u8, 6>::new();
+        buffer.push(0xFF);
+        let probe = packet(DnsHeaderKind::Query, Some((DnsQType::ALL, true)), None, Some(address([10, 0, 0, 1])));
+        assert_eq!(
+            responder.handle(100, &probe, &mut buffer),
+            Err(DnsMessageError::BufferError(BufferError::LengthOutOfBounds))
+        );
+        assert_eq!(buffer.as_slice(), &[0xFF]);
+    }
+
+    #[test]
+    fn goodbye() {
+        let records = records();
+        let mut buffer = ArrayVec::<u8, 512>::new();
+        let mut responder = MdnsResponder::new(&records, 0, 7);
+        assert_eq!(responder.goodbye(&mut buffer).unwrap(), None);
+        assert_eq!(responder.state(), MdnsState::Stopped);
+
+        let mut responder = running(&records);
+        assert_eq!(responder.goodbye(&mut buffer).unwrap(), Some(MdnsDestination::Multicast));
+        assert_eq!(responder.state(), MdnsState::Stopped);
+        let [a, ptr] = self::records();
+        assert_answers(&buffer, &[DnsAnswer { ttl: 0, ..a }, DnsAnswer { ttl: 0, ..ptr }]);
+    }
+
+    #[test]
+    fn rename() {
+        let mut buffer = [0; 255];
+        let name = super::rename(&host(), &mut buffer).unwrap();
+        assert_eq!(name, DnsName::new(b"\x06host-2\x05local\x00").unwrap());
+
+        let mut other = [0; 255];
+        let name = super::rename(&name, &mut other).unwrap();
+        assert_eq!(name, DnsName::new(b"\x06host-3\x05local\x00").unwrap());
+
+        let name = DnsName::new(b"\x07host-99\x05local\x00").unwrap();
+        let name = super::rename(&name, &mut buffer).unwrap();
+        assert_eq!(name, DnsName::new(b"\x08host-100\x05local\x00").unwrap());
+
+        let name = DnsName::new(b"\x03a-b\x05local\x00").unwrap();
+        let name = super::rename(&name, &mut buffer).unwrap();
+        assert_eq!(name, DnsName::new(b"\x05a-b-2\x05local\x00").unwrap());
+
+        let mut label = [b'a'; 65];
+        label[0] = 62;
+        label[63] = 0;
+        let name = DnsName::new(&label[..64]).unwrap();
+        assert_eq!(
+            super::rename(&name, &mut buffer),
+            Err(DnsMessageError::DnsError(DnsError::LabelTooLong)),
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use core::cmp::Ordering;
 use core::fmt::{Debug, Display, Formatter};
 use core::hash::Hash;
 use crate::{Buffer, DnsError, DnsMessage, DnsMessageError, MutBuffer};
@@ -104,6 +105,125 @@ impl<'a> DnsName<'a> {
         }
     }
 
+    /// Pass the name in canonical form, uncompressed and with its labels in
+    /// lowercase, to the given function, e.g. to hash it.
+    ///
+    /// According to [RFC 4034 Section 6.2](https://tools.ietf.org/rfc/rfc4034#section-6.2).
+    pub(crate) fn canonical<F: FnMut(&[u8])>(&self, mut f: F) -> Result<(), DnsMessageError> {
+        let mut lowercase = [0; MAX_DOMAIN_NAME_LABEL_LENGTH];
+        for label in self.iter() {
+            let label = label?;
+            let lowercase = lowercase.get_mut(..label.len())
+                .ok_or(DnsMessageError::DnsError(DnsError::LabelTooLong))?;
+            lowercase.copy_from_slice(label);
+            lowercase.make_ascii_lowercase();
+            f(&[label.len() as u8]);
+            f(lowercase);
+        }
+        f(&[0]);
+
+        Ok(())
+    }
+
+    // Return the bytes of the name, and of anything following it, to compare
+    // invalid names.
+    #[inline(always)]
+    fn raw_bytes(&self) -> &'a [u8] {
+        &self.bytes[self.offset..]
+    }
+
+    /// Return the number of labels of the name, without the root label.
+    #[inline]
+    pub fn label_count(&self) -> Result<usize, DnsMessageError> {
+        let mut labels = 0;
+        for label in self.iter() {
+            label?;
+            labels += 1;
+        }
+
+        Ok(labels)
+    }
+
+    /// Return whether the name is equal to or below the given name, ignoring
+    /// case. Invalid names are never below any name.
+    pub fn is_subdomain_of(&self, parent: &DnsName) -> bool {
+        let (Ok(labels), Ok(parent_labels)) = (self.label_count(), parent.label_count()) else {
+            return false;
+        };
+        if labels < parent_labels {
+            return false;
+        }
+
+        self.iter()
+            .skip(labels - parent_labels)
+            .zip(parent.iter())
+            .all(|(a, b)| matches!((a, b), (Ok(a), Ok(b)) if a.eq_ignore_ascii_case(b)))
+    }
+
+    /// Return the name without its first label, or `None` for the root name
+    /// and invalid names.
+    pub fn parent(&self) -> Option<Self> {
+        let mut iter = self.iter();
+        iter.next()?.ok()?;
+
+        Some(Self {
+            bytes: self.bytes,
+            offset: iter.offset,
+        })
+    }
+
+    /// Return the longest name both names are equal to or below, ignoring
+    /// case. The returned name is a suffix of this name.
+    pub fn common_suffix(&self, other: &DnsName) -> Result<Self, DnsMessageError> {
+        let mut suffix = *self;
+        for _ in self.common_labels(other)?..self.label_count()? {
+            if let Some(parent) = suffix.parent() {
+                suffix = parent;
+            }
+        }
+
+        Ok(suffix)
+    }
+
+    /// Compare the names in canonical order, ignoring case.
+    ///
+    /// According to [RFC 4034 Section 6.1](https://tools.ietf.org/rfc/rfc4034#section-6.1).
+    pub(crate) fn canonical_cmp(&self, other: &DnsName) -> Result<Ordering, DnsMessageError> {
+        let (labels, other_labels) = (self.label_count()?, other.label_count()?);
+
+        // The labels are compared from the rightmost label.
+        for (i, j) in (0..labels).rev().zip((0..other_labels).rev()) {
+            let (Some(a), Some(b)) = (self.iter().nth(i), other.iter().nth(j)) else {
+                break;
+            };
+            let ordering = a?.iter().map(u8::to_ascii_lowercase)
+                .cmp(b?.iter().map(u8::to_ascii_lowercase));
+            if ordering != Ordering::Equal {
+                return Ok(ordering);
+            }
+        }
+
+        Ok(labels.cmp(&other_labels))
+    }
+
+    // Return the number of rightmost labels both names have in common,
+    // ignoring case.
+    pub(crate) fn common_labels(&self, other: &DnsName) -> Result<usize, DnsMessageError> {
+        let (labels, other_labels) = (self.label_count()?, other.label_count()?);
+        let min = labels.min(other_labels);
+
+        let mut common = 0;
+        for (a, b) in self.iter().skip(labels - min).zip(other.iter().skip(other_labels - min)) {
+            if a?.eq_ignore_ascii_case(b?) {
+                common += 1;
+            } else {
+                common = 0;
+            }
+        }
+
+        Ok(common)
+    }
+
     pub(crate) fn split_first(&self) -> Result<(&'a [u8], Option<Self>), DnsMessageError> {
         let mut iter = self.iter();
         let first = iter.next().unwrap()?;
@@ -139,8 +259,6 @@ impl<'a> ParseBytes<'a> for DnsName<'a> {
                     break;
                 }
                 LabelType::Part(len) => {
-                    j += len as usize;
-
                     if len == 0 {
                         break;
                     }
@@ -148,6 +266,12 @@ impl<'a> ParseBytes<'a> for DnsName<'a> {
                     if len > MAX_DOMAIN_NAME_LABEL_LENGTH as u8 {
                         return Err(DnsMessageError::DnsError(DnsError::LabelTooLong));
                     }
+
+                    j += len as usize;
+
+                    if j > bytes.len() {
+                        return Err(DnsMessageError::DnsError(DnsError::UnexpectedEndOfBuffer));
+                    }
                 }
             }
         }
@@ -202,7 +326,12 @@ impl<'a> Iterator for NameIterator<'a> {
                 return Some(Err(DnsMessageError::DnsError(DnsError::NameTooLong)));
             }
 
-            match LabelType::from_bytes(self.bytes, &mut i).unwrap() {
+            let label = match LabelType::from_bytes(self.bytes, &mut i) {
+                Ok(label) => label,
+                Err(e) => return Some(Err(e)),
+            };
+
+            match label {
                 LabelType::Pointer(ptr) => {
                     if ptr < self.offset as u16 {
                         // The pointer points to an earlier part of the message.
@@ -268,36 +397,58 @@ impl<'a> Iterator for CycleSafeNameIterator<'a> {
     }
 }
 
+/// Names are compared ignoring case. Invalid names are only equal to
+/// invalid names with the same bytes.
 impl PartialEq<DnsName<'_>> for DnsName<'_> {
     fn eq(&self, other: &DnsName<'_>) -> bool {
-        for (a, b) in self.iter().zip(other.iter()) {
-            match (a, b) {
-                (Ok(a), Ok(b)) => {
-                    if a != b {
-                        return false;
-                    }
-                }
-                _ => {
-                    return false;
-                }
-            }
+        match (self.label_count(), other.label_count()) {
+            (Ok(labels), Ok(other_labels)) => labels == other_labels && self.iter()
+                .zip(other.iter())
+                .all(|(a, b)| matches!((a, b), (Ok(a), Ok(b)) if a.eq_ignore_ascii_case(b))),
+            (Err(_), Err(_)) => self.raw_bytes() == other.raw_bytes(),
+            _ => false,
         }
-
-        true
     }
 }
 
+impl Eq for DnsName<'_> {}
+
 impl Hash for DnsName<'_> {
     fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
-        for part in self.iter() {
-            if let Err(_) = part {
-                // If the name is invalid, we cannot hash it.
-                return;
-            }
+        if self.label_count().is_err() {
+            // An invalid name is hashed by its bytes, as it is compared.
+            state.write(self.raw_bytes());
+
+            return;
+        }
 
-            let part = part.unwrap();
+        for part in self.iter().flatten() {
             state.write_u8(part.len() as u8);
-            state.write(part);
+            for byte in part {
+                state.write_u8(byte.to_ascii_lowercase());
+            }
+        }
+    }
+}
+
+impl PartialOrd<DnsName<'_>> for DnsName<'_> {
+    #[inline]
+    fn partial_cmp(&self, other: &DnsName<'_>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Names are ordered in canonical order. Invalid names are ordered before
+/// all valid names and among themselves by their bytes.
+///
+/// According to [RFC 4034 Section 6.1](https://tools.ietf.org/rfc/rfc4034#section-6.1).
+impl Ord for DnsName<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.label_count(), other.label_count()) {
+            (Ok(_), Ok(_)) => self.canonical_cmp(other).unwrap_or(Ordering::Equal),
+            (Err(_), Ok(_)) => Ordering::Less,
+            (Ok(_), Err(_)) => Ordering::Greater,
+            (Err(_), Err(_)) => self.raw_bytes().cmp(other.raw_bytes()),
         }
     }
 }
@@ -352,10 +503,163 @@ impl LabelType {
             }
 
             Ok(Self::Pointer(pointer))
+        } else if c & PTR_MASK != 0 {
+            // The extended label types are not supported, report them like
+            // a label longer than 63 bytes.
+            Err(DnsMessageError::DnsError(DnsError::LabelTooLong))
         } else {
-            let len = c & LEN_MASK;
+            Ok(Self::Part(c))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::parse::Parse;
+    use super::*;
+
+    #[test]
+    fn truncated_label() {
+        assert_eq!(
+            DnsName::parse(b"\x07exam".as_slice(), &mut 0).err(),
+            Some(DnsMessageError::DnsError(DnsError::UnexpectedEndOfBuffer))
+        );
+        assert_eq!(
+            DnsName::new(b"\x07exam").err(),
+            Some(DnsMessageError::DnsError(DnsError::MessageTooShort))
+        );
+    }
+
+    #[test]
+    fn extended_label_type() {
+        assert_eq!(
+            DnsName::parse(b"\x41a\x00".as_slice(), &mut 0).err(),
+            Some(DnsMessageError::DnsError(DnsError::LabelTooLong))
+        );
+        let name = unsafe { DnsName::new_unchecked(b"\x01a\x81") };
+        let mut labels = name.iter();
+        assert_eq!(labels.next(), Some(Ok(b"a".as_slice())));
+        assert_eq!(labels.next(), Some(Err(DnsMessageError::DnsError(DnsError::LabelTooLong))));
+    }
+
+    #[test]
+    fn missing_terminator() {
+        let name = unsafe { DnsName::new_unchecked(b"\x01a") };
+        let mut labels = name.iter();
+        assert_eq!(labels.next(), Some(Ok(b"a".as_slice())));
+        assert_eq!(labels.next(), Some(Err(DnsMessageError::DnsError(DnsError::UnexpectedEndOfBuffer))));
+    }
+
+    #[test]
+    fn eq() {
+        let name = DnsName::new(b"\x03www\x07example\x03com\x00").unwrap();
+        assert_eq!(name, DnsName::new(b"\x03WWW\x07Example\x03com\x00").unwrap());
+        assert_ne!(name, DnsName::new(b"\x03www\x00").unwrap());
+        assert_ne!(name, DnsName::new(b"\x03www\x07example\x03com\x03net\x00").unwrap());
+        assert_ne!(name, DnsName::new(b"\x03ww2\x07example\x03com\x00").unwrap());
+
+        // A compressed name is equal to the same name written out.
+        let message = b"\x07example\x03com\x00\x03WWW\xc0\x00";
+        let compressed = DnsName::parse(message.as_slice(), &mut 13).unwrap();
+        assert_eq!(name, compressed);
+
+        let invalid = unsafe { DnsName::new_unchecked(b"\x01a") };
+        assert_eq!(invalid, invalid);
+        assert_ne!(invalid, unsafe { DnsName::new_unchecked(b"\x01b") });
+        assert_ne!(invalid, DnsName::new(b"\x01a\x00").unwrap());
+    }
 
-            Ok(Self::Part(len))
+    #[test]
+    fn hash() {
+        use core::hash::Hasher;
+
+        // FNV-1a.
+        struct TestHasher(u64);
+
+        impl Hasher for TestHasher {
+            fn finish(&self) -> u64 {
+                self.0
+            }
+
+            fn write(&mut self, bytes: &[u8]) {
+                for &byte in bytes {
+                    self.0 = (self.0 ^ byte as u64).wrapping_mul(0x100000001b3);
+                }
+            }
         }
+
+        let hash = |name: &DnsName| {
+            let mut hasher = TestHasher(0xcbf29ce484222325);
+            name.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        assert_eq!(
+            hash(&DnsName::new(b"\x03www\x07example\x03com\x00").unwrap()),
+            hash(&DnsName::new(b"\x03WWW\x07exAMPle\x03COM\x00").unwrap()),
+        );
+        assert_ne!(
+            hash(&DnsName::new(b"\x03www\x07example\x03com\x00").unwrap()),
+            hash(&DnsName::new(b"\x03www\x07example\x03net\x00").unwrap()),
+        );
+
+        let invalid = unsafe { DnsName::new_unchecked(b"\x01a") };
+        assert_eq!(hash(&invalid), hash(&unsafe { DnsName::new_unchecked(b"\x01a") }));
+        assert_ne!(hash(&invalid), hash(&unsafe { DnsName::new_unchecked(b"\x01b") }));
+    }
+
+    #[test]
+    fn canonical_order() {
+        // The example of RFC 4034 Section 6.1.
+        let names: [&[u8]; 9] = [
+            b"\x07example\x00",
+            b"\x01a\x07example\x00",
+            b"\x08yljkjljk\x01a\x07example\x00",
+            b"\x01Z\x01a\x07example\x00",
+            b"\x04zABC\x01a\x07EXAMPLE\x00",
+            b"\x01z\x07example\x00",
+            b"\x01\x01\x01z\x07example\x00",
+            b"\x01*\x01z\x07example\x00",
+            b"\x01\xc8\x01z\x07example\x00",
+        ];
+
+        for (i, a) in names.iter().enumerate() {
+            for (j, b) in names.iter().enumerate() {
+                let (a, b) = (DnsName::new(a).unwrap(), DnsName::new(b).unwrap());
+                assert_eq!(a.cmp(&b), i.cmp(&j));
+            }
+        }
+
+        let invalid = unsafe { DnsName::new_unchecked(b"\x01a") };
+        let other = unsafe { DnsName::new_unchecked(b"\x01b") };
+        assert!(invalid < DnsName::new(b"\x00").unwrap());
+        assert_eq!(invalid.cmp(&invalid), Ordering::Equal);
+        assert_eq!(invalid.cmp(&other), Ordering::Less);
+        assert_eq!(other.cmp(&invalid), Ordering::Greater);
+    }
+
+    #[test]
+    fn helpers() {
+        let name = DnsName::new(b"\x03www\x07example\x03com\x00").unwrap();
+        let other = DnsName::new(b"\x04mail\x07EXAMPLE\x03com\x00").unwrap();
+        let root = DnsName::new(b"\x00").unwrap();
+
+        assert_eq!(name.label_count(), Ok(3));
+        assert_eq!(root.label_count(), Ok(0));
+
+        let parent = name.parent().unwrap();
+        assert_eq!(parent, DnsName::new(b"\x07example\x03com\x00").unwrap());
+        assert_eq!(parent.parent().unwrap().parent(), Some(root));
+        assert_eq!(root.parent(), None);
+
+        assert!(name.is_subdomain_of(&other.parent().unwrap()));
+        assert!(name.is_subdomain_of(&name));
+        assert!(name.is_subdomain_of(&root));
+        assert!(!parent.is_subdomain_of(&name));
+        assert!(!name.is_subdomain_of(&other));
+
+        assert_eq!(name.common_suffix(&other), Ok(parent));
+        assert_eq!(name.common_suffix(&root), Ok(root));
+        assert_eq!(name.common_suffix(&name), Ok(name));
     }
 }
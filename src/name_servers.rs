@@ -1,5 +1,5 @@
 use crate::{Buffer, DnsMessage, DnsMessageError, MutBuffer};
-use crate::answer::DnsAClass;
+use crate::answer::{CACHE_FLUSH_MASK, DnsAClass};
 use crate::name::DnsName;
 use crate::parse::{Parse, ParseBytes};
 use crate::question::DnsQType;
@@ -21,7 +21,7 @@ impl<
 > DnsNameServers<PTR_STORAGE, B> {
     #[inline(always)]
     pub(crate) fn new(message: DnsMessage<PTR_STORAGE, 2, B>) -> Self {
-        let remaining = message.header().unwrap().answer_count() as usize;
+        let remaining = message.header().unwrap().name_server_count() as usize;
         Self {
             message,
             remaining,
@@ -67,11 +67,10 @@ impl<
         // Truncate the buffer to the current position.
         self.message.truncate()?;
         answer.write(&mut self.message)?;
-        // Set answer_count in the header to the current answer count + 1.
-        let answer_count = self.message.header().unwrap().answer_count();
-        let answer_count = answer_count + 1 - self.remaining as u16;
-        self.message.header_mut()?.set_answer_count(answer_count);
-        self.message.header_mut()?.set_name_server_count(0);
+        // Set name_server_count in the header to the current name server count + 1.
+        let name_server_count = self.message.header().unwrap().name_server_count();
+        let name_server_count = name_server_count + 1 - self.remaining as u16;
+        self.message.header_mut()?.set_name_server_count(name_server_count);
         self.message.header_mut()?.set_additional_records_count(0);
         self.remaining = 0;
 
@@ -111,7 +110,8 @@ pub struct NameServer<'a, D> {
     pub name: DnsName<'a>,
     /// The data of the name server.
     pub rdata: D,
-    /// Whether the name server is authoritative.
+    /// Whether the name server replaces the cached records of its name and
+    /// type (mDNS only).
     pub cache_flush: bool,
     /// The class of the name server.
     pub aclass: DnsAClass,
@@ -136,10 +136,10 @@ impl<'a> NameServer<'a, RData<'a>> {
 impl<'a> ParseBytes<'a> for NameServer<'a, RData<'a>> {
     fn parse_bytes(bytes: &'a [u8], i: &mut usize) -> Result<Self, DnsMessageError> {
         let name = DnsName::parse(bytes, i)?;
-        let atype_id = u16::parse(bytes, i)?;
-        let atype = DnsQType::from_id(atype_id);
-        let cache_flush = atype_id & 0b1000_0000_0000_0000 != 0;
-        let aclass = DnsAClass::from_id(u16::parse(bytes, i)?);
+        let atype = DnsQType::from_id(u16::parse(bytes, i)?);
+        let aclass = u16::parse(bytes, i)?;
+        let cache_flush = aclass & CACHE_FLUSH_MASK != 0;
+        let aclass = DnsAClass::from_id(aclass & !CACHE_FLUSH_MASK);
         let ttl = u32::parse(bytes, i)?;
         let rdata = RData::parse(bytes, i, atype)?;
 
@@ -166,7 +166,7 @@ impl<'a> WriteBytes for NameServer<'a, DnsAType<'a>> {
         bytes += self.rdata.id().write(message)?;
         let mut aclass = self.aclass.id();
         if self.cache_flush {
-            aclass |= 0b1000_0000;
+            aclass |= CACHE_FLUSH_MASK;
         }
         bytes += aclass.write(message)?;
         // Write the ttl to the buffer.
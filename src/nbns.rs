@@ -0,0 +1,364 @@
+use crate::{Buffer, BufferError, DnsError, DnsMessage, DnsMessageError, MutBuffer};
+use crate::name::DnsName;
+use crate::question::{DnsQClass, DnsQType, DnsQuestion};
+use crate::rdata::{DnsAType, Nb, NbStat};
+
+// The NB and NBSTAT types, which reuse the NIMLOC and SRV types.
+// According to RFC 1002 Section 4.2.1.2.
+pub(crate) const NB: u16 = 0x0020;
+pub(crate) const NBSTAT: u16 = 0x0021;
+// A NetBIOS name is padded to 15 bytes, followed by its suffix, and encoded
+// into a label of twice its length.
+const NAME_LENGTH: usize = 15;
+const ENCODED_LENGTH: usize = 32;
+const MAX_NAME_LENGTH: usize = 255;
+
+/// A NetBIOS name, of up to 15 bytes padded with spaces, followed by a suffix
+/// byte for the type of the service, e.g. `0x00` for a workstation or `0x20`
+/// for a file server.
+///
+/// According to [RFC 1001 Section 14](https://tools.ietf.org/rfc/rfc1001#section-14).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NetbiosName {
+    bytes: [u8; 16],
+}
+
+impl NetbiosName {
+    /// The wildcard name `*` padded with zeros, used in node status requests
+    /// to a node's address.
+    pub const WILDCARD: Self = Self {
+        bytes: [b'*', 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+    };
+
+    /// Create a new NetBIOS name, which is padded with spaces. If the name
+    /// is longer than 15 bytes, this function will return an error.
+    pub fn new(name: &[u8], suffix: u8) -> Result<Self, DnsMessageError> {
+        if name.len() > NAME_LENGTH {
+            return Err(DnsMessageError::DnsError(DnsError::InvalidNetbiosName));
+        }
+
+        let mut bytes = [b' '; 16];
+        bytes[..name.len()].copy_from_slice(name);
+        bytes[NAME_LENGTH] = suffix;
+
+        Ok(Self {
+            bytes,
+        })
+    }
+
+    /// Create a NetBIOS name from its 16 bytes, including the padding and
+    /// the suffix.
+    #[inline(always)]
+    pub const fn from_bytes(bytes: [u8; 16]) -> Self {
+        Self {
+            bytes,
+        }
+    }
+
+    /// Return the 16 bytes of the name, including the padding and the
+    /// suffix.
+    #[inline(always)]
+    pub fn bytes(&self) -> &[u8; 16] {
+        &self.bytes
+    }
+
+    /// Return the name without its padding and suffix.
+    #[inline]
+    pub fn name(&self) -> &[u8] {
+        let length = self.bytes[..NAME_LENGTH].iter()
+            .rposition(|&c| c != b' ' && c != 0)
+            .map_or(0, |i| i + 1);
+
+        &self.bytes[..length]
+    }
+
+    /// Return the suffix of the name.
+    #[inline(always)]
+    pub fn suffix(&self) -> u8 {
+        self.bytes[NAME_LENGTH]
+    }
+
+    /// Encode the name with the first-level encoding into a domain name,
+    /// followed by the scope, which is usually the root. The domain name is
+    /// written to the buffer.
+    ///
+    /// According to [RFC 1001 Section 14.1](https://tools.ietf.org/rfc/rfc1001#section-14.1).
+    pub fn encode<'b>(&self, scope: &DnsName, buffer: &'b mut [u8]) -> Result<DnsName<'b>, DnsMessageError> {
+        // The encoded label and the root label, followed by the scope.
+        let mut length = ENCODED_LENGTH + 2;
+        for label in scope.iter() {
+            length += label?.len() + 1;
+        }
+        if length > MAX_NAME_LENGTH {
+            return Err(DnsMessageError::DnsError(DnsError::NameTooLong));
+        }
+
+        let mut length = 0;
+        let mut write = |bytes: &[u8]| {
+            buffer.get_mut(length..length + bytes.len())
+                .ok_or(DnsMessageError::BufferError(BufferError::OutOfMemory))?
+                .copy_from_slice(bytes);
+            length += bytes.len();
+
+            Ok::<(), DnsMessageError>(())
+        };
+
+        write(&[ENCODED_LENGTH as u8])?;
+        for byte in self.bytes {
+            write(&[b'A' + (byte >> 4), b'A' + (byte & 0x0f)])?;
+        }
+        for label in scope.iter() {
+            let label = label?;
+            write(&[label.len() as u8])?;
+            write(label)?;
+        }
+        write(&[0])?;
+
+        DnsName::new(&buffer[..length])
+    }
+
+    /// Decode the first label of a domain name with the first-level
+    /// encoding. The other labels are the scope, see [`DnsName::parent`].
+    ///
+    /// According to [RFC 1001 Section 14.1](https://tools.ietf.org/rfc/rfc1001#section-14.1).
+    pub fn decode(name: &DnsName) -> Result<Self, DnsMessageError> {
+        let label = name.iter().next()
+            .ok_or(DnsMessageError::DnsError(DnsError::InvalidNetbiosName))??;
+        if label.len() != ENCODED_LENGTH {
+            return Err(DnsMessageError::DnsError(DnsError::InvalidNetbiosName));
+        }
+
+        let mut bytes = [0; 16];
+        for (byte, pair) in bytes.iter_mut().zip(label.chunks_exact(2)) {
+            let high = pair[0].to_ascii_uppercase().wrapping_sub(b'A');
+            let low = pair[1].to_ascii_uppercase().wrapping_sub(b'A');
+            if high > 0x0f || low > 0x0f {
+                return Err(DnsMessageError::DnsError(DnsError::InvalidNetbiosName));
+            }
+            *byte = high << 4 | low;
+        }
+
+        Ok(Self {
+            bytes,
+        })
+    }
+}
+
+/// The record data of an NBNS message, in which the NB and NBSTAT types
+/// replace the NIMLOC and SRV types.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum NbnsAType<'a> {
+    /// The addresses of a name.
+    Nb(Nb<'a>),
+    /// The names and statistics of a node.
+    NbStat(NbStat<'a>),
+    /// Another record, e.g. the NS and A records of a redirect.
+    Other(DnsAType<'a>),
+}
+
+/// Write a name query request for the name. It is broadcast, or sent to the
+/// name server.
+///
+/// According to [RFC 1002 Section 4.2.12](https://tools.ietf.org/rfc/rfc1002#section-4.2.12).
+pub fn name_query<B: MutBuffer + Buffer>(
+    id: u16,
+    name: &NetbiosName,
+    scope: &DnsName,
+    broadcast: bool,
+    buffer: B,
+) -> Result<B, DnsMessageError> {
+    write_query(id, name, scope, NB, true, broadcast, buffer)
+}
+
+/// Write a node status request for the name, usually
+/// [`NetbiosName::WILDCARD`] when sent to the address of a node.
+///
+/// According to [RFC 1002 Section 4.2.17](https://tools.ietf.org/rfc/rfc1002#section-4.2.17).
+pub fn node_status_request<B: MutBuffer + Buffer>(
+    id: u16,
+    name: &NetbiosName,
+    scope: &DnsName,
+    buffer: B,
+) -> Result<B, DnsMessageError> {
+    write_query(id, name, scope, NBSTAT, false, false, buffer)
+}
+
+fn write_query<B: MutBuffer + Buffer>(
+    id: u16,
+    name: &NetbiosName,
+    scope: &DnsName,
+    qtype: u16,
+    recursion_desired: bool,
+    broadcast: bool,
+    buffer: B,
+) -> Result<B, DnsMessageError> {
+    let mut encoded = [0; MAX_NAME_LENGTH];
+    let name = name.encode(scope, &mut encoded)?;
+
+    let mut message: DnsMessage<0, 0, _> = DnsMessage::new_mut(buffer)?;
+    let header = message.header_mut()?;
+    header.set_id(id);
+    header.set_recursion_desired(recursion_desired);
+    header.nbns_mut().set_broadcast(broadcast);
+    let mut questions = message.questions();
+    questions.append(DnsQuestion {
+        name,
+        qtype: DnsQType::from_id(qtype),
+        unicast_response: false,
+        qclass: DnsQClass::IN,
+    })?;
+
+    questions.complete()?.abort()
+}
+
+#[cfg(test)]
+mod test {
+    use arrayvec::ArrayVec;
+    use crate::answer::{DnsAClass, DnsAnswer};
+    use crate::header::DnsHeaderKind;
+    use crate::rdata::{NbNodeType, Unknown};
+    use super::*;
+
+    fn root() -> DnsName<'static> {
+        DnsName::new(b"\x00").unwrap()
+    }
+
+    #[test]
+    fn encode() {
+        // According to RFC 1001 Section 14.1.
+        let name = NetbiosName::new(b"FRED", b' ').unwrap();
+        let mut buffer = [0; 255];
+        let encoded = name.encode(&root(), &mut buffer).unwrap();
+        assert_eq!(encoded, DnsName::new(b"\x20EGFCEFEECACACACACACACACACACACACA\x00").unwrap());
+        assert_eq!(NetbiosName::decode(&encoded), Ok(name));
+        assert_eq!(name.name(), b"FRED");
+        assert_eq!(name.suffix(), b' ');
+
+        let scope = DnsName::new(b"\x07netbios\x03com\x00").unwrap();
+        let mut buffer = [0; 255];
+        let encoded = NetbiosName::WILDCARD.encode(&scope, &mut buffer).unwrap();
+        assert_eq!(encoded, DnsName::new(b"\x20CKAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA\x07netbios\x03com\x00").unwrap());
+        assert_eq!(NetbiosName::decode(&encoded), Ok(NetbiosName::WILDCARD));
+        assert_eq!(encoded.parent(), Some(scope));
+        assert_eq!(NetbiosName::WILDCARD.name(), b"*");
+
+        assert_eq!(
+            NetbiosName::new(b"SIXTEEN-LETTERS!", 0),
+            Err(DnsMessageError::DnsError(DnsError::InvalidNetbiosName)),
+        );
+        assert_eq!(
+            name.encode(&root(), &mut [0; 33]),
+            Err(DnsMessageError::BufferError(BufferError::OutOfMemory)),
+        );
+    }
+
+    #[test]
+    fn decode() {
+        let lowercase = DnsName::new(b"\x20egfcefeecacacacacacacacacacacaca\x00").unwrap();
+        assert_eq!(NetbiosName::decode(&lowercase), NetbiosName::new(b"FRED", b' '));
+
+        for name in [
+            &b"\x04fred\x00"[..],
+            b"\x20QGFCEFEECACACACACACACACACACACACA\x00",
+            b"\x00",
+        ] {
+            assert_eq!(
+                NetbiosName::decode(&DnsName::new(name).unwrap()),
+                Err(DnsMessageError::DnsError(DnsError::InvalidNetbiosName)),
+            );
+        }
+    }
+
+    #[test]
+    fn name_query() {
+        let name = NetbiosName::new(b"FRED", 0x00).unwrap();
+        let bytes = super::name_query(0x1234, &name, &root(), true, ArrayVec::<u8, 512>::new()).unwrap();
+        assert_eq!(
+            bytes.as_slice(),
+            [
+                0x12, 0x34, 0x01, 0x10, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // Header
+                0x20, b'E', b'G', b'F', b'C', b'E', b'F', b'E', b'E', b'C', b'A', b'C', b'A', b'C', b'A', // Name
+                b'C', b'A', b'C', b'A', b'C', b'A', b'C', b'A', b'C', b'A', b'C', b'A', b'C', b'A', b'C', b'A', // Name
+                b'A', b'A', 0x00, // Name
+                0x00, 0x20, // Type
+                0x00, 0x01, // Class
+            ].as_slice(),
+        );
+        let message: DnsMessage<0, 0, _> = DnsMessage::new(bytes.as_slice()).unwrap();
+        assert!(message.header().unwrap().nbns().broadcast());
+    }
+
+    #[test]
+    fn node_status_request() {
+        let bytes = super::node_status_request(0x1234, &NetbiosName::WILDCARD, &root(), ArrayVec::<u8, 512>::new()).unwrap();
+        assert_eq!(&bytes[..12], &[0x12, 0x34, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        assert_eq!(&bytes[13..15], b"CK");
+        assert_eq!(&bytes[bytes.len() - 4..], &[0x00, 0x21, 0x00, 0x01]);
+    }
+
+    #[test]
+    fn scope_too_long() {
+        // The scope fits a name, but not after the encoded name.
+        let mut scope = [0; 255];
+        for label in scope[..252].chunks_mut(63) {
+            label[0] = 62;
+            label[1..].fill(b'a');
+        }
+        let scope = DnsName::new(&scope[..253]).unwrap();
+        let name = NetbiosName::new(b"FRED", 0x00).unwrap();
+        assert_eq!(
+            super::name_query(0x1234, &name, &scope, false, ArrayVec::<u8, 512>::new()),
+            Err(DnsMessageError::DnsError(DnsError::NameTooLong)),
+        );
+    }
+
+    #[test]
+    fn parse_response() {
+        let name = NetbiosName::new(b"FRED", 0x00).unwrap();
+        let mut buffer = [0; 255];
+        let encoded = name.encode(&root(), &mut buffer).unwrap();
+        let mut nbstat = [0; 25];
+        nbstat[0] = 1;
+        nbstat[1..17].copy_from_slice(name.bytes());
+        nbstat[17] = 0x04;
+        nbstat[19..].copy_from_slice(&[0x00, 0x11, 0x22, 0x33, 0x44, 0x55]);
+
+        let mut message: DnsMessage<8, 0, _> = DnsMessage::new_mut(ArrayVec::<u8, 512>::new()).unwrap();
+        message.header_mut().unwrap().set_kind(DnsHeaderKind::Response);
+        let message = message.questions().complete().unwrap();
+        let mut answers = message.answers();
+        answers.append(DnsAnswer {
+            name: encoded,
+            rdata: DnsAType::Unknown(Unknown::new(NB, &[0x60, 0x00, 0xc0, 0xa8, 0x01, 0x14])),
+            cache_flush: false,
+            aclass: DnsAClass::IN,
+            ttl: 300000,
+        }).unwrap();
+        answers.append(DnsAnswer {
+            name: encoded,
+            rdata: DnsAType::Unknown(Unknown::new(NBSTAT, &nbstat)),
+            cache_flush: false,
+            aclass: DnsAClass::IN,
+            ttl: 0,
+        }).unwrap();
+        let bytes = answers.complete().unwrap().abort().unwrap();
+
+        let message: DnsMessage<8, 0, _> = DnsMessage::new(bytes.as_slice()).unwrap();
+        let message = message.questions().complete().unwrap();
+        let mut answers = message.answers();
+        let mut answers = answers.iter().unwrap();
+
+        let answer = answers.next().unwrap().unwrap();
+        assert_eq!(NetbiosName::decode(&answer.name), Ok(name));
+        let NbnsAType::Nb(nb) = answer.rdata.into_parsed_nbns().unwrap() else { panic!() };
+        let entry = nb.iter().next().unwrap();
+        assert_eq!(entry.node_type, NbNodeType::Reserved);
+        assert_eq!(entry.address, [192, 168, 1, 20]);
+
+        // The NBSTAT type is not a valid SRV record.
+        let answer = answers.next().unwrap().unwrap();
+        let NbnsAType::NbStat(nbstat) = answer.rdata.into_parsed_nbns().unwrap() else { panic!() };
+        assert_eq!(nbstat.names().next().unwrap().name, name);
+        assert_eq!(nbstat.unit_id(), Some([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]));
+    }
+}
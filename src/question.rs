@@ -3,6 +3,10 @@ use crate::name::DnsName;
 use crate::parse::{Parse, ParseBytes};
 use crate::write::WriteBytes;
 
+// The top bit of the class is the unicast-response bit in mDNS.
+// According to RFC 6762 Section 5.4.
+const UNICAST_RESPONSE_MASK: u16 = 0b1000_0000_0000_0000;
+
 /// A DNS message questions section.
 pub struct DnsQuestions<
     const PTR_STORAGE: usize,
@@ -109,6 +113,8 @@ pub struct DnsQuestion<'a> {
     pub name: DnsName<'a>,
     /// The type of the query.
     pub qtype: DnsQType,
+    /// Whether a unicast response is preferred (mDNS only).
+    pub unicast_response: bool,
     /// The class of the query.
     pub qclass: DnsQClass,
 }
@@ -117,11 +123,14 @@ impl<'a> ParseBytes<'a> for DnsQuestion<'a> {
     fn parse_bytes(bytes: &'a [u8], i: &mut usize) -> Result<Self, DnsMessageError> {
         let name = DnsName::parse(bytes, i)?;
         let qtype = u16::parse(bytes, i)?.into();
-        let qclass = u16::parse(bytes, i)?.into();
+        let qclass = u16::parse(bytes, i)?;
+        let unicast_response = qclass & UNICAST_RESPONSE_MASK != 0;
+        let qclass = DnsQClass::from_id(qclass & !UNICAST_RESPONSE_MASK);
 
         Ok(Self {
             name,
             qtype,
+            unicast_response,
             qclass,
         })
     }
@@ -137,7 +146,11 @@ impl<'a> WriteBytes for DnsQuestion<'a> {
 
         bytes += self.name.write(message)?;
         bytes += self.qtype.id().write(message)?;
-        bytes += self.qclass.id().write(message)?;
+        let mut qclass = self.qclass.id();
+        if self.unicast_response {
+            qclass |= UNICAST_RESPONSE_MASK;
+        }
+        bytes += qclass.write(message)?;
 
         Ok(bytes)
     }
@@ -148,60 +161,60 @@ impl<'a> WriteBytes for DnsQuestion<'a> {
 /// According to [RFC 1035 Section 3.2.2](https://tools.ietf.org/rfc/rfc1035#section-3.2.2)
 /// and [RFC 1035 Section 3.2.3](https://tools.ietf.org/rfc/rfc1035#section-3.2.3).
 #[derive(Copy, Clone, Debug, PartialEq)]
-#[repr(u16)]
 pub enum DnsQType {
-    A = 1,
-    NS = 2,
-    CNAME = 5,
-    SOA = 6,
-    PTR = 12,
-    HINFO = 13,
-    MX = 15,
-    TXT = 16,
-    RP = 17,
-    AFSDB = 18,
-    SIG = 24,
-    KEY = 25,
-    AAAA = 28,
-    LOC = 29,
-    SRV = 33,
-    NAPTR = 35,
-    KX = 36,
-    CERT = 37,
-    DNAME = 39,
-    OPT = 41,
-    APL = 42,
-    DS = 43,
-    SSHFP = 44,
-    IPSECKEY = 45,
-    RRSIG = 46,
-    NSEC = 47,
-    DNSKEY = 48,
-    DHCID = 49,
-    NSEC3 = 50,
-    NSEC3PARAM = 51,
-    TLSA = 52,
-    SMIMEA = 53,
-    HIP = 55,
-    CDS = 59,
-    CDNSKEY = 60,
-    OPENPGPKEY = 61,
-    CSYNC = 62,
-    ZONEMD = 63,
-    SVCB = 64,
-    HTTPS = 65,
-    EUI48 = 108,
-    EUI64 = 109,
-    TKEY = 249,
-    TSIG = 250,
-    IXFR = 251,
-    AXFR = 252,
-    ALL = 255,
-    URI = 256,
-    CAA = 257,
-    TA = 32768,
-    DLV = 32769,
-    Reserved,
+    A,
+    NS,
+    CNAME,
+    SOA,
+    PTR,
+    HINFO,
+    MX,
+    TXT,
+    RP,
+    AFSDB,
+    SIG,
+    KEY,
+    AAAA,
+    LOC,
+    SRV,
+    NAPTR,
+    KX,
+    CERT,
+    DNAME,
+    OPT,
+    APL,
+    DS,
+    SSHFP,
+    IPSECKEY,
+    RRSIG,
+    NSEC,
+    DNSKEY,
+    DHCID,
+    NSEC3,
+    NSEC3PARAM,
+    TLSA,
+    SMIMEA,
+    HIP,
+    CDS,
+    CDNSKEY,
+    OPENPGPKEY,
+    CSYNC,
+    ZONEMD,
+    SVCB,
+    HTTPS,
+    EUI48,
+    EUI64,
+    TKEY,
+    TSIG,
+    IXFR,
+    AXFR,
+    ALL,
+    URI,
+    CAA,
+    TA,
+    DLV,
+    /// A type without a name in this crate, with its ID.
+    Unknown(u16),
 }
 
 impl DnsQType {
@@ -209,13 +222,58 @@ impl DnsQType {
     #[inline(always)]
     pub fn from_id(id: u16) -> Self {
         match id {
-            1..=2 | 5..=6 | 12..=13 | 15..=18 | 24..=25 | 28..=29
-            | 33 | 35..=37 | 39 | 41..=53 | 55 | 59..=65
-            | 108..=109 | 249..=252 | 255 | 256 | 257
-            | 32768..=32769 => unsafe {
-                core::mem::transmute(id)
-            },
-            _ => DnsQType::Reserved,
+            1 => DnsQType::A,
+            2 => DnsQType::NS,
+            5 => DnsQType::CNAME,
+            6 => DnsQType::SOA,
+            12 => DnsQType::PTR,
+            13 => DnsQType::HINFO,
+            15 => DnsQType::MX,
+            16 => DnsQType::TXT,
+            17 => DnsQType::RP,
+            18 => DnsQType::AFSDB,
+            24 => DnsQType::SIG,
+            25 => DnsQType::KEY,
+            28 => DnsQType::AAAA,
+            29 => DnsQType::LOC,
+            33 => DnsQType::SRV,
+            35 => DnsQType::NAPTR,
+            36 => DnsQType::KX,
+            37 => DnsQType::CERT,
+            39 => DnsQType::DNAME,
+            41 => DnsQType::OPT,
+            42 => DnsQType::APL,
+            43 => DnsQType::DS,
+            44 => DnsQType::SSHFP,
+            45 => DnsQType::IPSECKEY,
+            46 => DnsQType::RRSIG,
+            47 => DnsQType::NSEC,
+            48 => DnsQType::DNSKEY,
+            49 => DnsQType::DHCID,
+            50 => DnsQType::NSEC3,
+            51 => DnsQType::NSEC3PARAM,
+            52 => DnsQType::TLSA,
+            53 => DnsQType::SMIMEA,
+            55 => DnsQType::HIP,
+            59 => DnsQType::CDS,
+            60 => DnsQType::CDNSKEY,
+            61 => DnsQType::OPENPGPKEY,
+            62 => DnsQType::CSYNC,
+            63 => DnsQType::ZONEMD,
+            64 => DnsQType::SVCB,
+            65 => DnsQType::HTTPS,
+            108 => DnsQType::EUI48,
+            109 => DnsQType::EUI64,
+            249 => DnsQType::TKEY,
+            250 => DnsQType::TSIG,
+            251 => DnsQType::IXFR,
+            252 => DnsQType::AXFR,
+            255 => DnsQType::ALL,
+            256 => DnsQType::URI,
+            257 => DnsQType::CAA,
+            32768 => DnsQType::TA,
+            32769 => DnsQType::DLV,
+            id => DnsQType::Unknown(id),
         }
     }
 
@@ -223,8 +281,58 @@ impl DnsQType {
     #[inline(always)]
     pub fn id(&self) -> u16 {
         match self {
-            DnsQType::Reserved => panic!("Reserved QType"),
-            _ => *self as u16,
+            DnsQType::A => 1,
+            DnsQType::NS => 2,
+            DnsQType::CNAME => 5,
+            DnsQType::SOA => 6,
+            DnsQType::PTR => 12,
+            DnsQType::HINFO => 13,
+            DnsQType::MX => 15,
+            DnsQType::TXT => 16,
+            DnsQType::RP => 17,
+            DnsQType::AFSDB => 18,
+            DnsQType::SIG => 24,
+            DnsQType::KEY => 25,
+            DnsQType::AAAA => 28,
+            DnsQType::LOC => 29,
+            DnsQType::SRV => 33,
+            DnsQType::NAPTR => 35,
+            DnsQType::KX => 36,
+            DnsQType::CERT => 37,
+            DnsQType::DNAME => 39,
+            DnsQType::OPT => 41,
+            DnsQType::APL => 42,
+            DnsQType::DS => 43,
+            DnsQType::SSHFP => 44,
+            DnsQType::IPSECKEY => 45,
+            DnsQType::RRSIG => 46,
+            DnsQType::NSEC => 47,
+            DnsQType::DNSKEY => 48,
+            DnsQType::DHCID => 49,
+            DnsQType::NSEC3 => 50,
+            DnsQType::NSEC3PARAM => 51,
+            DnsQType::TLSA => 52,
+            DnsQType::SMIMEA => 53,
+            DnsQType::HIP => 55,
+            DnsQType::CDS => 59,
+            DnsQType::CDNSKEY => 60,
+            DnsQType::OPENPGPKEY => 61,
+            DnsQType::CSYNC => 62,
+            DnsQType::ZONEMD => 63,
+            DnsQType::SVCB => 64,
+            DnsQType::HTTPS => 65,
+            DnsQType::EUI48 => 108,
+            DnsQType::EUI64 => 109,
+            DnsQType::TKEY => 249,
+            DnsQType::TSIG => 250,
+            DnsQType::IXFR => 251,
+            DnsQType::AXFR => 252,
+            DnsQType::ALL => 255,
+            DnsQType::URI => 256,
+            DnsQType::CAA => 257,
+            DnsQType::TA => 32768,
+            DnsQType::DLV => 32769,
+            DnsQType::Unknown(id) => *id,
         }
     }
 }
@@ -247,19 +355,19 @@ impl From<u16> for DnsQType {
 ///
 /// According to [RFC 1035 Section 3.2.4](https://tools.ietf.org/rfc/rfc1035#section-3.2.4).
 #[derive(Copy, Clone, Debug, PartialEq)]
-#[repr(u16)]
 pub enum DnsQClass {
     /// Internet
-    IN = 1,
+    IN,
     /// CSNET
-    CS = 2,
+    CS,
     /// CHAOS
-    CH = 3,
+    CH,
     /// Hesiod
-    HS = 4,
+    HS,
     /// Any
-    ANY = 255,
-    Reserved,
+    ANY,
+    /// A class without a name in this crate, with its ID.
+    Unknown(u16),
 }
 
 impl DnsQClass {
@@ -267,8 +375,12 @@ impl DnsQClass {
     #[inline(always)]
     pub fn from_id(id: u16) -> Self {
         match id {
-            1..=4 | 255 => unsafe { core::mem::transmute(id) },
-            _ => DnsQClass::Reserved,
+            1 => DnsQClass::IN,
+            2 => DnsQClass::CS,
+            3 => DnsQClass::CH,
+            4 => DnsQClass::HS,
+            255 => DnsQClass::ANY,
+            id => DnsQClass::Unknown(id),
         }
     }
 
@@ -276,8 +388,12 @@ impl DnsQClass {
     #[inline(always)]
     pub fn id(&self) -> u16 {
         match self {
-            DnsQClass::Reserved => panic!("Reserved QClass"),
-            _ => *self as u16,
+            DnsQClass::IN => 1,
+            DnsQClass::CS => 2,
+            DnsQClass::CH => 3,
+            DnsQClass::HS => 4,
+            DnsQClass::ANY => 255,
+            DnsQClass::Unknown(id) => *id,
         }
     }
 }
@@ -48,6 +48,12 @@ mod uri;
 mod caa;
 mod ta;
 mod dlv;
+mod unknown;
+mod raw_bytes;
+mod type_bit_map;
+mod svc_params;
+mod nb;
+mod nbstat;
 
 pub use a::A;
 pub use ns::Ns;
@@ -56,7 +62,7 @@ pub use soa::Soa;
 pub use ptr::Ptr;
 pub use hinfo::HInfo;
 pub use mx::Mx;
-pub use txt::Txt;
+pub use txt::{Txt, TxtAttribute, TxtAttributes, TxtIterator};
 pub use rp::Rp;
 pub use afsdb::AfsDb;
 pub use sig::Sig;
@@ -68,7 +74,7 @@ pub use naptr::Naptr;
 pub use kx::Kx;
 pub use cert::Cert;
 pub use dname::DName;
-pub use opt::Opt;
+pub use opt::{Opt, OptIterator};
 pub use apl::Apl;
 pub use ds::Ds;
 pub use sshfp::SshFp;
@@ -99,8 +105,15 @@ pub use uri::Uri;
 pub use caa::Caa;
 pub use ta::Ta;
 pub use dlv::Dlv;
+pub use unknown::Unknown;
+pub use raw_bytes::RawBytes;
+pub use type_bit_map::{TypeBitMap, TypeBitMapIterator};
+pub use nb::{Nb, NbEntry, NbIterator, NbNodeType};
+pub use nbstat::{NbStat, NbStatIterator, NbStatName};
+pub use svc_params::{AlpnIds, SvcParam, SvcParamIterator, SvcParamKey, SvcParamKeys, SvcParams, SvcParamsBuilder};
 
 use crate::{Buffer, DnsError, DnsMessage, DnsMessageError, MutBuffer};
+use crate::nbns::{NB, NBSTAT, NbnsAType};
 use crate::parse::{Parse, ParseData};
 use crate::question::DnsQType;
 use crate::write::WriteBytes;
@@ -147,65 +160,107 @@ impl<'a> RData<'a> {
 
         *i += len;
 
-        Ok(RData { buffer: bytes, pos, len, type_ })
+        // The rdata may point to names earlier in the message, but must not
+        // read past its own length.
+        Ok(RData { buffer: &bytes[..pos + len], pos, len, type_ })
     }
 
+    /// Parse the rdata into a structured type. The structured type must
+    /// consume exactly the length of the rdata.
     pub fn into_parsed(self) -> Result<DnsAType<'a>, DnsMessageError> {
+        self.parse_exact(Self::parse_type)
+    }
+
+    /// Parse the rdata of an NBNS message into a structured type, in which
+    /// the NB and NBSTAT types replace the NIMLOC and SRV types.
+    ///
+    /// According to [RFC 1002 Section 4.2.1.3](https://tools.ietf.org/rfc/rfc1002#section-4.2.1.3).
+    pub fn into_parsed_nbns(self) -> Result<NbnsAType<'a>, DnsMessageError> {
+        self.parse_exact(|rdata, pos| Ok(match rdata.type_.id() {
+            NB => NbnsAType::Nb(Nb::parse(rdata, pos)?),
+            NBSTAT => NbnsAType::NbStat(NbStat::parse(rdata, pos)?),
+            _ => NbnsAType::Other(rdata.parse_type(pos)?),
+        }))
+    }
+
+    // Parse the rdata with the given function, which must consume exactly
+    // the length of the rdata.
+    #[inline(always)]
+    fn parse_exact<T, F: FnOnce(&Self, &mut usize) -> Result<T, DnsMessageError>>(
+        &self,
+        f: F,
+    ) -> Result<T, DnsMessageError> {
         let mut pos = self.pos;
 
+        let parsed = f(self, &mut pos).map_err(|e| match e {
+            DnsMessageError::DnsError(DnsError::UnexpectedEndOfBuffer) => {
+                DnsMessageError::DnsError(DnsError::RDataOverrun)
+            }
+            e => e,
+        })?;
+
+        if pos != self.pos + self.len {
+            return Err(DnsMessageError::DnsError(DnsError::RDataTrailingBytes));
+        }
+
+        Ok(parsed)
+    }
+
+    #[inline(always)]
+    fn parse_type(&self, pos: &mut usize) -> Result<DnsAType<'a>, DnsMessageError> {
         Ok(match self.type_ {
-            DnsQType::A => DnsAType::A(A::parse(&self, &mut pos)?),
-            DnsQType::NS => DnsAType::NS(Ns::parse(&self, &mut pos)?),
-            DnsQType::CNAME => DnsAType::CName(CName::parse(&self, &mut pos)?),
-            DnsQType::SOA => DnsAType::Soa(Soa::parse(&self, &mut pos)?),
-            DnsQType::PTR => DnsAType::Ptr(Ptr::parse(&self, &mut pos)?),
-            DnsQType::HINFO => DnsAType::HInfo(HInfo::parse(&self, &mut pos)?),
-            DnsQType::MX => DnsAType::MX(Mx::parse(&self, &mut pos)?),
-            DnsQType::TXT => DnsAType::Txt(Txt::parse(&self, &mut pos)?),
-            DnsQType::RP => DnsAType::RP(Rp::parse(&self, &mut pos)?),
-            DnsQType::AFSDB => DnsAType::AFSDB(AfsDb::parse(&self, &mut pos)?),
-            DnsQType::SIG => DnsAType::SIG(Sig::parse(&self, &mut pos)?),
-            DnsQType::KEY => DnsAType::KEY(Key::parse(&self, &mut pos)?),
-            DnsQType::AAAA => DnsAType::AAAA(Aaaa::parse(&self, &mut pos)?),
-            DnsQType::LOC => DnsAType::Loc(Loc::parse(&self, &mut pos)?),
-            DnsQType::SRV => DnsAType::Srv(Srv::parse(&self, &mut pos)?),
-            DnsQType::NAPTR => DnsAType::Naptr(Naptr::parse(&self, &mut pos)?),
-            DnsQType::KX => DnsAType::KX(Kx::parse(&self, &mut pos)?),
-            DnsQType::CERT => DnsAType::Cert(Cert::parse(&self, &mut pos)?),
-            DnsQType::DNAME => DnsAType::DName(DName::parse(&self, &mut pos)?),
-            DnsQType::OPT => DnsAType::OPT(Opt::parse(&self, &mut pos)?),
-            DnsQType::APL => DnsAType::APL(Apl::parse(&self, &mut pos)?),
-            DnsQType::DS => DnsAType::DS(Ds::parse(&self, &mut pos)?),
-            DnsQType::SSHFP => DnsAType::SSHFP(SshFp::parse(&self, &mut pos)?),
-            DnsQType::IPSECKEY => DnsAType::IPSECKEY(IpSecKey::parse(&self, &mut pos)?),
-            DnsQType::RRSIG => DnsAType::RRSIG(RRSig::parse(&self, &mut pos)?),
-            DnsQType::NSEC => DnsAType::NSEC(Nsec::parse(&self, &mut pos)?),
-            DnsQType::DNSKEY => DnsAType::DNSKEY(DnsKey::parse(&self, &mut pos)?),
-            DnsQType::DHCID => DnsAType::DHCID(DhcId::parse(&self, &mut pos)?),
-            DnsQType::NSEC3 => DnsAType::NSEC3(Nsec3::parse(&self, &mut pos)?),
-            DnsQType::NSEC3PARAM => DnsAType::NSEC3PARAM(Nsec3Param::parse(&self, &mut pos)?),
-            DnsQType::TLSA => DnsAType::TLSA(Tlsa::parse(&self, &mut pos)?),
-            DnsQType::SMIMEA => DnsAType::SMIMEA(SmimeA::parse(&self, &mut pos)?),
-            DnsQType::HIP => DnsAType::HIP(Hip::parse(&self, &mut pos)?),
-            DnsQType::CDS => DnsAType::CDS(Cds::parse(&self, &mut pos)?),
-            DnsQType::CDNSKEY => DnsAType::CDNSKEY(CdnsKey::parse(&self, &mut pos)?),
-            DnsQType::OPENPGPKEY => DnsAType::OPENPGPKEY(OpenPgpKey::parse(&self, &mut pos)?),
-            DnsQType::CSYNC => DnsAType::CSYNC(CSync::parse(&self, &mut pos)?),
-            DnsQType::ZONEMD => DnsAType::ZONEMD(ZoneMd::parse(&self, &mut pos)?),
-            DnsQType::SVCB => DnsAType::SVCB(Svcb::parse(&self, &mut pos)?),
-            DnsQType::HTTPS => DnsAType::HTTPS(Https::parse(&self, &mut pos)?),
-            DnsQType::EUI48 => DnsAType::EUI48(EUI48::parse(&self, &mut pos)?),
-            DnsQType::EUI64 => DnsAType::EUI64(EUI64::parse(&self, &mut pos)?),
-            DnsQType::TKEY => DnsAType::TKEY(TKey::parse(&self, &mut pos)?),
-            DnsQType::TSIG => DnsAType::TSIG(TSig::parse(&self, &mut pos)?),
-            DnsQType::IXFR => DnsAType::IXFR(IXfr::parse(&self, &mut pos)?),
-            DnsQType::AXFR => DnsAType::AXFR(AXfr::parse(&self, &mut pos)?),
-            DnsQType::ALL => return Err(DnsMessageError::DnsError(DnsError::InvalidAnswer)),
-            DnsQType::URI => DnsAType::URI(Uri::parse(&self, &mut pos)?),
-            DnsQType::CAA => DnsAType::CAA(Caa::parse(&self, &mut pos)?),
-            DnsQType::TA => DnsAType::TA(Ta::parse(&self, &mut pos)?),
-            DnsQType::DLV => DnsAType::DLV(Dlv::parse(&self, &mut pos)?),
-            DnsQType::Reserved => return Err(DnsMessageError::DnsError(DnsError::InvalidAnswer)),
+            DnsQType::A => DnsAType::A(A::parse(self, pos)?),
+            DnsQType::NS => DnsAType::NS(Ns::parse(self, pos)?),
+            DnsQType::CNAME => DnsAType::CName(CName::parse(self, pos)?),
+            DnsQType::SOA => DnsAType::Soa(Soa::parse(self, pos)?),
+            DnsQType::PTR => DnsAType::Ptr(Ptr::parse(self, pos)?),
+            DnsQType::HINFO => DnsAType::HInfo(HInfo::parse(self, pos)?),
+            DnsQType::MX => DnsAType::MX(Mx::parse(self, pos)?),
+            DnsQType::TXT => DnsAType::Txt(Txt::parse(self, pos)?),
+            DnsQType::RP => DnsAType::RP(Rp::parse(self, pos)?),
+            DnsQType::AFSDB => DnsAType::AFSDB(AfsDb::parse(self, pos)?),
+            DnsQType::SIG => DnsAType::SIG(Sig::parse(self, pos)?),
+            DnsQType::KEY => DnsAType::KEY(Key::parse(self, pos)?),
+            DnsQType::AAAA => DnsAType::AAAA(Aaaa::parse(self, pos)?),
+            DnsQType::LOC => DnsAType::Loc(Loc::parse(self, pos)?),
+            DnsQType::SRV => DnsAType::Srv(Srv::parse(self, pos)?),
+            DnsQType::NAPTR => DnsAType::Naptr(Naptr::parse(self, pos)?),
+            DnsQType::KX => DnsAType::KX(Kx::parse(self, pos)?),
+            DnsQType::CERT => DnsAType::Cert(Cert::parse(self, pos)?),
+            DnsQType::DNAME => DnsAType::DName(DName::parse(self, pos)?),
+            DnsQType::OPT => DnsAType::OPT(Opt::parse(self, pos)?),
+            DnsQType::APL => DnsAType::APL(Apl::parse(self, pos)?),
+            DnsQType::DS => DnsAType::DS(Ds::parse(self, pos)?),
+            DnsQType::SSHFP => DnsAType::SSHFP(SshFp::parse(self, pos)?),
+            DnsQType::IPSECKEY => DnsAType::IPSECKEY(IpSecKey::parse(self, pos)?),
+            DnsQType::RRSIG => DnsAType::RRSIG(RRSig::parse(self, pos)?),
+            DnsQType::NSEC => DnsAType::NSEC(Nsec::parse(self, pos)?),
+            DnsQType::DNSKEY => DnsAType::DNSKEY(DnsKey::parse(self, pos)?),
+            DnsQType::DHCID => DnsAType::DHCID(DhcId::parse(self, pos)?),
+            DnsQType::NSEC3 => DnsAType::NSEC3(Nsec3::parse(self, pos)?),
+            DnsQType::NSEC3PARAM => DnsAType::NSEC3PARAM(Nsec3Param::parse(self, pos)?),
+            DnsQType::TLSA => DnsAType::TLSA(Tlsa::parse(self, pos)?),
+            DnsQType::SMIMEA => DnsAType::SMIMEA(SmimeA::parse(self, pos)?),
+            DnsQType::HIP => DnsAType::HIP(Hip::parse(self, pos)?),
+            DnsQType::CDS => DnsAType::CDS(Cds::parse(self, pos)?),
+            DnsQType::CDNSKEY => DnsAType::CDNSKEY(CdnsKey::parse(self, pos)?),
+            DnsQType::OPENPGPKEY => DnsAType::OPENPGPKEY(OpenPgpKey::parse(self, pos)?),
+            DnsQType::CSYNC => DnsAType::CSYNC(CSync::parse(self, pos)?),
+            DnsQType::ZONEMD => DnsAType::ZONEMD(ZoneMd::parse(self, pos)?),
+            DnsQType::SVCB => DnsAType::SVCB(Svcb::parse(self, pos)?),
+            DnsQType::HTTPS => DnsAType::HTTPS(Https::parse(self, pos)?),
+            DnsQType::EUI48 => DnsAType::EUI48(EUI48::parse(self, pos)?),
+            DnsQType::EUI64 => DnsAType::EUI64(EUI64::parse(self, pos)?),
+            DnsQType::TKEY => DnsAType::TKEY(TKey::parse(self, pos)?),
+            DnsQType::TSIG => DnsAType::TSIG(TSig::parse(self, pos)?),
+            DnsQType::IXFR => DnsAType::IXFR(IXfr::parse(self, pos)?),
+            DnsQType::AXFR => DnsAType::AXFR(AXfr::parse(self, pos)?),
+            DnsQType::ALL => DnsAType::Unknown(Unknown::parse(self, pos)?),
+            DnsQType::URI => DnsAType::URI(Uri::parse(self, pos)?),
+            DnsQType::CAA => DnsAType::CAA(Caa::parse(self, pos)?),
+            DnsQType::TA => DnsAType::TA(Ta::parse(self, pos)?),
+            DnsQType::DLV => DnsAType::DLV(Dlv::parse(self, pos)?),
+            DnsQType::Unknown(_) => DnsAType::Unknown(Unknown::parse(self, pos)?),
         })
     }
 }
@@ -280,8 +335,8 @@ pub enum DnsAType<'a> {
     CAA(Caa<'a>),
     TA(Ta<'a>),
     DLV(Dlv<'a>),
-    /// Unknown
-    Reserved,
+    /// A record type without a structured parser.
+    Unknown(Unknown<'a>),
 }
 
 impl<'a> Into<DnsQType> for DnsAType<'a> {
@@ -338,7 +393,7 @@ impl<'a> Into<DnsQType> for DnsAType<'a> {
             DnsAType::CAA(_) => DnsQType::CAA,
             DnsAType::TA(_) => DnsQType::TA,
             DnsAType::DLV(_) => DnsQType::DLV,
-            DnsAType::Reserved => DnsQType::Reserved,
+            DnsAType::Unknown(r) => DnsQType::from_id(r.type_),
         }
     }
 }
@@ -401,7 +456,7 @@ impl<'a> WriteBytes for DnsAType<'a> {
             DnsAType::CAA(r) => r.write(message),
             DnsAType::TA(r) => r.write(message),
             DnsAType::DLV(r) => r.write(message),
-            DnsAType::Reserved => Err(DnsMessageError::DnsError(DnsError::InvalidAnswer)),
+            DnsAType::Unknown(r) => r.write(message),
         }
     }
 }
@@ -435,6 +490,7 @@ mod testutils {
         };
         let parsed = A::parse(&rdata, &mut i).unwrap();
         assert_eq!(parsed, expected);
+        assert_eq!(i, bytes.len());
     }
 
     #[cfg(feature = "arrayvec")]
@@ -542,3 +598,61 @@ mod testutils {
 
     pub(crate) use parse_write_test_macro as parse_write_test;
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn trailing_bytes() {
+        let bytes = [0x00, 0x05, 0x7f, 0x00, 0x00, 0x01, 0xff];
+        let rdata = RData::parse(&bytes, &mut 0, DnsQType::A).unwrap();
+        assert_eq!(
+            rdata.into_parsed(),
+            Err(DnsMessageError::DnsError(DnsError::RDataTrailingBytes))
+        );
+    }
+
+    #[test]
+    fn overrun() {
+        // The address continues into the next record.
+        let bytes = [0x00, 0x03, 0x7f, 0x00, 0x00, 0x01];
+        let mut i = 0;
+        let rdata = RData::parse(&bytes, &mut i, DnsQType::A).unwrap();
+        assert_eq!(i, 5);
+        assert_eq!(
+            rdata.into_parsed(),
+            Err(DnsMessageError::DnsError(DnsError::RDataOverrun))
+        );
+
+        // The name continues into the next record.
+        let bytes = [0x00, 0x05, 0x00, 0x0a, 0x03, b'c', b'o', b'm', 0x00];
+        let rdata = RData::parse(&bytes, &mut 0, DnsQType::MX).unwrap();
+        assert_eq!(
+            rdata.into_parsed(),
+            Err(DnsMessageError::DnsError(DnsError::RDataOverrun))
+        );
+    }
+
+    #[test]
+    fn compressed_name() {
+        // The name may point to a name earlier in the message.
+        let bytes = [
+            0x03, b'c', b'o', b'm', 0x00, // Name
+            0x00, 0x06, // Data length
+            0x00, 0x0a, // Preference
+            0x01, b'a', 0xc0, 0x00, // Exchange
+        ];
+        let mut i = 5;
+        let rdata = RData::parse(&bytes, &mut i, DnsQType::MX).unwrap();
+        match rdata.into_parsed().unwrap() {
+            DnsAType::MX(mx) => {
+                let mut labels = mx.exchange.iter();
+                assert_eq!(labels.next(), Some(Ok(b"a".as_slice())));
+                assert_eq!(labels.next(), Some(Ok(b"com".as_slice())));
+                assert_eq!(labels.next(), None);
+            }
+            rdata => panic!("Expected MX record, got {:?}", rdata),
+        }
+    }
+}
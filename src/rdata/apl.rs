@@ -35,6 +35,7 @@ impl<'a> RDataParse<'a> for Apl<'a> {
         let data = unsafe {
             Characters::new_unchecked(&rdata.buffer[*i - 1..*i + length as usize])
         };
+        *i += length as usize;
 
         Ok(Self {
             address_family,
@@ -1,7 +1,6 @@
 use crate::{Buffer, DnsMessage, DnsMessageError, MutBuffer};
-use crate::characters::Characters;
 use crate::parse::Parse;
-use crate::rdata::{RData, RDataParse};
+use crate::rdata::{RawBytes, RData, RDataParse};
 use crate::write::WriteBytes;
 
 /// # Child DNS Key (CDNSKEY) Record
@@ -16,7 +15,7 @@ pub struct CdnsKey<'a> {
     /// The algorithm field specifies the public key's cryptographic algorithm
     pub algorithm: u8,
     /// The public key field holds the public key material.
-    pub public_key: Characters<'a>
+    pub public_key: RawBytes<'a>
 }
 
 impl<'a> RDataParse<'a> for CdnsKey<'a> {
@@ -25,7 +24,7 @@ impl<'a> RDataParse<'a> for CdnsKey<'a> {
         let flags = u16::parse(rdata, i)?;
         let protocol = u8::parse(rdata, i)?;
         let algorithm = u8::parse(rdata, i)?;
-        let public_key = Characters::parse(rdata, i)?;
+        let public_key = RawBytes::parse(rdata, i)?;
 
         Ok(Self {
             flags,
@@ -61,18 +60,17 @@ mod test {
     use super::*;
 
     parse_write_test!(
-        5,
+        4,
         [
             0x01, 0x02, // flags
             0x03, // protocol
             0x04, // algorithm
-            0x00, // public key
         ],
         CdnsKey {
             flags: 0x0102,
             protocol: 0x03,
             algorithm: 0x04,
-            public_key: unsafe { Characters::new_unchecked(&[]) },
+            public_key: RawBytes::new(&[]),
         },
     );
 }
\ No newline at end of file
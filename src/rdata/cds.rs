@@ -1,7 +1,8 @@
 use crate::{Buffer, DnsMessage, DnsMessageError, MutBuffer};
-use crate::characters::Characters;
+use crate::dnssec::{DigestHasher, DsDigestType};
+use crate::name::DnsName;
 use crate::parse::Parse;
-use crate::rdata::{RData, RDataParse};
+use crate::rdata::{DnsKey, RawBytes, RData, RDataParse};
 use crate::write::WriteBytes;
 
 /// # Child Delegation Signer (CDS) Record
@@ -16,7 +17,30 @@ pub struct Cds<'a> {
     /// The digest type of the key that is being published.
     pub digest_type: u8,
     /// The digest of the key that is being published.
-    pub digest: Characters<'a>,
+    pub digest: RawBytes<'a>,
+}
+
+impl<'a> Cds<'a> {
+    /// Create the record for the key with the given owner name, hashed with
+    /// the hasher of the digest type. The digest is written to the buffer.
+    ///
+    /// According to [RFC 4034 Section 5.1.4](https://tools.ietf.org/rfc/rfc4034#section-5.1.4).
+    pub fn build<H: DigestHasher>(
+        owner: DnsName,
+        key: &DnsKey,
+        digest_type: DsDigestType,
+        hasher: H,
+        buffer: &'a mut [u8],
+    ) -> Result<Self, DnsMessageError> {
+        let digest = crate::dnssec::digest::digest(owner, key, digest_type, hasher, buffer)?;
+
+        Ok(Self {
+            key_tag: key.key_tag(),
+            algorithm: key.algorithm,
+            digest_type: digest_type.id(),
+            digest: RawBytes::new(digest),
+        })
+    }
 }
 
 impl<'a> RDataParse<'a> for Cds<'a> {
@@ -25,7 +49,7 @@ impl<'a> RDataParse<'a> for Cds<'a> {
         let key_tag = u16::parse(rdata, i)?;
         let algorithm = u8::parse(rdata, i)?;
         let digest_type = u8::parse(rdata, i)?;
-        let digest = Characters::parse(rdata, i)?;
+        let digest = RawBytes::parse(rdata, i)?;
 
         Ok(Self {
             key_tag,
@@ -61,13 +85,13 @@ mod test {
     use super::*;
 
     parse_write_test!(
-        5,
-        [ 0x00, 0x01, 0x08, 0x01, 0x00 ],
+        4,
+        [ 0x00, 0x01, 0x08, 0x01 ],
         Cds {
             key_tag: 1,
             algorithm: 8,
             digest_type: 1,
-            digest: unsafe { Characters::new_unchecked(&[]) },
+            digest: RawBytes::new(&[]),
         },
     );
 }
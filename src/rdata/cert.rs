@@ -1,7 +1,6 @@
 use crate::{Buffer, DnsMessage, DnsMessageError, MutBuffer};
-use crate::characters::Characters;
 use crate::parse::Parse;
-use crate::rdata::{RData, RDataParse};
+use crate::rdata::{RawBytes, RData, RDataParse};
 use crate::write::WriteBytes;
 
 /// # Certificate record
@@ -16,7 +15,7 @@ pub struct Cert<'a> {
     /// The algorithm used to sign the certificate.
     pub algorithm: u8,
     /// The certificate data.
-    pub certificate: Characters<'a>,
+    pub certificate: RawBytes<'a>,
 }
 
 impl<'a> RDataParse<'a> for Cert<'a> {
@@ -25,7 +24,7 @@ impl<'a> RDataParse<'a> for Cert<'a> {
         let cert_type = u16::parse(rdata.buffer, i)?;
         let key_tag = u16::parse(rdata.buffer, i)?;
         let algorithm = u8::parse(rdata.buffer, i)?;
-        let certificate = Characters::parse(rdata.buffer, i)?;
+        let certificate = RawBytes::parse(rdata, i)?;
 
         Ok(Self {
             cert_type,
@@ -61,18 +60,17 @@ mod test {
     use super::*;
 
     parse_write_test!(
-        6,
+        5,
         [
             0x00, 0x01, // cert type
             0x02, 0x03, // key tag
             0x04, // algorithm
-            0x00, // certificate length
         ],
         Cert {
             cert_type: 1,
             key_tag: 0x0203,
             algorithm: 4,
-            certificate: unsafe { Characters::new_unchecked(&[]) },
+            certificate: RawBytes::new(&[]),
         },
     );
 }
\ No newline at end of file
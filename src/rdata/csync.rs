@@ -1,7 +1,6 @@
 use crate::{Buffer, DnsMessage, DnsMessageError, MutBuffer};
-use crate::characters::Characters;
 use crate::parse::Parse;
-use crate::rdata::{RData, RDataParse};
+use crate::rdata::{RData, RDataParse, TypeBitMap};
 use crate::write::WriteBytes;
 
 /// # Child-to-Parent Synchronization (CSYNC) Record
@@ -13,7 +12,7 @@ pub struct CSync<'a> {
     /// flags is a bitmap of flags (see [RFC 7477](https://tools.ietf.org/html/rfc7477))
     pub flags: u16,
     /// type_bit_maps is the set of RRset types present at the next owner name in the zone
-    pub type_bit_maps: Characters<'a>,
+    pub type_bit_maps: TypeBitMap<'a>,
 }
 
 impl<'a> RDataParse<'a> for CSync<'a> {
@@ -21,7 +20,7 @@ impl<'a> RDataParse<'a> for CSync<'a> {
     fn parse(rdata: &RData<'a>, i: &mut usize) -> Result<Self, DnsMessageError> {
         let serial = u32::parse(rdata, i)?;
         let flags = u16::parse(rdata, i)?;
-        let type_bit_maps = Characters::parse(rdata, i)?;
+        let type_bit_maps = TypeBitMap::parse(rdata, i)?;
 
         Ok(Self {
             serial,
@@ -55,16 +54,16 @@ mod test {
     use super::*;
 
     parse_write_test!(
-        10,
+        9,
         [
             0x00, 0x01, 0x02, 0x03, // serial
             0x10, 0x01, // flags
-            3, b'w', b'w', b'w', // type_bit_maps
+            0x00, 0x01, 0x40, // type_bit_maps
         ],
         CSync {
             serial: 0x00010203,
             flags: 0x1001,
-            type_bit_maps: unsafe { Characters::new_unchecked(b"www") },
+            type_bit_maps: unsafe { TypeBitMap::new_unchecked(&[0x00, 0x01, 0x40]) },
         },
     );
 }
@@ -1,7 +1,6 @@
 use crate::{Buffer, DnsMessage, DnsMessageError, MutBuffer};
-use crate::characters::Characters;
 use crate::parse::Parse;
-use crate::rdata::{RData, RDataParse};
+use crate::rdata::{RawBytes, RData, RDataParse};
 use crate::write::WriteBytes;
 
 /// # Dynamic host configuration protocol record
@@ -13,7 +12,7 @@ pub struct DhcId<'a> {
     /// The digest type.
     pub digest_type: u8,
     /// The digest of the DHCP information.
-    pub digest: Characters<'a>,
+    pub digest: RawBytes<'a>,
 }
 
 impl<'a> RDataParse<'a> for DhcId<'a> {
@@ -21,7 +20,7 @@ impl<'a> RDataParse<'a> for DhcId<'a> {
     fn parse(rdata: &RData<'a>, i: &mut usize) -> Result<Self, DnsMessageError> {
         let type_ = u16::parse(rdata, i)?;
         let digest_type = u8::parse(rdata, i)?;
-        let digest = Characters::parse(rdata, i)?;
+        let digest = RawBytes::parse(rdata, i)?;
 
         Ok(Self {
             type_,
@@ -55,17 +54,16 @@ mod test {
     use super::*;
 
     parse_write_test!(
-        7,
+        6,
         [
             0x00, 0x0e, // type
             0x03, // digest type
-            0x03, // digest len
             b'w', b'w', b'w', // digest
         ],
         DhcId {
             type_: 14,
             digest_type: 3,
-            digest: unsafe { Characters::new_unchecked(b"www") },
+            digest: RawBytes::new(b"www"),
         },
     );
 }
@@ -1,7 +1,6 @@
 use crate::{Buffer, DnsMessage, DnsMessageError, MutBuffer};
-use crate::characters::Characters;
 use crate::parse::Parse;
-use crate::rdata::{RData, RDataParse};
+use crate::rdata::{RawBytes, RData, RDataParse};
 use crate::write::WriteBytes;
 
 /// # DNSSEC lookaside validation record (DLV)
@@ -16,7 +15,7 @@ pub struct Dlv<'a> {
     /// digest_type is the algorithm used to construct the digest
     pub digest_type: u8,
     /// digest is the digest of the public key
-    pub digest: Characters<'a>,
+    pub digest: RawBytes<'a>,
 }
 
 impl<'a> RDataParse<'a> for Dlv<'a> {
@@ -25,7 +24,7 @@ impl<'a> RDataParse<'a> for Dlv<'a> {
         let key_tag = u16::parse(rdata, i)?;
         let algorithm = u8::parse(rdata, i)?;
         let digest_type = u8::parse(rdata, i)?;
-        let digest = Characters::parse(rdata, i)?;
+        let digest = RawBytes::parse(rdata, i)?;
 
         Ok(Self {
             key_tag,
@@ -61,19 +60,18 @@ mod test {
     use super::*;
 
     parse_write_test!(
-        8,
+        7,
         [
             0x00, 0x0e, // key_tag
             0x03, // algorithm
             0x03, // digest_type
-            0x03, // digest len
             b'w', b'w', b'w', // digest
         ],
         Dlv {
             key_tag: 14,
             algorithm: 3,
             digest_type: 3,
-            digest: unsafe { Characters::new_unchecked(b"www") },
+            digest: RawBytes::new(b"www"),
         },
     );
 }
@@ -1,7 +1,7 @@
 use crate::{Buffer, DnsMessage, DnsMessageError, MutBuffer};
-use crate::characters::Characters;
+use crate::dnssec::DnssecAlgorithm;
 use crate::parse::Parse;
-use crate::rdata::{RData, RDataParse};
+use crate::rdata::{RawBytes, RData, RDataParse};
 use crate::write::WriteBytes;
 
 /// # DNS key record
@@ -16,7 +16,34 @@ pub struct DnsKey<'a> {
     /// The algorithm field specifies the public key's cryptographic algorithm
     pub algorithm: u8,
     /// The public key field holds the public key material.
-    pub public_key: Characters<'a>
+    pub public_key: RawBytes<'a>
+}
+
+impl<'a> DnsKey<'a> {
+    /// Return the key tag of the key, which identifies the key in the RRSIG
+    /// and DS records. Key tags are not unique, several keys may share one.
+    ///
+    /// According to [RFC 4034 Appendix B](https://tools.ietf.org/rfc/rfc4034#appendix-B).
+    pub fn key_tag(&self) -> u16 {
+        let public_key = self.public_key.bytes();
+        if self.algorithm == DnssecAlgorithm::RsaMd5.id() {
+            // The most significant 16 of the least significant 24 bits of
+            // the modulus.
+            return match public_key {
+                [.., a, b, _] => u16::from_be_bytes([*a, *b]),
+                _ => 0,
+            };
+        }
+
+        let mut sum = self.flags as u32;
+        sum += (self.protocol as u32) << 8 | self.algorithm as u32;
+        for chunk in public_key.chunks(2) {
+            sum += (chunk[0] as u32) << 8 | chunk.get(1).copied().unwrap_or(0) as u32;
+        }
+        sum += sum >> 16;
+
+        sum as u16
+    }
 }
 
 impl<'a> RDataParse<'a> for DnsKey<'a> {
@@ -25,7 +52,7 @@ impl<'a> RDataParse<'a> for DnsKey<'a> {
         let flags = u16::parse(rdata, i)?;
         let protocol = u8::parse(rdata, i)?;
         let algorithm = u8::parse(rdata, i)?;
-        let public_key = Characters::parse(rdata, i)?;
+        let public_key = RawBytes::parse(rdata, i)?;
 
         Ok(DnsKey {
             flags,
@@ -61,19 +88,107 @@ mod test {
     use super::*;
 
     parse_write_test!(
-        8,
+        7,
         [
             0x0f, 0x0e, // flags
             0x5c, // protocol
             0x8a, // algorithm
-            0x03, // public key length
             b'w', b'w', b'w', // public key
         ],
         DnsKey {
             flags: 0x0f0e,
             protocol: 0x5c,
             algorithm: 0x8a,
-            public_key: unsafe { Characters::new_unchecked(b"www") },
+            public_key: RawBytes::new(b"www"),
+        },
+    );
+
+    // The root zone key signing key with key tag 20326.
+    // . IN DNSKEY 257 3 8 AwEAAaz/tAm8yTn4Mfeh5eyI96WSVexTBAvkMgJzkKTOiW1vkIbzxeF3+/4RgWOq7HrxRixHlFlExOLAJr5em
+    //     LvN7SWXgnLh4+B5xQlNVz8Og8kvArMtNROxVQuCaSnIDdD5LKyWbRd2n9WGe2R8PzgCmr3EgVLrjyBxWezF0jLHwVN8efS3rCj/EWgvI
+    //     Wgb9tarpVUDK/b58Da+sqqls3eNbuv7pr+eoZG+SrDK6nWeL3c6H5Apxz7LjVc1uTIdsIXxuOLYA4/ilBmSVIzuDWfdRUfhHdY6+cn8HF
+    //     Rm+2hM8AnXGXws9555KrUB5qihylGa8subX2Nn6UwNR1AkUTV74bU=
+    const ROOT_PUBLIC_KEY: [u8; 260] = [
+        0x03, 0x01, 0x00, 0x01, 0xac, 0xff, 0xb4, 0x09, 0xbc, 0xc9, 0x39, 0xf8,
+        0x31, 0xf7, 0xa1, 0xe5, 0xec, 0x88, 0xf7, 0xa5, 0x92, 0x55, 0xec, 0x53,
+        0x04, 0x0b, 0xe4, 0x32, 0x02, 0x73, 0x90, 0xa4, 0xce, 0x89, 0x6d, 0x6f,
+        0x90, 0x86, 0xf3, 0xc5, 0xe1, 0x77, 0xfb, 0xfe, 0x11, 0x81, 0x63, 0xaa,
+        0xec, 0x7a, 0xf1, 0x46, 0x2c, 0x47, 0x94, 0x59, 0x44, 0xc4, 0xe2, 0xc0,
+        0x26, 0xbe, 0x5e, 0x98, 0xbb, 0xcd, 0xed, 0x25, 0x97, 0x82, 0x72, 0xe1,
+        0xe3, 0xe0, 0x79, 0xc5, 0x09, 0x4d, 0x57, 0x3f, 0x0e, 0x83, 0xc9, 0x2f,
+        0x02, 0xb3, 0x2d, 0x35, 0x13, 0xb1, 0x55, 0x0b, 0x82, 0x69, 0x29, 0xc8,
+        0x0d, 0xd0, 0xf9, 0x2c, 0xac, 0x96, 0x6d, 0x17, 0x76, 0x9f, 0xd5, 0x86,
+        0x7b, 0x64, 0x7c, 0x3f, 0x38, 0x02, 0x9a, 0xbd, 0xc4, 0x81, 0x52, 0xeb,
+        0x8f, 0x20, 0x71, 0x59, 0xec, 0xc5, 0xd2, 0x32, 0xc7, 0xc1, 0x53, 0x7c,
+        0x79, 0xf4, 0xb7, 0xac, 0x28, 0xff, 0x11, 0x68, 0x2f, 0x21, 0x68, 0x1b,
+        0xf6, 0xd6, 0xab, 0xa5, 0x55, 0x03, 0x2b, 0xf6, 0xf9, 0xf0, 0x36, 0xbe,
+        0xb2, 0xaa, 0xa5, 0xb3, 0x77, 0x8d, 0x6e, 0xeb, 0xfb, 0xa6, 0xbf, 0x9e,
+        0xa1, 0x91, 0xbe, 0x4a, 0xb0, 0xca, 0xea, 0x75, 0x9e, 0x2f, 0x77, 0x3a,
+        0x1f, 0x90, 0x29, 0xc7, 0x3e, 0xcb, 0x8d, 0x57, 0x35, 0xb9, 0x32, 0x1d,
+        0xb0, 0x85, 0xf1, 0xb8, 0xe2, 0xd8, 0x03, 0x8f, 0xe2, 0x94, 0x19, 0x92,
+        0x54, 0x8c, 0xee, 0x0d, 0x67, 0xdd, 0x45, 0x47, 0xe1, 0x1d, 0xd6, 0x3a,
+        0xf9, 0xc9, 0xfc, 0x1c, 0x54, 0x66, 0xfb, 0x68, 0x4c, 0xf0, 0x09, 0xd7,
+        0x19, 0x7c, 0x2c, 0xf7, 0x9e, 0x79, 0x2a, 0xb5, 0x01, 0xe6, 0xa8, 0xa1,
+        0xca, 0x51, 0x9a, 0xf2, 0xcb, 0x9b, 0x5f, 0x63, 0x67, 0xe9, 0x4c, 0x0d,
+        0x47, 0x50, 0x24, 0x51, 0x35, 0x7b, 0xe1, 0xb5,
+    ];
+
+    parse_write_test!(
+        264,
+        [
+            0x01, 0x01, // flags
+            0x03, // protocol
+            0x08, // algorithm
+            0x03, 0x01, 0x00, 0x01, 0xac, 0xff, 0xb4, 0x09, 0xbc, 0xc9, 0x39, 0xf8,
+            0x31, 0xf7, 0xa1, 0xe5, 0xec, 0x88, 0xf7, 0xa5, 0x92, 0x55, 0xec, 0x53,
+            0x04, 0x0b, 0xe4, 0x32, 0x02, 0x73, 0x90, 0xa4, 0xce, 0x89, 0x6d, 0x6f,
+            0x90, 0x86, 0xf3, 0xc5, 0xe1, 0x77, 0xfb, 0xfe, 0x11, 0x81, 0x63, 0xaa,
+            0xec, 0x7a, 0xf1, 0x46, 0x2c, 0x47, 0x94, 0x59, 0x44, 0xc4, 0xe2, 0xc0,
+            0x26, 0xbe, 0x5e, 0x98, 0xbb, 0xcd, 0xed, 0x25, 0x97, 0x82, 0x72, 0xe1,
+            0xe3, 0xe0, 0x79, 0xc5, 0x09, 0x4d, 0x57, 0x3f, 0x0e, 0x83, 0xc9, 0x2f,
+            0x02, 0xb3, 0x2d, 0x35, 0x13, 0xb1, 0x55, 0x0b, 0x82, 0x69, 0x29, 0xc8,
+            0x0d, 0xd0, 0xf9, 0x2c, 0xac, 0x96, 0x6d, 0x17, 0x76, 0x9f, 0xd5, 0x86,
+            0x7b, 0x64, 0x7c, 0x3f, 0x38, 0x02, 0x9a, 0xbd, 0xc4, 0x81, 0x52, 0xeb,
+            0x8f, 0x20, 0x71, 0x59, 0xec, 0xc5, 0xd2, 0x32, 0xc7, 0xc1, 0x53, 0x7c,
+            0x79, 0xf4, 0xb7, 0xac, 0x28, 0xff, 0x11, 0x68, 0x2f, 0x21, 0x68, 0x1b,
+            0xf6, 0xd6, 0xab, 0xa5, 0x55, 0x03, 0x2b, 0xf6, 0xf9, 0xf0, 0x36, 0xbe,
+            0xb2, 0xaa, 0xa5, 0xb3, 0x77, 0x8d, 0x6e, 0xeb, 0xfb, 0xa6, 0xbf, 0x9e,
+            0xa1, 0x91, 0xbe, 0x4a, 0xb0, 0xca, 0xea, 0x75, 0x9e, 0x2f, 0x77, 0x3a,
+            0x1f, 0x90, 0x29, 0xc7, 0x3e, 0xcb, 0x8d, 0x57, 0x35, 0xb9, 0x32, 0x1d,
+            0xb0, 0x85, 0xf1, 0xb8, 0xe2, 0xd8, 0x03, 0x8f, 0xe2, 0x94, 0x19, 0x92,
+            0x54, 0x8c, 0xee, 0x0d, 0x67, 0xdd, 0x45, 0x47, 0xe1, 0x1d, 0xd6, 0x3a,
+            0xf9, 0xc9, 0xfc, 0x1c, 0x54, 0x66, 0xfb, 0x68, 0x4c, 0xf0, 0x09, 0xd7,
+            0x19, 0x7c, 0x2c, 0xf7, 0x9e, 0x79, 0x2a, 0xb5, 0x01, 0xe6, 0xa8, 0xa1,
+            0xca, 0x51, 0x9a, 0xf2, 0xcb, 0x9b, 0x5f, 0x63, 0x67, 0xe9, 0x4c, 0x0d,
+            0x47, 0x50, 0x24, 0x51, 0x35, 0x7b, 0xe1, 0xb5,
+        ],
+        DnsKey {
+            flags: 257,
+            protocol: 3,
+            algorithm: 8,
+            public_key: RawBytes::new(&ROOT_PUBLIC_KEY),
         },
+        parse_root,
+        write_root,
     );
+
+    #[test]
+    fn key_tag() {
+        let root = DnsKey {
+            flags: 257,
+            protocol: 3,
+            algorithm: 8,
+            public_key: RawBytes::new(&ROOT_PUBLIC_KEY),
+        };
+        assert_eq!(root.key_tag(), 20326);
+
+        // The key tag of RSA/MD5 keys is taken from the modulus.
+        let rsa_md5 = DnsKey {
+            flags: 256,
+            protocol: 3,
+            algorithm: 1,
+            public_key: RawBytes::new(&[0x01, 0x03, 0xab, 0xcd, 0x12, 0x34, 0x56]),
+        };
+        assert_eq!(rsa_md5.key_tag(), 0x1234);
+    }
 }
@@ -1,7 +1,8 @@
 use crate::{Buffer, DnsMessage, DnsMessageError, MutBuffer};
-use crate::characters::Characters;
+use crate::dnssec::{DigestHasher, DsDigestType};
+use crate::name::DnsName;
 use crate::parse::Parse;
-use crate::rdata::{RData, RDataParse};
+use crate::rdata::{DnsKey, RawBytes, RData, RDataParse};
 use crate::write::WriteBytes;
 
 /// # Delegation signer record
@@ -15,7 +16,30 @@ pub struct Ds<'a> {
     /// The digest type of the DNSKEY record.
     pub digest_type: u8,
     /// The digest of the DNSKEY record.
-    pub digest: Characters<'a>,
+    pub digest: RawBytes<'a>,
+}
+
+impl<'a> Ds<'a> {
+    /// Create the record for the key with the given owner name, hashed with
+    /// the hasher of the digest type. The digest is written to the buffer.
+    ///
+    /// According to [RFC 4034 Section 5.1.4](https://tools.ietf.org/rfc/rfc4034#section-5.1.4).
+    pub fn build<H: DigestHasher>(
+        owner: DnsName,
+        key: &DnsKey,
+        digest_type: DsDigestType,
+        hasher: H,
+        buffer: &'a mut [u8],
+    ) -> Result<Self, DnsMessageError> {
+        let digest = crate::dnssec::digest::digest(owner, key, digest_type, hasher, buffer)?;
+
+        Ok(Self {
+            key_tag: key.key_tag(),
+            algorithm: key.algorithm,
+            digest_type: digest_type.id(),
+            digest: RawBytes::new(digest),
+        })
+    }
 }
 
 impl<'a> RDataParse<'a> for Ds<'a> {
@@ -24,7 +48,7 @@ impl<'a> RDataParse<'a> for Ds<'a> {
         let key_tag = u16::parse(rdata.buffer, i)?;
         let algorithm = u8::parse(rdata.buffer, i)?;
         let digest_type = u8::parse(rdata.buffer, i)?;
-        let digest = Characters::parse(rdata.buffer, i)?;
+        let digest = RawBytes::parse(rdata, i)?;
 
         Ok(Ds {
             key_tag,
@@ -60,19 +84,41 @@ mod test {
     use super::*;
 
     parse_write_test!(
-        8,
+        7,
         [
             0x0c, 0x07, // key tag
             0x83, // algorithm
             0x73, // digest type
-            0x03, // digest len
             b'w', b'w', b'w', // digest
         ],
         Ds {
             key_tag: 0x0c07,
             algorithm: 0x83,
             digest_type: 0x73,
-            digest: unsafe { Characters::new_unchecked(b"www") },
+            digest: RawBytes::new(b"www"),
+        },
+    );
+
+    // . IN DS 20326 8 2 E06D44B80B8F1D39A95C0B0D7C65D08458E880409BBC683457104237C7F8EC8D
+    parse_write_test!(
+        36,
+        [
+            0x4f, 0x66, // key tag
+            0x08, // algorithm
+            0x02, // digest type
+            0xe0, 0x6d, 0x44, 0xb8, 0x0b, 0x8f, 0x1d, 0x39, 0xa9, 0x5c, 0x0b, 0x0d, 0x7c, 0x65, 0xd0, 0x84,
+            0x58, 0xe8, 0x80, 0x40, 0x9b, 0xbc, 0x68, 0x34, 0x57, 0x10, 0x42, 0x37, 0xc7, 0xf8, 0xec, 0x8d,
+        ],
+        Ds {
+            key_tag: 20326,
+            algorithm: 8,
+            digest_type: 2,
+            digest: RawBytes::new(&[
+                0xe0, 0x6d, 0x44, 0xb8, 0x0b, 0x8f, 0x1d, 0x39, 0xa9, 0x5c, 0x0b, 0x0d, 0x7c, 0x65, 0xd0, 0x84,
+                0x58, 0xe8, 0x80, 0x40, 0x9b, 0xbc, 0x68, 0x34, 0x57, 0x10, 0x42, 0x37, 0xc7, 0xf8, 0xec, 0x8d,
+            ]),
         },
+        parse_root,
+        write_root,
     );
 }
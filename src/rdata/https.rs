@@ -1,39 +1,33 @@
 use crate::{Buffer, DnsMessage, DnsMessageError, MutBuffer};
-use crate::characters::Characters;
 use crate::name::DnsName;
 use crate::parse::Parse;
-use crate::rdata::{RData, RDataParse};
+use crate::rdata::{RData, RDataParse, SvcParams};
 use crate::write::WriteBytes;
 
-/// # HTTPs certificate record (HTTPS)
-/// This record is used to describe the parameters of a service binding.
+/// # HTTPS service binding record (HTTPS)
+/// This record is the [`Svcb`](crate::rdata::Svcb) record for HTTPS
+/// origins.
+///
+/// According to [RFC 9460 Section 2.2](https://tools.ietf.org/rfc/rfc9460#section-2.2).
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Https<'a> {
-    /// The priority of this target host
+    /// The priority of this record, 0 for alias mode
     pub priority: u16,
-    /// The relative weight for entries with the same priority
-    pub weight: u16,
-    /// The TCP or UDP port on which the service is to be found
-    pub port: u16,
-    /// The domain name of the target host
+    /// The domain name of the alias target or of the alternative endpoint
     pub target: DnsName<'a>,
     /// The parameters of the service binding
-    pub parameters: Characters<'a>,
+    pub parameters: SvcParams<'a>,
 }
 
 impl<'a> RDataParse<'a> for Https<'a> {
     #[inline]
     fn parse(rdata: &RData<'a>, i: &mut usize) -> Result<Self, DnsMessageError> {
         let priority = u16::parse(rdata, i)?;
-        let weight = u16::parse(rdata, i)?;
-        let port = u16::parse(rdata, i)?;
         let target = DnsName::parse(rdata, i)?;
-        let parameters = Characters::parse(rdata, i)?;
+        let parameters = SvcParams::parse(rdata, i)?;
 
         Ok(Self {
             priority,
-            weight,
-            port,
             target,
             parameters
         })
@@ -50,9 +44,8 @@ impl<'a> WriteBytes for Https<'a> {
         let mut bytes = 0;
 
         bytes += self.priority.write(message)?;
-        bytes += self.weight.write(message)?;
-        bytes += self.port.write(message)?;
-        bytes += self.target.write(message)?;
+        // The target name must not be compressed.
+        bytes += message.write_name_uncompressed(self.target)?;
         bytes += self.parameters.write(message)?;
 
         Ok(bytes)
@@ -65,24 +58,40 @@ mod test {
 
     use super::*;
 
+    // example.com. HTTPS 0 foo.example.com.
     parse_write_test!(
-        15,
+        19,
         [
-            0x00, 0x0e, // priority
-            0x00, 0x0e, // weight
-            0x00, 0x0e, // port
-            0x03, // length of "www"
-            b'w', b'w', b'w', // "www"
-            0x00, // end of name
-            0x03, // length of "www"
-            b'w', b'w', b'w', // "www"
+            0x00, 0x00, // priority
+            0x03, b'f', b'o', b'o', // target
+            0x07, b'e', b'x', b'a', b'm', b'p', b'l', b'e',
+            0x03, b'c', b'o', b'm', 0x00,
         ],
         Https {
-            priority: 14,
-            weight: 14,
-            port: 14,
-            target: unsafe { DnsName::new_unchecked(b"\x03www\x00") },
-            parameters: unsafe { Characters::new_unchecked(b"www") },
+            priority: 0,
+            target: unsafe { DnsName::new_unchecked(b"\x03foo\x07example\x03com\x00") },
+            parameters: unsafe { SvcParams::new_unchecked(&[]) },
+        },
+        parse_alias,
+        write_alias,
+    );
+
+    // example.com. HTTPS 16 foo.example.com. port=53
+    parse_write_test!(
+        25,
+        [
+            0x00, 0x10, // priority
+            0x03, b'f', b'o', b'o', // target
+            0x07, b'e', b'x', b'a', b'm', b'p', b'l', b'e',
+            0x03, b'c', b'o', b'm', 0x00,
+            0x00, 0x03, // key port
+            0x00, 0x02, // length
+            0x00, 0x35, // port 53
+        ],
+        Https {
+            priority: 16,
+            target: unsafe { DnsName::new_unchecked(b"\x03foo\x07example\x03com\x00") },
+            parameters: unsafe { SvcParams::new_unchecked(&[0x00, 0x03, 0x00, 0x02, 0x00, 0x35]) },
         },
     );
 }
@@ -1,7 +1,6 @@
 use crate::{Buffer, DnsMessage, DnsMessageError, MutBuffer};
-use crate::characters::Characters;
 use crate::parse::Parse;
-use crate::rdata::{RData, RDataParse};
+use crate::rdata::{RawBytes, RData, RDataParse};
 use crate::write::WriteBytes;
 
 /// # Key
@@ -15,7 +14,7 @@ pub struct Key<'a> {
     /// The algorithm field is used to store the algorithm number for this key
     pub algorithm: u8,
     /// The public key is stored as a character string
-    pub public_key: Characters<'a>,
+    pub public_key: RawBytes<'a>,
 }
 
 impl<'a> RDataParse<'a> for Key<'a> {
@@ -24,7 +23,7 @@ impl<'a> RDataParse<'a> for Key<'a> {
         let flags = u16::parse(rdata, i)?;
         let protocol = u8::parse(rdata, i)?;
         let algorithm = u8::parse(rdata, i)?;
-        let public_key = Characters::parse(rdata, i)?;
+        let public_key = RawBytes::parse(rdata, i)?;
 
         Ok(Self {
             flags,
@@ -60,19 +59,18 @@ mod test {
     use super::*;
 
     parse_write_test!(
-        8,
+        7,
         [
             0x00, 0x0e, // flags
             0xc4, // protocol
             0x4c, // algorithm
-            0x03, // length
             b'w', b'w', b'w', // "www"
         ],
         Key {
             flags: 14,
             protocol: 196,
             algorithm: 76,
-            public_key: unsafe { Characters::new_unchecked(b"www") },
+            public_key: RawBytes::new(b"www"),
         },
     );
 }
@@ -0,0 +1,175 @@
+use crate::{Buffer, DnsError, DnsMessage, DnsMessageError, MutBuffer};
+use crate::rdata::{RData, RDataParse};
+use crate::write::WriteBytes;
+
+// An entry is the flags followed by an IPv4 address.
+const ENTRY_SIZE: usize = 6;
+
+/// # NetBIOS general name service record
+/// This record is used to return the addresses of a NetBIOS name, each with
+/// the flags of its owner. It is only used in NBNS messages, use
+/// [`RData::into_parsed_nbns`] to parse it.
+///
+/// According to [RFC 1002 Section 4.2.1.3](https://tools.ietf.org/rfc/rfc1002#section-4.2.1.3).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Nb<'a> {
+    /// The entries
+    data: &'a [u8],
+}
+
+impl<'a> Nb<'a> {
+    /// Creates a new NB record from its entries of 6 bytes each. If the
+    /// length is not a multiple of 6, this function will return an error.
+    #[inline(always)]
+    pub fn new(data: &'a [u8]) -> Result<Self, DnsMessageError> {
+        if !data.chunks_exact(ENTRY_SIZE).remainder().is_empty() {
+            return Err(DnsMessageError::DnsError(DnsError::InvalidNetbiosRecord));
+        }
+
+        Ok(Self {
+            data,
+        })
+    }
+
+    /// Returns an iterator over the entries.
+    #[inline(always)]
+    pub fn iter(&self) -> NbIterator<'a> {
+        NbIterator {
+            entries: self.data.chunks_exact(ENTRY_SIZE),
+        }
+    }
+}
+
+impl<'a> RDataParse<'a> for Nb<'a> {
+    #[inline]
+    fn parse(rdata: &RData<'a>, i: &mut usize) -> Result<Self, DnsMessageError> {
+        let end = rdata.pos + rdata.len;
+        let nb = Self::new(&rdata.buffer[*i..end])?;
+        *i = end;
+
+        Ok(nb)
+    }
+}
+
+impl<'a> WriteBytes for Nb<'a> {
+    #[inline]
+    fn write<
+        const PTR_STORAGE: usize,
+        const DNS_SECTION: usize,
+        B: MutBuffer + Buffer,
+    >(&self, message: &mut DnsMessage<PTR_STORAGE, DNS_SECTION, B>) -> Result<usize, DnsMessageError> {
+        message.write_bytes(self.data)
+    }
+}
+
+/// An entry of an NB record.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct NbEntry {
+    /// Whether the name is a group name rather than a unique name.
+    pub group: bool,
+    /// The type of the node owning the name.
+    pub node_type: NbNodeType,
+    /// The IPv4 address of the node.
+    pub address: [u8; 4],
+}
+
+/// An iterator over the entries of an NB record.
+#[derive(Clone, Debug)]
+pub struct NbIterator<'a> {
+    entries: core::slice::ChunksExact<'a, u8>,
+}
+
+impl<'a> Iterator for NbIterator<'a> {
+    type Item = NbEntry;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry = self.entries.next()?;
+
+        Some(NbEntry {
+            group: entry[0] & 0b1000_0000 != 0,
+            node_type: NbNodeType::from_id(entry[0] >> 5),
+            address: [entry[2], entry[3], entry[4], entry[5]],
+        })
+    }
+}
+
+/// The type of a node owning a NetBIOS name.
+///
+/// According to [RFC 1002 Section 4.2.1.3](https://tools.ietf.org/rfc/rfc1002#section-4.2.1.3).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum NbNodeType {
+    /// A node resolving names by broadcast.
+    Broadcast,
+    /// A node resolving names through the name server.
+    PointToPoint,
+    /// A node resolving names by broadcast first, then through the name
+    /// server.
+    Mixed,
+    /// A reserved node type, used by some implementations for hybrid nodes.
+    Reserved,
+}
+
+impl NbNodeType {
+    /// Create a node type from the two bits of its id.
+    #[inline(always)]
+    pub fn from_id(id: u8) -> Self {
+        match id & 0b11 {
+            0 => NbNodeType::Broadcast,
+            1 => NbNodeType::PointToPoint,
+            2 => NbNodeType::Mixed,
+            _ => NbNodeType::Reserved,
+        }
+    }
+
+    /// Get the id of the node type.
+    #[inline(always)]
+    pub fn id(&self) -> u8 {
+        match self {
+            NbNodeType::Broadcast => 0,
+            NbNodeType::PointToPoint => 1,
+            NbNodeType::Mixed => 2,
+            NbNodeType::Reserved => 3,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rdata::testutils::parse_write_test;
+
+    use super::*;
+
+    parse_write_test!(
+        12,
+        [
+            0x00, 0x00, 0xc0, 0xa8, 0x01, 0x14, // B node, unique
+            0xc0, 0x00, 0x0a, 0x00, 0x00, 0x01, // M node, group
+        ],
+        Nb {
+            data: &[
+                0x00, 0x00, 0xc0, 0xa8, 0x01, 0x14,
+                0xc0, 0x00, 0x0a, 0x00, 0x00, 0x01,
+            ],
+        },
+    );
+
+    #[test]
+    fn iter() {
+        let nb = Nb::new(&[0x00, 0x00, 0xc0, 0xa8, 0x01, 0x14, 0xc0, 0x00, 0x0a, 0x00, 0x00, 0x01]).unwrap();
+        let mut entries = nb.iter();
+        assert_eq!(entries.next(), Some(NbEntry {
+            group: false,
+            node_type: NbNodeType::Broadcast,
+            address: [192, 168, 1, 20],
+        }));
+        assert_eq!(entries.next(), Some(NbEntry {
+            group: true,
+            node_type: NbNodeType::Mixed,
+            address: [10, 0, 0, 1],
+        }));
+        assert_eq!(entries.next(), None);
+
+        assert_eq!(Nb::new(&[0x00; 5]), Err(DnsMessageError::DnsError(DnsError::InvalidNetbiosRecord)));
+    }
+}
@@ -0,0 +1,205 @@
+use crate::{Buffer, DnsError, DnsMessage, DnsMessageError, MutBuffer};
+use crate::nbns::NetbiosName;
+use crate::parse::Parse;
+use crate::rdata::{NbNodeType, RawBytes, RData, RDataParse};
+use crate::write::WriteBytes;
+
+// A name is the NetBIOS name followed by its flags.
+const NAME_SIZE: usize = 18;
+
+/// # NetBIOS node status record
+/// This record is used to return the names registered by a node and its
+/// statistics. It is only used in NBNS messages, where its type collides
+/// with the SRV record, use [`RData::into_parsed_nbns`] to parse it.
+///
+/// According to [RFC 1002 Section 4.2.18](https://tools.ietf.org/rfc/rfc1002#section-4.2.18).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct NbStat<'a> {
+    /// The names
+    names: &'a [u8],
+    /// The statistics
+    statistics: RawBytes<'a>,
+}
+
+impl<'a> NbStat<'a> {
+    /// Creates a new NBSTAT record from its names of 18 bytes each and its
+    /// statistics. If the names are invalid, this function will return an
+    /// error.
+    #[inline(always)]
+    pub fn new(names: &'a [u8], statistics: &'a [u8]) -> Result<Self, DnsMessageError> {
+        if !names.chunks_exact(NAME_SIZE).remainder().is_empty() || names.len() / NAME_SIZE > u8::MAX as usize {
+            return Err(DnsMessageError::DnsError(DnsError::InvalidNetbiosRecord));
+        }
+
+        Ok(Self {
+            names,
+            statistics: RawBytes::new(statistics),
+        })
+    }
+
+    /// Returns an iterator over the names registered by the node.
+    #[inline(always)]
+    pub fn names(&self) -> NbStatIterator<'a> {
+        NbStatIterator {
+            names: self.names.chunks_exact(NAME_SIZE),
+        }
+    }
+
+    /// Returns the statistics of the node in wire format, of which many
+    /// implementations only fill the unit id.
+    #[inline(always)]
+    pub fn statistics(&self) -> &'a [u8] {
+        self.statistics.bytes()
+    }
+
+    /// Returns the unit id of the node, usually its MAC address, if the
+    /// statistics contain it.
+    #[inline(always)]
+    pub fn unit_id(&self) -> Option<[u8; 6]> {
+        self.statistics().get(..6)?.try_into().ok()
+    }
+}
+
+impl<'a> RDataParse<'a> for NbStat<'a> {
+    #[inline]
+    fn parse(rdata: &RData<'a>, i: &mut usize) -> Result<Self, DnsMessageError> {
+        let count = u8::parse(rdata, i)? as usize;
+        let end = *i + count * NAME_SIZE;
+        if end > rdata.pos + rdata.len {
+            return Err(DnsMessageError::DnsError(DnsError::RDataOverrun));
+        }
+        let names = &rdata.buffer[*i..end];
+        *i = end;
+        let statistics = RawBytes::parse(rdata, i)?;
+
+        Ok(Self {
+            names,
+            statistics,
+        })
+    }
+}
+
+impl<'a> WriteBytes for NbStat<'a> {
+    #[inline]
+    fn write<
+        const PTR_STORAGE: usize,
+        const DNS_SECTION: usize,
+        B: MutBuffer + Buffer,
+    >(&self, message: &mut DnsMessage<PTR_STORAGE, DNS_SECTION, B>) -> Result<usize, DnsMessageError> {
+        let mut bytes = 0;
+
+        bytes += ((self.names.len() / NAME_SIZE) as u8).write(message)?;
+        bytes += message.write_bytes(self.names)?;
+        bytes += self.statistics.write(message)?;
+
+        Ok(bytes)
+    }
+}
+
+/// A name registered by a node, from an NBSTAT record.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct NbStatName {
+    /// The NetBIOS name.
+    pub name: NetbiosName,
+    /// Whether the name is a group name rather than a unique name.
+    pub group: bool,
+    /// The type of the node owning the name.
+    pub node_type: NbNodeType,
+    /// Whether the name is being deregistered.
+    pub deregistering: bool,
+    /// Whether the name is in conflict.
+    pub conflict: bool,
+    /// Whether the name is active.
+    pub active: bool,
+    /// Whether the name is the permanent node name.
+    pub permanent: bool,
+}
+
+/// An iterator over the names of an NBSTAT record.
+#[derive(Clone, Debug)]
+pub struct NbStatIterator<'a> {
+    names: core::slice::ChunksExact<'a, u8>,
+}
+
+impl<'a> Iterator for NbStatIterator<'a> {
+    type Item = NbStatName;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let name = self.names.next()?;
+        let mut bytes = [0; 16];
+        bytes.copy_from_slice(&name[..16]);
+        let flags = name[16];
+
+        Some(NbStatName {
+            name: NetbiosName::from_bytes(bytes),
+            group: flags & 0b1000_0000 != 0,
+            node_type: NbNodeType::from_id(flags >> 5),
+            deregistering: flags & 0b0001_0000 != 0,
+            conflict: flags & 0b0000_1000 != 0,
+            active: flags & 0b0000_0100 != 0,
+            permanent: flags & 0b0000_0010 != 0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rdata::testutils::parse_write_test;
+
+    use super::*;
+
+    const NAMES: [u8; 36] = [
+        b'W', b'O', b'R', b'K', b'S', b'T', b'A', b'T', b'I', b'O', b'N', b' ', b' ', b' ', b' ', 0x00,
+        0x04, 0x00, // Unique, B node, active
+        b'W', b'O', b'R', b'K', b'G', b'R', b'O', b'U', b'P', b' ', b' ', b' ', b' ', b' ', b' ', 0x00,
+        0x84, 0x00, // Group, B node, active
+    ];
+
+    parse_write_test!(
+        43,
+        [
+            0x02, // Number of names
+            b'W', b'O', b'R', b'K', b'S', b'T', b'A', b'T', b'I', b'O', b'N', b' ', b' ', b' ', b' ', 0x00,
+            0x04, 0x00,
+            b'W', b'O', b'R', b'K', b'G', b'R', b'O', b'U', b'P', b' ', b' ', b' ', b' ', b' ', b' ', 0x00,
+            0x84, 0x00,
+            0x00, 0x11, 0x22, 0x33, 0x44, 0x55, // Unit id
+        ],
+        NbStat {
+            names: &NAMES,
+            statistics: RawBytes::new(&[0x00, 0x11, 0x22, 0x33, 0x44, 0x55]),
+        },
+    );
+
+    #[test]
+    fn names() {
+        let nbstat = NbStat::new(&NAMES, &[0x00, 0x11, 0x22, 0x33, 0x44, 0x55]).unwrap();
+        let mut names = nbstat.names();
+        let name = names.next().unwrap();
+        assert_eq!(name.name, NetbiosName::new(b"WORKSTATION", 0x00).unwrap());
+        assert!(!name.group);
+        assert_eq!(name.node_type, NbNodeType::Broadcast);
+        assert!(name.active);
+        assert!(!name.conflict);
+        let name = names.next().unwrap();
+        assert_eq!(name.name.name(), b"WORKGROUP");
+        assert!(name.group);
+        assert_eq!(names.next(), None);
+
+        assert_eq!(nbstat.unit_id(), Some([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]));
+        assert_eq!(nbstat.statistics(), &[0x00, 0x11, 0x22, 0x33, 0x44, 0x55]);
+        assert_eq!(NbStat::new(&NAMES, &[]).unwrap().unit_id(), None);
+        assert_eq!(NbStat::new(&NAMES[1..], &[]), Err(DnsMessageError::DnsError(DnsError::InvalidNetbiosRecord)));
+    }
+
+    #[test]
+    fn overrun() {
+        // Three names are announced, but the rdata ends before them.
+        let rdata = RData::parse(&[0x00, 0x02, 0x03, 0x00], &mut 0, crate::question::DnsQType::ALL).unwrap();
+        assert_eq!(
+            NbStat::parse(&rdata, &mut 2),
+            Err(DnsMessageError::DnsError(DnsError::RDataOverrun)),
+        );
+    }
+}
@@ -1,8 +1,7 @@
 use crate::{Buffer, DnsMessage, DnsMessageError, MutBuffer};
-use crate::characters::Characters;
 use crate::name::DnsName;
 use crate::parse::Parse;
-use crate::rdata::{RData, RDataParse};
+use crate::rdata::{RData, RDataParse, TypeBitMap};
 use crate::write::WriteBytes;
 
 /// # Next secure record
@@ -12,14 +11,14 @@ pub struct Nsec<'a> {
     /// The next owner name in the canonical ordering of the zone.
     pub next_domain_name: DnsName<'a>,
     /// The set of RR types present at the NSEC RR's owner name.
-    pub type_bit_maps: Characters<'a>,
+    pub type_bit_maps: TypeBitMap<'a>,
 }
 
 impl<'a> RDataParse<'a> for Nsec<'a> {
     #[inline]
     fn parse(rdata: &RData<'a>, i: &mut usize) -> Result<Self, DnsMessageError> {
         let next_domain_name = DnsName::parse(rdata, i)?;
-        let type_bit_maps = Characters::parse(rdata, i)?;
+        let type_bit_maps = TypeBitMap::parse(rdata, i)?;
 
         Ok(Self {
             next_domain_name,
@@ -51,17 +50,44 @@ mod test {
     use super::*;
 
     parse_write_test!(
-        9,
+        8,
         [
             0x03, // length of "www"
             b'w', b'w', b'w', // "www"
             0x00, // end of name
-            0x03, // length of "www"
-            b'w', b'w', b'w', // "www"
+            0x00, 0x01, 0x40, // type bit maps
         ],
         Nsec {
             next_domain_name: unsafe { DnsName::new_unchecked(b"\x03www\x00") },
-            type_bit_maps: unsafe { Characters::new_unchecked(b"www") },
+            type_bit_maps: unsafe { TypeBitMap::new_unchecked(&[0x00, 0x01, 0x40]) },
+        },
+    );
+
+    // alfa.example.com. IN NSEC host.example.com. A MX RRSIG NSEC TYPE1234
+    // From RFC 4034 Section 4.3.
+    parse_write_test!(
+        55,
+        [
+            0x04, b'h', b'o', b's', b't', // next domain name
+            0x07, b'e', b'x', b'a', b'm', b'p', b'l', b'e',
+            0x03, b'c', b'o', b'm', 0x00,
+            0x00, 0x06, 0x40, 0x01, 0x00, 0x00, 0x00, 0x03, // window 0
+            0x04, 0x1b, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // window 4
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x20,
+        ],
+        Nsec {
+            next_domain_name: unsafe { DnsName::new_unchecked(b"\x04host\x07example\x03com\x00") },
+            type_bit_maps: unsafe { TypeBitMap::new_unchecked(&[
+                0x00, 0x06, 0x40, 0x01, 0x00, 0x00, 0x00, 0x03,
+                0x04, 0x1b, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x00, 0x00, 0x00, 0x20,
+            ]) },
         },
+        parse_rfc4034,
+        write_rfc4034,
     );
 }
@@ -1,9 +1,14 @@
 use crate::{Buffer, DnsMessage, DnsMessageError, MutBuffer};
 use crate::characters::Characters;
+use crate::dnssec::{DigestHasher, Nsec3Hash};
+use crate::name::DnsName;
 use crate::parse::Parse;
-use crate::rdata::{RData, RDataParse};
+use crate::rdata::{RData, RDataParse, TypeBitMap};
 use crate::write::WriteBytes;
 
+// The owner name of this record may not cover an unsigned delegation.
+const OPT_OUT_FLAG: u8 = 0x01;
+
 /// # Next secure record version 3
 /// This record is used to provide authenticated denial of existence for DNSSEC.
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -19,7 +24,28 @@ pub struct Nsec3<'a> {
     /// The next hashed owner name in the canonical ordering of the zone.
     pub next_hashed_owner_name: Characters<'a>,
     /// The type bit maps field.
-    pub type_bit_maps: Characters<'a>,
+    pub type_bit_maps: TypeBitMap<'a>,
+}
+
+impl<'a> Nsec3<'a> {
+    /// Return whether the record may cover unsigned delegations.
+    #[inline(always)]
+    pub fn opt_out(&self) -> bool {
+        self.flags & OPT_OUT_FLAG != 0
+    }
+
+    /// Hash the name with the parameters of this record and the SHA-1
+    /// hasher. Only the SHA-1 hash algorithm is supported.
+    ///
+    /// According to [RFC 5155 Section 5](https://tools.ietf.org/rfc/rfc5155#section-5).
+    #[inline]
+    pub fn hash<H: DigestHasher + Clone>(
+        &self,
+        name: &DnsName,
+        hasher: &H,
+    ) -> Result<Nsec3Hash, DnsMessageError> {
+        crate::dnssec::nsec3::hash(name, false, self.hash_algorithm, self.salt.bytes(), self.iterations, hasher)
+    }
 }
 
 impl<'a> RDataParse<'a> for Nsec3<'a> {
@@ -30,7 +56,7 @@ impl<'a> RDataParse<'a> for Nsec3<'a> {
         let iterations = u16::parse(rdata, i)?;
         let salt = Characters::parse(rdata, i)?;
         let next_hashed_owner_name = Characters::parse(rdata, i)?;
-        let type_bit_maps = Characters::parse(rdata, i)?;
+        let type_bit_maps = TypeBitMap::parse(rdata, i)?;
 
         Ok(Self {
             hash_algorithm,
@@ -70,7 +96,7 @@ mod test {
     use super::*;
 
     parse_write_test!(
-        16,
+        15,
         [
             0x0e, // hash algorithm
             0x0a, // flags
@@ -79,8 +105,7 @@ mod test {
             b'a', b'b', b'c',
             0x03, // length of next hashed owner name
             b'd', b'e', b'f',
-            0x03, // length of type bit maps
-            b'w', b'w', b'w',
+            0x00, 0x01, 0x40, // type bit maps
         ],
         Nsec3 {
             hash_algorithm: 0x0e,
@@ -88,7 +113,7 @@ mod test {
             iterations: 0x000e,
             salt: unsafe { Characters::new_unchecked(b"abc") },
             next_hashed_owner_name: unsafe { Characters::new_unchecked(b"def") },
-            type_bit_maps: unsafe { Characters::new_unchecked(b"www") },
+            type_bit_maps: unsafe { TypeBitMap::new_unchecked(&[0x00, 0x01, 0x40]) },
         },
     );
 }
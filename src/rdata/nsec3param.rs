@@ -1,5 +1,7 @@
 use crate::{Buffer, DnsMessage, DnsMessageError, MutBuffer};
 use crate::characters::Characters;
+use crate::dnssec::{DigestHasher, Nsec3Hash};
+use crate::name::DnsName;
 use crate::parse::Parse;
 use crate::rdata::{RData, RDataParse};
 use crate::write::WriteBytes;
@@ -18,6 +20,21 @@ pub struct Nsec3Param<'a> {
     pub salt: Characters<'a>,
 }
 
+impl<'a> Nsec3Param<'a> {
+    /// Hash the name with the parameters of this record and the SHA-1
+    /// hasher. Only the SHA-1 hash algorithm is supported.
+    ///
+    /// According to [RFC 5155 Section 5](https://tools.ietf.org/rfc/rfc5155#section-5).
+    #[inline]
+    pub fn hash<H: DigestHasher + Clone>(
+        &self,
+        name: &DnsName,
+        hasher: &H,
+    ) -> Result<Nsec3Hash, DnsMessageError> {
+        crate::dnssec::nsec3::hash(name, false, self.hash_algorithm, self.salt.bytes(), self.iterations, hasher)
+    }
+}
+
 impl<'a> RDataParse<'a> for Nsec3Param<'a> {
     #[inline]
     fn parse(rdata: &RData<'a>, i: &mut usize) -> Result<Self, DnsMessageError> {
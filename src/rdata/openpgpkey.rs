@@ -1,7 +1,5 @@
 use crate::{Buffer, DnsMessage, DnsMessageError, MutBuffer};
-use crate::characters::Characters;
-use crate::parse::Parse;
-use crate::rdata::{RData, RDataParse};
+use crate::rdata::{RawBytes, RData, RDataParse};
 use crate::write::WriteBytes;
 
 /// # OpenPGP Key Record (OPENPGPKEY)
@@ -11,29 +9,17 @@ use crate::write::WriteBytes;
 /// TLSA record [RFC6698].
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct OpenPgpKey<'a> {
-    /// flags is a bitmap of flags (see [RFC 4880](https://tools.ietf.org/html/rfc4880))
-    pub flags: u16,
-    /// algorithm is the algorithm of the public key
-    pub algorithm: u8,
-    /// public_key is the public key
-    pub public_key: Characters<'a>,
-    /// fingerprint is the fingerprint of the referenced public key
-    pub fingerprint: Characters<'a>,
+    /// public_key is the transferable public key (see [RFC 4880](https://tools.ietf.org/html/rfc4880#section-11.1))
+    pub public_key: RawBytes<'a>,
 }
 
 impl<'a> RDataParse<'a> for OpenPgpKey<'a> {
     #[inline]
     fn parse(rdata: &RData<'a>, i: &mut usize) -> Result<Self, DnsMessageError> {
-        let flags = u16::parse(rdata, i)?;
-        let algorithm = u8::parse(rdata, i)?;
-        let public_key = Characters::parse(rdata, i)?;
-        let fingerprint = Characters::parse(rdata, i)?;
+        let public_key = RawBytes::parse(rdata, i)?;
 
         Ok(Self {
-            flags,
-            algorithm,
             public_key,
-            fingerprint
         })
     }
 }
@@ -45,14 +31,7 @@ impl<'a> WriteBytes for OpenPgpKey<'a> {
         const DNS_SECTION: usize,
         B: MutBuffer + Buffer,
     >(&self, message: &mut DnsMessage<PTR_STORAGE, DNS_SECTION, B>) -> Result<usize, DnsMessageError> {
-        let mut bytes = 0;
-
-        bytes += self.flags.write(message)?;
-        bytes += self.algorithm.write(message)?;
-        bytes += self.public_key.write(message)?;
-        bytes += self.fingerprint.write(message)?;
-
-        Ok(bytes)
+        self.public_key.write(message)
     }
 }
 
@@ -63,18 +42,13 @@ mod test {
     use super::*;
 
     parse_write_test!(
-        11,
+        8,
         [
-            0x00, 0x0e, // flags
-            0x0a, // algorithm
-            0x03, b'w', b'w', b'w', // public key
-            0x03, b'w', b'w', b'w', // fingerprint
+            0x99, 0x01, 0xa2, 0x04, // public key packet header
+            0x4f, 0x2d, 0x3a, 0x1c, // creation time
         ],
         OpenPgpKey {
-            flags: 0x000e,
-            algorithm: 0x0a,
-            public_key: unsafe { Characters::new_unchecked(b"www") },
-            fingerprint: unsafe { Characters::new_unchecked(b"www") },
+            public_key: RawBytes::new(&[0x99, 0x01, 0xa2, 0x04, 0x4f, 0x2d, 0x3a, 0x1c]),
         },
     );
 }
@@ -1,30 +1,90 @@
-use crate::{Buffer, DnsMessage, DnsMessageError, MutBuffer};
-use crate::characters::Characters;
+use crate::{Buffer, DnsError, DnsMessage, DnsMessageError, MutBuffer};
+use crate::edns::EdnsOption;
 use crate::parse::Parse;
 use crate::rdata::{RData, RDataParse};
 use crate::write::WriteBytes;
 
 /// # Option record
-/// This record is used to store options for the DNS protocol.
+/// This record is used to store options for the DNS protocol (EDNS(0)).
+/// The record data is a sequence of options, each consisting of a 16 bit
+/// option code, a 16 bit option length and the option data.
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Opt<'a> {
-    /// The code for the option.
-    pub code: u16,
-    /// The data for the option.
-    pub data: Characters<'a>,
+    /// The options in wire format
+    data: &'a [u8],
 }
 
-impl<'a> RDataParse<'a> for Opt<'a> {
-    #[inline]
-    fn parse(rdata: &RData<'a>, i: &mut usize) -> Result<Self, DnsMessageError> {
-        let code = u16::parse(rdata.buffer, i)?;
-        let data = Characters::parse(rdata.buffer, i)?;
+impl<'a> Opt<'a> {
+    /// Creates a new option record and checks the data. The data needs to be
+    /// in DNS wire format. If the framing of the options is invalid, this
+    /// function will return an error. The contents of the options are checked
+    /// when iterating.
+    ///
+    /// # Example
+    /// ```
+    /// use flex_dns::rdata::Opt;
+    ///
+    /// let opt = Opt::new(
+    ///     b"\x00\x0a\x00\x08\x01\x02\x03\x04\x05\x06\x07\x08"
+    /// ).unwrap();
+    /// ```
+    #[inline(always)]
+    pub fn new(data: &'a [u8]) -> Result<Self, DnsMessageError> {
+        let mut pos = 0;
+        while let Some(r) = next_option(data, &mut pos) {
+            r?;
+        }
 
         Ok(Self {
-            code,
             data,
         })
     }
+
+    /// Creates a new option record without checking the data.
+    /// This function is unsafe because it doesn't check the data.
+    /// If the data is invalid it can lead to an invalid DNS message.
+    ///
+    /// # Safety
+    /// The data must be a valid sequence of options in DNS wire format.
+    #[inline(always)]
+    pub const unsafe fn new_unchecked(data: &'a [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    /// Creates a new option record without any options.
+    #[inline(always)]
+    pub const fn empty() -> Self {
+        Self {
+            data: &[],
+        }
+    }
+
+    /// Returns the options in wire format.
+    #[inline(always)]
+    pub fn bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Returns an iterator over the options.
+    #[inline(always)]
+    pub fn iter(&self) -> OptIterator<'a> {
+        OptIterator {
+            data: self.data,
+            pos: 0,
+        }
+    }
+}
+
+impl<'a> RDataParse<'a> for Opt<'a> {
+    #[inline]
+    fn parse(rdata: &RData<'a>, i: &mut usize) -> Result<Self, DnsMessageError> {
+        let data = &rdata.buffer[*i..*i + rdata.len];
+        *i += rdata.len;
+
+        Self::new(data)
+    }
 }
 
 impl<'a> WriteBytes for Opt<'a> {
@@ -34,13 +94,55 @@ impl<'a> WriteBytes for Opt<'a> {
         const DNS_SECTION: usize,
         B: MutBuffer + Buffer,
     >(&self, message: &mut DnsMessage<PTR_STORAGE, DNS_SECTION, B>) -> Result<usize, DnsMessageError> {
-        let mut bytes = 0;
+        message.write_bytes(self.data)
+    }
+}
+
+/// An iterator over the options of an option record.
+#[derive(Copy, Clone, Debug)]
+pub struct OptIterator<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Iterator for OptIterator<'a> {
+    type Item = Result<EdnsOption<'a>, DnsMessageError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(next_option(self.data, &mut self.pos)?.and_then(|(code, data)| {
+            EdnsOption::parse(code, data)
+        }))
+    }
+}
 
-        bytes += self.code.write(message)?;
-        bytes += self.data.write(message)?;
+fn next_option<'a>(data: &'a [u8], pos: &mut usize) -> Option<Result<(u16, &'a [u8]), DnsMessageError>> {
+    if *pos >= data.len() {
+        return None;
+    }
+
+    let header = <[u8; 4]>::parse(data, pos);
+    let (code, length) = match header {
+        Ok([c0, c1, l0, l1]) => (
+            u16::from_be_bytes([c0, c1]),
+            u16::from_be_bytes([l0, l1]) as usize,
+        ),
+        Err(_) => {
+            *pos = data.len();
+            return Some(Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption)));
+        }
+    };
+
+    let end = *pos + length;
 
-        Ok(bytes)
+    if end > data.len() {
+        *pos = data.len();
+        return Some(Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption)));
     }
+
+    let result = &data[*pos..end];
+    *pos = end;
+
+    Some(Ok((code, result)))
 }
 
 #[cfg(test)]
@@ -50,15 +152,41 @@ mod test {
     use super::*;
 
     parse_write_test!(
-        6,
+        16,
         [
-            0x00, 0x0e, // code
-            0x03, // length
-            b'w', b'w', b'w', // data
+            0x00, 0x0a, // code
+            0x00, 0x08, // length
+            0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, // data
+            0x00, 0x0c, // code
+            0x00, 0x00, // length
         ],
         Opt {
-            code: 14,
-            data: unsafe { Characters::new_unchecked(b"www") },
+            data: &[
+                0x00, 0x0a, 0x00, 0x08,
+                0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08,
+                0x00, 0x0c, 0x00, 0x00,
+            ],
         },
     );
+
+    #[test]
+    fn iterate() {
+        let opt = Opt::new(b"\x00\x0a\x00\x02ab\x00\x0c\x00\x00").unwrap();
+        let mut iter = opt.iter();
+        assert_eq!(iter.next(), Some(Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption))));
+        assert_eq!(iter.next(), Some(Ok(EdnsOption::Padding(0))));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn invalid_length() {
+        assert_eq!(
+            Opt::new(b"\x00\x0a\x00\x03ab"),
+            Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption))
+        );
+        assert_eq!(
+            Opt::new(b"\x00\x0a\x00"),
+            Err(DnsMessageError::DnsError(DnsError::InvalidEdnsOption))
+        );
+    }
 }
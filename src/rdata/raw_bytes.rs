@@ -0,0 +1,74 @@
+use core::fmt::Display;
+use crate::{Buffer, DnsError, DnsMessage, DnsMessageError, MutBuffer};
+use crate::rdata::{RData, RDataParse};
+use crate::write::WriteBytes;
+
+/// Raw bytes running to the end of the record data, like signatures, public
+/// keys and digests. Unlike [`Characters`](crate::characters::Characters)
+/// there is no length byte, the length is given by the length of the record
+/// data.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RawBytes<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> RawBytes<'a> {
+    /// Create a new [`RawBytes`] from a byte slice.
+    #[inline(always)]
+    pub const fn new(bytes: &'a [u8]) -> Self {
+        RawBytes { bytes }
+    }
+
+    /// Return the bytes.
+    #[inline(always)]
+    pub const fn bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+}
+
+impl<'a> RDataParse<'a> for RawBytes<'a> {
+    #[inline]
+    fn parse(rdata: &RData<'a>, i: &mut usize) -> Result<Self, DnsMessageError> {
+        let end = rdata.pos + rdata.len;
+        if *i > end {
+            return Err(DnsMessageError::DnsError(DnsError::UnexpectedEndOfBuffer));
+        }
+
+        let bytes = &rdata.buffer[*i..end];
+        *i = end;
+
+        Ok(RawBytes { bytes })
+    }
+}
+
+impl<'a> WriteBytes for RawBytes<'a> {
+    #[inline]
+    fn write<
+        const PTR_STORAGE: usize,
+        const DNS_SECTION: usize,
+        B: MutBuffer + Buffer,
+    >(
+        &self,
+        message: &mut DnsMessage<PTR_STORAGE, DNS_SECTION, B>,
+    ) -> Result<usize, DnsMessageError> {
+        message.write_bytes(self.bytes)
+    }
+}
+
+impl<'a> AsRef<[u8]> for RawBytes<'a> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.bytes
+    }
+}
+
+impl<'a> Display for RawBytes<'a> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for &byte in self.bytes.iter() {
+            write!(f, "{:02x}", byte)?;
+        }
+
+        Ok(())
+    }
+}
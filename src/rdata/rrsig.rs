@@ -1,8 +1,7 @@
 use crate::{Buffer, DnsMessage, DnsMessageError, MutBuffer};
-use crate::characters::Characters;
 use crate::name::DnsName;
 use crate::parse::Parse;
-use crate::rdata::{RData, RDataParse};
+use crate::rdata::{RawBytes, RData, RDataParse};
 use crate::write::WriteBytes;
 
 /// # DNSSEC signature record
@@ -14,6 +13,9 @@ pub struct RRSig<'a> {
     pub type_covered: u16,
     /// The algorithm used to create the signature.
     pub algorithm: u8,
+    /// The number of labels in the original owner name, without the root
+    /// label and a leading wildcard label.
+    pub labels: u8,
     /// The number of seconds the signature is valid for.
     pub original_ttl: u32,
     /// The time at which the signature was created.
@@ -25,7 +27,7 @@ pub struct RRSig<'a> {
     /// The name of the zone that this signature was created for.
     pub signer_name: DnsName<'a>,
     /// The signature.
-    pub signature: Characters<'a>,
+    pub signature: RawBytes<'a>,
 }
 
 impl<'a> RDataParse<'a> for RRSig<'a> {
@@ -33,16 +35,18 @@ impl<'a> RDataParse<'a> for RRSig<'a> {
     fn parse(rdata: &RData<'a>, i: &mut usize) -> Result<Self, DnsMessageError> {
         let type_covered = u16::parse(rdata.buffer, i)?;
         let algorithm = u8::parse(rdata.buffer, i)?;
+        let labels = u8::parse(rdata.buffer, i)?;
         let original_ttl = u32::parse(rdata.buffer, i)?;
         let signature_expiration = u32::parse(rdata.buffer, i)?;
         let signature_inception = u32::parse(rdata.buffer, i)?;
         let key_tag = u16::parse(rdata.buffer, i)?;
         let signer_name = DnsName::parse(rdata.buffer, i)?;
-        let signature = Characters::parse(rdata.buffer, i)?;
+        let signature = RawBytes::parse(rdata, i)?;
 
         Ok(Self {
             type_covered,
             algorithm,
+            labels,
             original_ttl,
             signature_expiration,
             signature_inception,
@@ -64,11 +68,13 @@ impl<'a> WriteBytes for RRSig<'a> {
 
         bytes += self.type_covered.write(message)?;
         bytes += self.algorithm.write(message)?;
+        bytes += self.labels.write(message)?;
         bytes += self.original_ttl.write(message)?;
         bytes += self.signature_expiration.write(message)?;
         bytes += self.signature_inception.write(message)?;
         bytes += self.key_tag.write(message)?;
-        bytes += self.signer_name.write(message)?;
+        // The signer's name must not be compressed.
+        bytes += message.write_name_uncompressed(self.signer_name)?;
         bytes += self.signature.write(message)?;
 
         Ok(bytes)
@@ -81,30 +87,58 @@ mod test {
 
     use super::*;
 
+    // host.example.com. 86400 IN RRSIG A 5 3 86400 20030322173103 (
+    //     20030220173103 2642 example.com. oJB1W6WNGv+ldvQ3WDG0MQkg5IEhjRip8WTrPYGv07h1
+    //     08dUKGMeDPKijVCHX3DDKdfb+v6oB9wfuh3DTJXUAfI/M0zmO/zz8bW0Rznl8O3tGNazPwQKkRN20XPXV
+    //     6nwwfoXmJQbsLNrLfkGJ5D6fwFm8nN+6pBzeDQfsS3Ap3o= )
+    // from RFC 4034 Section 3.3.
     parse_write_test!(
-        42,
+        159,
         [
-            0x00, 0x0e, // type covered
+            0x00, 0x01, // type covered
             0x05, // algorithm
-            0x00, 0x00, 0x00, 0x0a, // original ttl
-            0x00, 0x00, 0x00, 0x0b, // signature expiration
-            0x00, 0x00, 0x00, 0x0c, // signature inception
-            0x00, 0x0d, // key tag
-            0x03, b'w', b'w', b'w',
+            0x03, // labels
+            0x00, 0x01, 0x51, 0x80, // original ttl
+            0x3e, 0x7c, 0x9d, 0xd7, // signature expiration
+            0x3e, 0x55, 0x10, 0xd7, // signature inception
+            0x0a, 0x52, // key tag
             0x07, b'e', b'x', b'a', b'm', b'p', b'l', b'e',
             0x03, b'c', b'o', b'm',
             0x00, // signer name
-            0x07, b'f', b'o', b'o', b'-', b'b', b'a', b'r', // signature
+            0xa0, 0x90, 0x75, 0x5b, 0xa5, 0x8d, 0x1a, 0xff, 0xa5, 0x76, 0xf4, 0x37,
+            0x58, 0x31, 0xb4, 0x31, 0x09, 0x20, 0xe4, 0x81, 0x21, 0x8d, 0x18, 0xa9,
+            0xf1, 0x64, 0xeb, 0x3d, 0x81, 0xaf, 0xd3, 0xb8, 0x75, 0xd3, 0xc7, 0x54,
+            0x28, 0x63, 0x1e, 0x0c, 0xf2, 0xa2, 0x8d, 0x50, 0x87, 0x5f, 0x70, 0xc3,
+            0x29, 0xd7, 0xdb, 0xfa, 0xfe, 0xa8, 0x07, 0xdc, 0x1f, 0xba, 0x1d, 0xc3,
+            0x4c, 0x95, 0xd4, 0x01, 0xf2, 0x3f, 0x33, 0x4c, 0xe6, 0x3b, 0xfc, 0xf3,
+            0xf1, 0xb5, 0xb4, 0x47, 0x39, 0xe5, 0xf0, 0xed, 0xed, 0x18, 0xd6, 0xb3,
+            0x3f, 0x04, 0x0a, 0x91, 0x13, 0x76, 0xd1, 0x73, 0xd7, 0x57, 0xa9, 0xf0,
+            0xc1, 0xfa, 0x17, 0x98, 0x94, 0x1b, 0xb0, 0xb3, 0x6b, 0x2d, 0xf9, 0x06,
+            0x27, 0x90, 0xfa, 0x7f, 0x01, 0x66, 0xf2, 0x73, 0x7e, 0xea, 0x90, 0x73,
+            0x78, 0x34, 0x1f, 0xb1, 0x2d, 0xc0, 0xa7, 0x7a,
         ],
         RRSig {
-            type_covered: 14,
+            type_covered: 1,
             algorithm: 5,
-            original_ttl: 10,
-            signature_expiration: 11,
-            signature_inception: 12,
-            key_tag: 13,
-            signer_name: unsafe { DnsName::new_unchecked(b"\x03www\x07example\x03com\x00") },
-            signature: unsafe { Characters::new_unchecked(b"foo-bar") },
+            labels: 3,
+            original_ttl: 86400,
+            signature_expiration: 1048354263,
+            signature_inception: 1045762263,
+            key_tag: 2642,
+            signer_name: unsafe { DnsName::new_unchecked(b"\x07example\x03com\x00") },
+            signature: RawBytes::new(&[
+                0xa0, 0x90, 0x75, 0x5b, 0xa5, 0x8d, 0x1a, 0xff, 0xa5, 0x76, 0xf4, 0x37,
+                0x58, 0x31, 0xb4, 0x31, 0x09, 0x20, 0xe4, 0x81, 0x21, 0x8d, 0x18, 0xa9,
+                0xf1, 0x64, 0xeb, 0x3d, 0x81, 0xaf, 0xd3, 0xb8, 0x75, 0xd3, 0xc7, 0x54,
+                0x28, 0x63, 0x1e, 0x0c, 0xf2, 0xa2, 0x8d, 0x50, 0x87, 0x5f, 0x70, 0xc3,
+                0x29, 0xd7, 0xdb, 0xfa, 0xfe, 0xa8, 0x07, 0xdc, 0x1f, 0xba, 0x1d, 0xc3,
+                0x4c, 0x95, 0xd4, 0x01, 0xf2, 0x3f, 0x33, 0x4c, 0xe6, 0x3b, 0xfc, 0xf3,
+                0xf1, 0xb5, 0xb4, 0x47, 0x39, 0xe5, 0xf0, 0xed, 0xed, 0x18, 0xd6, 0xb3,
+                0x3f, 0x04, 0x0a, 0x91, 0x13, 0x76, 0xd1, 0x73, 0xd7, 0x57, 0xa9, 0xf0,
+                0xc1, 0xfa, 0x17, 0x98, 0x94, 0x1b, 0xb0, 0xb3, 0x6b, 0x2d, 0xf9, 0x06,
+                0x27, 0x90, 0xfa, 0x7f, 0x01, 0x66, 0xf2, 0x73, 0x7e, 0xea, 0x90, 0x73,
+                0x78, 0x34, 0x1f, 0xb1, 0x2d, 0xc0, 0xa7, 0x7a,
+            ]),
         },
     );
 }
@@ -1,8 +1,7 @@
 use crate::{Buffer, DnsMessage, DnsMessageError, MutBuffer};
-use crate::characters::Characters;
 use crate::name::DnsName;
 use crate::parse::Parse;
-use crate::rdata::{RData, RDataParse};
+use crate::rdata::{RawBytes, RData, RDataParse};
 use crate::write::WriteBytes;
 
 /// # Signature
@@ -26,7 +25,7 @@ pub struct Sig<'a> {
     /// The signer's name
     pub signer_name: DnsName<'a>,
     /// The signature
-    pub signature: Characters<'a>,
+    pub signature: RawBytes<'a>,
 }
 
 impl<'a> RDataParse<'a> for Sig<'a> {
@@ -40,7 +39,7 @@ impl<'a> RDataParse<'a> for Sig<'a> {
         let signature_inception = u32::parse(rdata, i)?;
         let key_tag = u16::parse(rdata, i)?;
         let signer_name = DnsName::parse(rdata, i)?;
-        let signature = Characters::parse(rdata, i)?;
+        let signature = RawBytes::parse(rdata, i)?;
 
         Ok(Self {
             type_covered,
@@ -72,7 +71,8 @@ impl<'a> WriteBytes for Sig<'a> {
         bytes += self.signature_expiration.write(message)?;
         bytes += self.signature_inception.write(message)?;
         bytes += self.key_tag.write(message)?;
-        bytes += self.signer_name.write(message)?;
+        // The signer's name must not be compressed.
+        bytes += message.write_name_uncompressed(self.signer_name)?;
         bytes += self.signature.write(message)?;
 
         Ok(bytes)
@@ -86,7 +86,7 @@ mod test {
     use super::*;
 
     parse_write_test!(
-        43,
+        42,
         [
             0x00, 0x0e, // type covered
             0x05, // algorithm
@@ -99,7 +99,7 @@ mod test {
             0x07, b'e', b'x', b'a', b'm', b'p', b'l', b'e',
             0x03, b'c', b'o', b'm',
             0x00, // signer name
-            0x07, b'f', b'o', b'o', b'-', b'b', b'a', b'r', // signature
+            b'f', b'o', b'o', b'-', b'b', b'a', b'r', // signature
         ],
         Sig {
             type_covered: 14,
@@ -110,7 +110,7 @@ mod test {
             signature_inception: 12,
             key_tag: 13,
             signer_name: unsafe { DnsName::new_unchecked(b"\x03www\x07example\x03com\x00") },
-            signature: unsafe { Characters::new_unchecked(b"foo-bar") },
+            signature: RawBytes::new(b"foo-bar"),
         },
     );
 }
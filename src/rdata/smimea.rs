@@ -1,7 +1,6 @@
 use crate::{Buffer, DnsMessage, DnsMessageError, MutBuffer};
-use crate::characters::Characters;
 use crate::parse::Parse;
-use crate::rdata::{RData, RDataParse};
+use crate::rdata::{RawBytes, RData, RDataParse};
 use crate::write::WriteBytes;
 
 /// # S/MIME cert association record (SMIMEA)
@@ -15,7 +14,7 @@ pub struct SmimeA<'a> {
     /// The matching type of the certificate
     pub matching_type: u8,
     /// The certificate data
-    pub certificate: Characters<'a>,
+    pub certificate: RawBytes<'a>,
 }
 
 impl<'a> RDataParse<'a> for SmimeA<'a> {
@@ -24,7 +23,7 @@ impl<'a> RDataParse<'a> for SmimeA<'a> {
         let usage = u8::parse(rdata, i)?;
         let selector = u8::parse(rdata, i)?;
         let matching_type = u8::parse(rdata, i)?;
-        let certificate = Characters::parse(rdata, i)?;
+        let certificate = RawBytes::parse(rdata, i)?;
 
         Ok(Self {
             usage,
@@ -60,12 +59,11 @@ mod test {
     use super::*;
 
     parse_write_test!(
-        19,
+        18,
         [
             0x0e, // usage
             0x0f, // selector
             0x10, // matching type
-            0x0f, // length of certificate
             0x01, 0x02, 0x03, 0x04, 0x05,
             0x06, 0x07, 0x08, 0x09, 0x0a,
             0x0b, 0x0c, 0x0d, 0x0e, 0x0f, // certificate
@@ -74,11 +72,11 @@ mod test {
             usage: 0x0e,
             selector: 0x0f,
             matching_type: 0x10,
-            certificate: unsafe { Characters::new_unchecked(&[
+            certificate: RawBytes::new(&[
                 0x01, 0x02, 0x03, 0x04, 0x05,
                 0x06, 0x07, 0x08, 0x09, 0x0a,
                 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
-            ]) },
+            ]),
         },
     );
 }
@@ -1,7 +1,6 @@
 use crate::{Buffer, DnsMessage, DnsMessageError, MutBuffer};
-use crate::characters::Characters;
 use crate::parse::Parse;
-use crate::rdata::{RData, RDataParse};
+use crate::rdata::{RawBytes, RData, RDataParse};
 use crate::write::WriteBytes;
 
 /// # SSH public key fingerprint record
@@ -13,7 +12,7 @@ pub struct SshFp<'a> {
     /// The fingerprint type.
     pub fingerprint_type: u8,
     /// The fingerprint data.
-    pub data: Characters<'a>,
+    pub data: RawBytes<'a>,
 }
 
 impl<'a> RDataParse<'a> for SshFp<'a> {
@@ -21,7 +20,7 @@ impl<'a> RDataParse<'a> for SshFp<'a> {
     fn parse(rdata: &RData<'a>, i: &mut usize) -> Result<Self, DnsMessageError> {
         let algorithm = u8::parse(rdata.buffer, i)?;
         let fingerprint_type = u8::parse(rdata.buffer, i)?;
-        let data = Characters::parse(rdata.buffer, i)?;
+        let data = RawBytes::parse(rdata, i)?;
 
         Ok(Self {
             algorithm,
@@ -55,17 +54,16 @@ mod test {
     use super::*;
 
     parse_write_test!(
-        6,
+        5,
         [
             0x01, // algorithm
             0x02, // fingerprint type
-            0x03, // length of "www"
             b'w', b'w', b'w', // "www"
         ],
         SshFp {
             algorithm: 1,
             fingerprint_type: 2,
-            data: unsafe { Characters::new_unchecked(b"www") },
+            data: RawBytes::new(b"www"),
         },
     );
 }
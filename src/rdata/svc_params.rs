@@ -0,0 +1,677 @@
+use crate::{Buffer, BufferError, DnsError, DnsMessage, DnsMessageError, MutBuffer};
+use crate::rdata::{RawBytes, RData, RDataParse};
+use crate::write::WriteBytes;
+
+/// # Service parameters
+/// The parameters of a [`Svcb`](crate::rdata::Svcb) or
+/// [`Https`](crate::rdata::Https) record. Each parameter is encoded as its
+/// key, the length of its value and the value itself, the keys are in
+/// strictly increasing order.
+///
+/// According to [RFC 9460 Section 2.2](https://tools.ietf.org/rfc/rfc9460#section-2.2).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SvcParams<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> SvcParams<'a> {
+    /// Create a new [`SvcParams`] from its wire format. The keys must be in
+    /// strictly increasing order and the values of the known keys must be
+    /// well formed.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, DnsMessageError> {
+        let mut position = 0;
+        let mut last_key = None;
+        while position < bytes.len() {
+            let (key, value) = Self::split_param(bytes, position)
+                .ok_or(DnsMessageError::DnsError(DnsError::InvalidSvcParam))?;
+            if last_key.is_some_and(|last| key <= last) {
+                return Err(DnsMessageError::DnsError(DnsError::InvalidSvcParam));
+            }
+
+            SvcParam::parse(key, value)?;
+            last_key = Some(key);
+            position += 4 + value.len();
+        }
+
+        Ok(Self { bytes })
+    }
+
+    /// Create a new [`SvcParams`] from its wire format without checking the
+    /// parameters.
+    ///
+    /// # Safety
+    /// The parameters are not checked, this can lead to an invalid DNS
+    /// message. Iterating over invalid parameters stops at the first invalid
+    /// parameter.
+    #[inline(always)]
+    pub const unsafe fn new_unchecked(bytes: &'a [u8]) -> Self {
+        SvcParams { bytes }
+    }
+
+    /// Return an iterator over the parameters.
+    #[inline]
+    pub fn iter(&self) -> SvcParamIterator<'a> {
+        SvcParamIterator {
+            bytes: self.bytes,
+            position: 0,
+        }
+    }
+
+    /// Return the parameter with the given key, if present.
+    #[inline]
+    pub fn get(&self, key: SvcParamKey) -> Option<SvcParam<'a>> {
+        self.iter().find(|param| param.key() == key)
+    }
+
+    /// Return whether there are no parameters.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Return the wire format of the parameters.
+    #[inline(always)]
+    pub const fn bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    #[inline]
+    fn split_param(bytes: &'a [u8], position: usize) -> Option<(u16, &'a [u8])> {
+        let header = bytes.get(position..position + 4)?;
+        let key = u16::from_be_bytes([header[0], header[1]]);
+        let length = u16::from_be_bytes([header[2], header[3]]) as usize;
+        let value = bytes.get(position + 4..position + 4 + length)?;
+
+        Some((key, value))
+    }
+}
+
+impl<'a> RDataParse<'a> for SvcParams<'a> {
+    #[inline]
+    fn parse(rdata: &RData<'a>, i: &mut usize) -> Result<Self, DnsMessageError> {
+        let bytes = RawBytes::parse(rdata, i)?;
+
+        Self::new(bytes.bytes())
+    }
+}
+
+impl<'a> WriteBytes for SvcParams<'a> {
+    #[inline]
+    fn write<
+        const PTR_STORAGE: usize,
+        const DNS_SECTION: usize,
+        B: MutBuffer + Buffer,
+    >(&self, message: &mut DnsMessage<PTR_STORAGE, DNS_SECTION, B>) -> Result<usize, DnsMessageError> {
+        message.write_bytes(self.bytes)
+    }
+}
+
+impl<'a> IntoIterator for SvcParams<'a> {
+    type Item = SvcParam<'a>;
+    type IntoIter = SvcParamIterator<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the parameters of [`SvcParams`].
+#[derive(Clone, Debug)]
+pub struct SvcParamIterator<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Iterator for SvcParamIterator<'a> {
+    type Item = SvcParam<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, value) = SvcParams::split_param(self.bytes, self.position)?;
+        let param = SvcParam::parse(key, value).ok()?;
+        self.position += 4 + value.len();
+
+        Some(param)
+    }
+}
+
+/// The key of a service parameter.
+///
+/// According to [RFC 9460 Section 14.3.2](https://tools.ietf.org/rfc/rfc9460#section-14.3.2).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SvcParamKey {
+    Mandatory,
+    Alpn,
+    NoDefaultAlpn,
+    Port,
+    Ipv4Hint,
+    Ech,
+    Ipv6Hint,
+    DohPath,
+    Unknown(u16),
+}
+
+impl SvcParamKey {
+    /// Create a new [`SvcParamKey`] from its numeric value.
+    #[inline(always)]
+    pub fn from_id(id: u16) -> Self {
+        match id {
+            0 => SvcParamKey::Mandatory,
+            1 => SvcParamKey::Alpn,
+            2 => SvcParamKey::NoDefaultAlpn,
+            3 => SvcParamKey::Port,
+            4 => SvcParamKey::Ipv4Hint,
+            5 => SvcParamKey::Ech,
+            6 => SvcParamKey::Ipv6Hint,
+            7 => SvcParamKey::DohPath,
+            id => SvcParamKey::Unknown(id),
+        }
+    }
+
+    /// Return the numeric value of the key.
+    #[inline(always)]
+    pub fn id(&self) -> u16 {
+        match self {
+            SvcParamKey::Mandatory => 0,
+            SvcParamKey::Alpn => 1,
+            SvcParamKey::NoDefaultAlpn => 2,
+            SvcParamKey::Port => 3,
+            SvcParamKey::Ipv4Hint => 4,
+            SvcParamKey::Ech => 5,
+            SvcParamKey::Ipv6Hint => 6,
+            SvcParamKey::DohPath => 7,
+            SvcParamKey::Unknown(id) => *id,
+        }
+    }
+}
+
+impl From<SvcParamKey> for u16 {
+    #[inline(always)]
+    fn from(key: SvcParamKey) -> Self {
+        key.id()
+    }
+}
+
+impl From<u16> for SvcParamKey {
+    #[inline(always)]
+    fn from(id: u16) -> Self {
+        SvcParamKey::from_id(id)
+    }
+}
+
+/// A service parameter.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SvcParam<'a> {
+    /// The keys which must be understood by the client to use the record.
+    Mandatory(SvcParamKeys<'a>),
+    /// The application protocol identifiers supported by the service.
+    Alpn(AlpnIds<'a>),
+    /// The service does not support the default protocol of the scheme.
+    NoDefaultAlpn,
+    /// The port on which the service is to be found.
+    Port(u16),
+    /// IPv4 addresses which may be used to reach the service.
+    Ipv4Hint(&'a [[u8; 4]]),
+    /// The encrypted client hello configuration list.
+    Ech(&'a [u8]),
+    /// IPv6 addresses which may be used to reach the service.
+    Ipv6Hint(&'a [[u8; 16]]),
+    /// The URI template of a DNS over HTTPS service.
+    DohPath(&'a str),
+    /// A parameter with an unknown key.
+    Unknown {
+        key: u16,
+        value: &'a [u8],
+    },
+}
+
+impl<'a> SvcParam<'a> {
+    /// Return the key of the parameter.
+    #[inline]
+    pub fn key(&self) -> SvcParamKey {
+        match self {
+            SvcParam::Mandatory(_) => SvcParamKey::Mandatory,
+            SvcParam::Alpn(_) => SvcParamKey::Alpn,
+            SvcParam::NoDefaultAlpn => SvcParamKey::NoDefaultAlpn,
+            SvcParam::Port(_) => SvcParamKey::Port,
+            SvcParam::Ipv4Hint(_) => SvcParamKey::Ipv4Hint,
+            SvcParam::Ech(_) => SvcParamKey::Ech,
+            SvcParam::Ipv6Hint(_) => SvcParamKey::Ipv6Hint,
+            SvcParam::DohPath(_) => SvcParamKey::DohPath,
+            SvcParam::Unknown { key, .. } => SvcParamKey::Unknown(*key),
+        }
+    }
+
+    fn parse(key: u16, value: &'a [u8]) -> Result<Self, DnsMessageError> {
+        let invalid = DnsMessageError::DnsError(DnsError::InvalidSvcParam);
+        match SvcParamKey::from_id(key) {
+            SvcParamKey::Mandatory => {
+                if value.is_empty() || value.len() & 1 != 0 {
+                    return Err(invalid);
+                }
+
+                Ok(SvcParam::Mandatory(SvcParamKeys { bytes: value }))
+            }
+            SvcParamKey::Alpn => {
+                let mut position = 0;
+                while position < value.len() {
+                    let length = value[position] as usize;
+                    if length == 0 || position + 1 + length > value.len() {
+                        return Err(invalid);
+                    }
+
+                    position += 1 + length;
+                }
+                if value.is_empty() {
+                    return Err(invalid);
+                }
+
+                Ok(SvcParam::Alpn(AlpnIds { bytes: value }))
+            }
+            SvcParamKey::NoDefaultAlpn => {
+                if !value.is_empty() {
+                    return Err(invalid);
+                }
+
+                Ok(SvcParam::NoDefaultAlpn)
+            }
+            SvcParamKey::Port => {
+                if value.len() != 2 {
+                    return Err(invalid);
+                }
+
+                Ok(SvcParam::Port(u16::from_be_bytes([value[0], value[1]])))
+            }
+            SvcParamKey::Ipv4Hint => {
+                if value.is_empty() || value.len() & 0x03 != 0 {
+                    return Err(invalid);
+                }
+
+                // SAFETY: `[u8; 4]` has the same alignment as `u8` and the
+                // length is a multiple of 4.
+                let addresses = unsafe {
+                    core::slice::from_raw_parts(value.as_ptr() as *const [u8; 4], value.len() / 4)
+                };
+
+                Ok(SvcParam::Ipv4Hint(addresses))
+            }
+            SvcParamKey::Ech => Ok(SvcParam::Ech(value)),
+            SvcParamKey::Ipv6Hint => {
+                if value.is_empty() || value.len() & 0x0F != 0 {
+                    return Err(invalid);
+                }
+
+                // SAFETY: `[u8; 16]` has the same alignment as `u8` and the
+                // length is a multiple of 16.
+                let addresses = unsafe {
+                    core::slice::from_raw_parts(value.as_ptr() as *const [u8; 16], value.len() / 16)
+                };
+
+                Ok(SvcParam::Ipv6Hint(addresses))
+            }
+            SvcParamKey::DohPath => {
+                let path = core::str::from_utf8(value).map_err(|_| invalid)?;
+
+                Ok(SvcParam::DohPath(path))
+            }
+            SvcParamKey::Unknown(key) => Ok(SvcParam::Unknown { key, value }),
+        }
+    }
+}
+
+/// The list of keys of a mandatory parameter.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SvcParamKeys<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Iterator for SvcParamKeys<'a> {
+    type Item = SvcParamKey;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, rest) = self.bytes.split_at(self.bytes.len().min(2));
+        if key.len() != 2 {
+            return None;
+        }
+        self.bytes = rest;
+
+        Some(SvcParamKey::from_id(u16::from_be_bytes([key[0], key[1]])))
+    }
+}
+
+/// The list of application protocol identifiers of an alpn parameter.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AlpnIds<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Iterator for AlpnIds<'a> {
+    type Item = &'a [u8];
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (&length, rest) = self.bytes.split_first()?;
+        let id = rest.get(..length as usize)?;
+        self.bytes = &rest[length as usize..];
+
+        Some(id)
+    }
+}
+
+/// A builder for [`SvcParams`], writing the parameters into a buffer. The
+/// parameters must be added in strictly increasing key order.
+pub struct SvcParamsBuilder<'a> {
+    buffer: &'a mut [u8],
+    position: usize,
+    last_key: Option<u16>,
+}
+
+impl<'a> SvcParamsBuilder<'a> {
+    /// Create a new builder writing into the given buffer.
+    #[inline]
+    pub fn new(buffer: &'a mut [u8]) -> Self {
+        Self {
+            buffer,
+            position: 0,
+            last_key: None,
+        }
+    }
+
+    /// Add the keys which must be understood by the client. The keys must be
+    /// in strictly increasing order and must not contain the mandatory key
+    /// itself.
+    pub fn mandatory(&mut self, keys: &[SvcParamKey]) -> Result<&mut Self, DnsMessageError> {
+        if keys.is_empty()
+            || keys.contains(&SvcParamKey::Mandatory)
+            || keys.windows(2).any(|w| w[0].id() >= w[1].id()) {
+            return Err(DnsMessageError::DnsError(DnsError::InvalidSvcParam));
+        }
+
+        let value = self.begin(SvcParamKey::Mandatory, keys.len() * 2)?;
+        for (chunk, key) in value.chunks_exact_mut(2).zip(keys) {
+            chunk.copy_from_slice(&key.id().to_be_bytes());
+        }
+
+        Ok(self)
+    }
+
+    /// Add the application protocol identifiers supported by the service.
+    pub fn alpn(&mut self, ids: &[&[u8]]) -> Result<&mut Self, DnsMessageError> {
+        if ids.is_empty() || ids.iter().any(|id| id.is_empty() || id.len() > u8::MAX as usize) {
+            return Err(DnsMessageError::DnsError(DnsError::InvalidSvcParam));
+        }
+
+        let length = ids.iter().map(|id| 1 + id.len()).sum();
+        let value = self.begin(SvcParamKey::Alpn, length)?;
+        let mut position = 0;
+        for id in ids {
+            value[position] = id.len() as u8;
+            value[position + 1..position + 1 + id.len()].copy_from_slice(id);
+            position += 1 + id.len();
+        }
+
+        Ok(self)
+    }
+
+    /// Mark the default protocol of the scheme as unsupported.
+    pub fn no_default_alpn(&mut self) -> Result<&mut Self, DnsMessageError> {
+        self.begin(SvcParamKey::NoDefaultAlpn, 0)?;
+
+        Ok(self)
+    }
+
+    /// Add the port on which the service is to be found.
+    pub fn port(&mut self, port: u16) -> Result<&mut Self, DnsMessageError> {
+        self.begin(SvcParamKey::Port, 2)?.copy_from_slice(&port.to_be_bytes());
+
+        Ok(self)
+    }
+
+    /// Add IPv4 addresses which may be used to reach the service.
+    pub fn ipv4_hint(&mut self, addresses: &[[u8; 4]]) -> Result<&mut Self, DnsMessageError> {
+        if addresses.is_empty() {
+            return Err(DnsMessageError::DnsError(DnsError::InvalidSvcParam));
+        }
+
+        let value = self.begin(SvcParamKey::Ipv4Hint, addresses.len() * 4)?;
+        for (chunk, address) in value.chunks_exact_mut(4).zip(addresses) {
+            chunk.copy_from_slice(address);
+        }
+
+        Ok(self)
+    }
+
+    /// Add the encrypted client hello configuration list.
+    pub fn ech(&mut self, config: &[u8]) -> Result<&mut Self, DnsMessageError> {
+        self.begin(SvcParamKey::Ech, config.len())?.copy_from_slice(config);
+
+        Ok(self)
+    }
+
+    /// Add IPv6 addresses which may be used to reach the service.
+    pub fn ipv6_hint(&mut self, addresses: &[[u8; 16]]) -> Result<&mut Self, DnsMessageError> {
+        if addresses.is_empty() {
+            return Err(DnsMessageError::DnsError(DnsError::InvalidSvcParam));
+        }
+
+        let value = self.begin(SvcParamKey::Ipv6Hint, addresses.len() * 16)?;
+        for (chunk, address) in value.chunks_exact_mut(16).zip(addresses) {
+            chunk.copy_from_slice(address);
+        }
+
+        Ok(self)
+    }
+
+    /// Add the URI template of a DNS over HTTPS service.
+    pub fn doh_path(&mut self, path: &str) -> Result<&mut Self, DnsMessageError> {
+        self.begin(SvcParamKey::DohPath, path.len())?.copy_from_slice(path.as_bytes());
+
+        Ok(self)
+    }
+
+    /// Add a parameter with an unknown key.
+    pub fn unknown(&mut self, key: u16, value: &[u8]) -> Result<&mut Self, DnsMessageError> {
+        if key <= SvcParamKey::DohPath.id() {
+            return Err(DnsMessageError::DnsError(DnsError::InvalidSvcParam));
+        }
+
+        self.begin(SvcParamKey::Unknown(key), value.len())?.copy_from_slice(value);
+
+        Ok(self)
+    }
+
+    /// Complete the parameters. Every key listed in the mandatory parameter
+    /// must be present.
+    pub fn build(self) -> Result<SvcParams<'a>, DnsMessageError> {
+        let bytes: &'a [u8] = self.buffer;
+        let params = SvcParams { bytes: &bytes[..self.position] };
+        if let Some(SvcParam::Mandatory(keys)) = params.get(SvcParamKey::Mandatory) {
+            for key in keys {
+                if params.get(key).is_none() {
+                    return Err(DnsMessageError::DnsError(DnsError::InvalidSvcParam));
+                }
+            }
+        }
+
+        Ok(params)
+    }
+
+    // Write the key and length of a parameter and return the space for its
+    // value.
+    fn begin(&mut self, key: SvcParamKey, length: usize) -> Result<&mut [u8], DnsMessageError> {
+        let key = key.id();
+        if self.last_key.is_some_and(|last| key <= last) || length > u16::MAX as usize {
+            return Err(DnsMessageError::DnsError(DnsError::InvalidSvcParam));
+        }
+        if self.position + 4 + length > self.buffer.len() {
+            return Err(DnsMessageError::BufferError(BufferError::OutOfMemory));
+        }
+
+        let position = self.position;
+        self.buffer[position..position + 2].copy_from_slice(&key.to_be_bytes());
+        self.buffer[position + 2..position + 4].copy_from_slice(&(length as u16).to_be_bytes());
+        self.position += 4 + length;
+        self.last_key = Some(key);
+
+        Ok(&mut self.buffer[position + 4..position + 4 + length])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // alpn=h2,h3 ipv6hint=2001:db8::1,2001:db8::53:1
+    const BYTES: [u8; 46] = [
+        0x00, 0x01, // key 1
+        0x00, 0x06, // length 6
+        0x02, b'h', b'2', // alpn h2
+        0x02, b'h', b'3', // alpn h3
+        0x00, 0x06, // key 6
+        0x00, 0x20, // length 32
+        0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+        0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x53, 0x00, 0x01,
+    ];
+
+    const ADDRESSES: [[u8; 16]; 2] = [
+        [0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x01],
+        [0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x53, 0, 0x01],
+    ];
+
+    #[test]
+    fn iter() {
+        let params = SvcParams::new(&BYTES).unwrap();
+        let mut iter = params.iter();
+
+        let Some(SvcParam::Alpn(mut ids)) = iter.next() else {
+            panic!("expected alpn");
+        };
+        assert_eq!(ids.next(), Some(&b"h2"[..]));
+        assert_eq!(ids.next(), Some(&b"h3"[..]));
+        assert_eq!(ids.next(), None);
+
+        assert_eq!(iter.next(), Some(SvcParam::Ipv6Hint(&ADDRESSES)));
+        assert_eq!(iter.next(), None);
+
+        assert_eq!(params.get(SvcParamKey::Ipv6Hint), Some(SvcParam::Ipv6Hint(&ADDRESSES)));
+        assert_eq!(params.get(SvcParamKey::Port), None);
+    }
+
+    #[test]
+    fn build() {
+        let mut buffer = [0; 64];
+        let mut builder = SvcParamsBuilder::new(&mut buffer);
+        builder.alpn(&[b"h2", b"h3"]).unwrap()
+            .ipv6_hint(&ADDRESSES).unwrap();
+        let params = builder.build().unwrap();
+        assert_eq!(params.bytes(), &BYTES);
+    }
+
+    #[test]
+    fn build_all() {
+        let mut buffer = [0; 128];
+        let mut builder = SvcParamsBuilder::new(&mut buffer);
+        builder.mandatory(&[SvcParamKey::Alpn, SvcParamKey::Port]).unwrap()
+            .alpn(&[b"h3"]).unwrap()
+            .no_default_alpn().unwrap()
+            .port(8443).unwrap()
+            .ipv4_hint(&[[192, 0, 2, 1]]).unwrap()
+            .ech(&[0x01, 0x02]).unwrap()
+            .ipv6_hint(&ADDRESSES[..1]).unwrap()
+            .doh_path("/dns-query{?dns}").unwrap()
+            .unknown(667, b"hello").unwrap();
+        let params = builder.build().unwrap();
+
+        let params = SvcParams::new(params.bytes()).unwrap();
+        let mut iter = params.iter();
+        let Some(SvcParam::Mandatory(mut keys)) = iter.next() else {
+            panic!("expected mandatory");
+        };
+        assert_eq!(keys.next(), Some(SvcParamKey::Alpn));
+        assert_eq!(keys.next(), Some(SvcParamKey::Port));
+        assert_eq!(keys.next(), None);
+        assert_eq!(iter.next().map(|param| param.key()), Some(SvcParamKey::Alpn));
+        assert_eq!(iter.next(), Some(SvcParam::NoDefaultAlpn));
+        assert_eq!(iter.next(), Some(SvcParam::Port(8443)));
+        assert_eq!(iter.next(), Some(SvcParam::Ipv4Hint(&[[192, 0, 2, 1]])));
+        assert_eq!(iter.next(), Some(SvcParam::Ech(&[0x01, 0x02])));
+        assert_eq!(iter.next(), Some(SvcParam::Ipv6Hint(&ADDRESSES[..1])));
+        assert_eq!(iter.next(), Some(SvcParam::DohPath("/dns-query{?dns}")));
+        assert_eq!(iter.next(), Some(SvcParam::Unknown { key: 667, value: b"hello" }));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn build_key_order() {
+        let mut buffer = [0; 64];
+        let mut builder = SvcParamsBuilder::new(&mut buffer);
+        builder.port(53).unwrap();
+        assert_eq!(
+            builder.alpn(&[b"h2"]).err(),
+            Some(DnsMessageError::DnsError(DnsError::InvalidSvcParam)),
+        );
+        assert_eq!(
+            builder.port(53).err(),
+            Some(DnsMessageError::DnsError(DnsError::InvalidSvcParam)),
+        );
+    }
+
+    #[test]
+    fn build_mandatory() {
+        let mut buffer = [0; 64];
+        let mut builder = SvcParamsBuilder::new(&mut buffer);
+        builder.mandatory(&[SvcParamKey::Port]).unwrap();
+        assert_eq!(
+            builder.build(),
+            Err(DnsMessageError::DnsError(DnsError::InvalidSvcParam)),
+        );
+
+        let mut buffer = [0; 64];
+        let mut builder = SvcParamsBuilder::new(&mut buffer);
+        assert_eq!(
+            builder.mandatory(&[SvcParamKey::Port, SvcParamKey::Alpn]).err(),
+            Some(DnsMessageError::DnsError(DnsError::InvalidSvcParam)),
+        );
+        assert_eq!(
+            builder.mandatory(&[SvcParamKey::Mandatory]).err(),
+            Some(DnsMessageError::DnsError(DnsError::InvalidSvcParam)),
+        );
+    }
+
+    #[test]
+    fn build_out_of_memory() {
+        let mut buffer = [0; 5];
+        let mut builder = SvcParamsBuilder::new(&mut buffer);
+        assert_eq!(
+            builder.port(53).err(),
+            Some(DnsMessageError::BufferError(BufferError::OutOfMemory)),
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        // Keys out of order.
+        assert_eq!(
+            SvcParams::new(&[0x00, 0x03, 0x00, 0x02, 0x00, 0x35, 0x00, 0x02, 0x00, 0x00]),
+            Err(DnsMessageError::DnsError(DnsError::InvalidSvcParam)),
+        );
+        // Port of the wrong length.
+        assert_eq!(
+            SvcParams::new(&[0x00, 0x03, 0x00, 0x01, 0x35]),
+            Err(DnsMessageError::DnsError(DnsError::InvalidSvcParam)),
+        );
+        // Truncated value.
+        assert_eq!(
+            SvcParams::new(&[0x00, 0x03, 0x00, 0x02, 0x00]),
+            Err(DnsMessageError::DnsError(DnsError::InvalidSvcParam)),
+        );
+        // Empty alpn id.
+        assert_eq!(
+            SvcParams::new(&[0x00, 0x01, 0x00, 0x01, 0x00]),
+            Err(DnsMessageError::DnsError(DnsError::InvalidSvcParam)),
+        );
+    }
+}
@@ -1,7 +1,6 @@
 use crate::{Buffer, DnsMessage, DnsMessageError, MutBuffer};
-use crate::characters::Characters;
 use crate::parse::Parse;
-use crate::rdata::{RData, RDataParse};
+use crate::rdata::{RawBytes, RData, RDataParse};
 use crate::write::WriteBytes;
 
 /// # Transport Layer Security Authentication (TLSA) Record
@@ -17,7 +16,7 @@ pub struct Tlsa<'a> {
     /// The matching type of this TLSA record
     pub matching_type: u8,
     /// The certificate association data
-    pub certificate_association_data: Characters<'a>,
+    pub certificate_association_data: RawBytes<'a>,
 }
 
 impl<'a> RDataParse<'a> for Tlsa<'a> {
@@ -26,7 +25,7 @@ impl<'a> RDataParse<'a> for Tlsa<'a> {
         let usage = u8::parse(rdata, i)?;
         let selector = u8::parse(rdata, i)?;
         let matching_type = u8::parse(rdata, i)?;
-        let certificate_association_data = Characters::parse(rdata, i)?;
+        let certificate_association_data = RawBytes::parse(rdata, i)?;
 
         Ok(Self {
             usage,
@@ -62,19 +61,18 @@ mod test {
     use super::*;
 
     parse_write_test!(
-        7,
+        6,
         [
             0x0a, // usage
             0x0b, // selector
             0x0c, // matching type
-            0x03, // length of certificate association data
             0x77, 0x77, 0x77, // certificate association data
         ],
         Tlsa {
             usage: 10,
             selector: 11,
             matching_type: 12,
-            certificate_association_data: unsafe { Characters::new_unchecked(b"www") },
+            certificate_association_data: RawBytes::new(b"www"),
         },
     );
 }
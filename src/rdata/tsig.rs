@@ -1,5 +1,5 @@
-use crate::{Buffer, DnsMessage, DnsMessageError, MutBuffer};
-use crate::characters::Characters;
+use crate::{Buffer, DnsError, DnsMessage, DnsMessageError, MutBuffer};
+use crate::header::DnsHeaderResponseCode;
 use crate::name::DnsName;
 use crate::parse::Parse;
 use crate::rdata::{RData, RDataParse};
@@ -8,43 +8,44 @@ use crate::write::WriteBytes;
 /// # Transaction signature record (TSIG)
 /// This record is used to authenticate dynamic updates as coming from an
 /// approved client, and to authenticate responses as coming from an approved
-/// recursive server.
+/// recursive server. See the [`tsig`](crate::tsig) module for signing and
+/// verifying messages.
+///
+/// According to [RFC 8945 Section 4.2](https://tools.ietf.org/rfc/rfc8945#section-4.2).
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct TSig<'a> {
     /// The name of the algorithm in domain name syntax.
     pub algorithm: DnsName<'a>,
-    /// The time that the signature was generated.
+    /// The time that the signature was generated, in seconds since the
+    /// epoch. Only the lower 48 bits are transmitted.
     pub time_signed: u64,
-    /// The Fudge value is an unsigned 8-bit field that specifies the allowed
-    /// time difference in seconds.
-    pub fudge: u8,
-    /// The MAC is a variable length octet string containing the message
-    /// authentication code.
-    pub mac: Characters<'a>,
+    /// The allowed time difference in seconds.
+    pub fudge: u16,
+    /// The message authentication code.
+    pub mac: &'a [u8],
     /// The original ID of the message.
     pub original_id: u16,
-    /// The error field is an unsigned 16-bit field that contains the extended
-    /// RCODE covering TSIG processing.
-    pub error: u16,
-    /// The other field is a variable length octet string that contains
-    /// information that may be used by the server to complete the transaction.
-    pub other: Characters<'a>,
+    /// The extended RCODE covering TSIG processing.
+    pub error: DnsHeaderResponseCode,
+    /// Additional data, the server time for BADTIME errors.
+    pub other: &'a [u8],
 }
 
 impl<'a> RDataParse<'a> for TSig<'a> {
     #[inline]
     fn parse(rdata: &RData<'a>, i: &mut usize) -> Result<Self, DnsMessageError> {
         let algorithm = DnsName::parse(rdata, i)?;
-        let time_signed = u64::parse(rdata, i)?;
-        let fudge = u8::parse(rdata, i)?;
-        let mac = Characters::parse(rdata, i)?;
+        let time_signed_high = u16::parse(rdata, i)? as u64;
+        let time_signed_low = u32::parse(rdata, i)? as u64;
+        let fudge = u16::parse(rdata, i)?;
+        let mac = parse_data(rdata, i)?;
         let original_id = u16::parse(rdata, i)?;
-        let error = u16::parse(rdata, i)?;
-        let other = Characters::parse(rdata, i)?;
+        let error = u16::parse(rdata, i)?.into();
+        let other = parse_data(rdata, i)?;
 
         Ok(Self {
             algorithm,
-            time_signed,
+            time_signed: time_signed_high << 32 | time_signed_low,
             fudge,
             mac,
             original_id,
@@ -63,18 +64,48 @@ impl<'a> WriteBytes for TSig<'a> {
     >(&self, message: &mut DnsMessage<PTR_STORAGE, DNS_SECTION, B>) -> Result<usize, DnsMessageError> {
         let mut bytes = 0;
 
-        bytes += self.algorithm.write(message)?;
-        bytes += self.time_signed.write(message)?;
+        // The algorithm name must not be compressed.
+        bytes += message.write_name_uncompressed(self.algorithm)?;
+        bytes += message.write_bytes(&self.time_signed.to_be_bytes()[2..])?;
         bytes += self.fudge.write(message)?;
-        bytes += self.mac.write(message)?;
+        bytes += write_data(self.mac, message)?;
         bytes += self.original_id.write(message)?;
-        bytes += self.error.write(message)?;
-        bytes += self.other.write(message)?;
+        bytes += u16::from(self.error).write(message)?;
+        bytes += write_data(self.other, message)?;
 
         Ok(bytes)
     }
 }
 
+// Parse a field prefixed with its 16 bit length.
+#[inline]
+fn parse_data<'a>(rdata: &RData<'a>, i: &mut usize) -> Result<&'a [u8], DnsMessageError> {
+    let length = u16::parse(rdata, i)? as usize;
+    let data = rdata.buffer.get(*i..*i + length)
+        .ok_or(DnsMessageError::DnsError(DnsError::UnexpectedEndOfBuffer))?;
+    *i += length;
+
+    Ok(data)
+}
+
+// Write a field prefixed with its 16 bit length.
+#[inline]
+fn write_data<
+    const PTR_STORAGE: usize,
+    const DNS_SECTION: usize,
+    B: MutBuffer + Buffer,
+>(data: &[u8], message: &mut DnsMessage<PTR_STORAGE, DNS_SECTION, B>) -> Result<usize, DnsMessageError> {
+    if data.len() > u16::MAX as usize {
+        return Err(DnsMessageError::DnsError(DnsError::RDataLongerThanMessage));
+    }
+
+    let mut bytes = 0;
+    bytes += (data.len() as u16).write(message)?;
+    bytes += message.write_bytes(data)?;
+
+    Ok(bytes)
+}
+
 #[cfg(test)]
 mod test {
     use crate::rdata::testutils::parse_write_test;
@@ -82,24 +113,58 @@ mod test {
     use super::*;
 
     parse_write_test!(
-        26,
+        61,
+        [
+            0x0b, b'h', b'm', b'a', b'c', b'-', b's', b'h', b'a', b'2', b'5', b'6', 0x00, // algorithm
+            0x00, 0x00, 0x65, 0x8e, 0x4d, 0x80, // time signed
+            0x01, 0x2c, // fudge
+            0x00, 0x20, // mac size
+            0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, // mac
+            0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 0x10,
+            0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18,
+            0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f, 0x20,
+            0xbe, 0xef, // original id
+            0x00, 0x00, // error
+            0x00, 0x00, // other len
+        ],
+        TSig {
+            algorithm: unsafe { DnsName::new_unchecked(b"\x0bhmac-sha256\x00") },
+            time_signed: 1703824768,
+            fudge: 300,
+            mac: &[
+                0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08,
+                0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 0x10,
+                0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18,
+                0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f, 0x20,
+            ],
+            original_id: 0xbeef,
+            error: DnsHeaderResponseCode::NoError,
+            other: &[],
+        },
+    );
+
+    parse_write_test!(
+        35,
         [
-            0x03, b'w', b'w', b'w', 0x00, // algorithm
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0a, // time_signed
-            0x0b, // fudge
-            0x03, 0x77, 0x77, 0x77, // mac
-            0x00, 0x0c, // original_id
-            0x00, 0x0d, // error
-            0x03, 0x77, 0x77, 0x77, // other
+            0x0b, b'h', b'm', b'a', b'c', b'-', b's', b'h', b'a', b'2', b'5', b'6', 0x00, // algorithm
+            0x01, 0x00, 0x65, 0x8e, 0x4d, 0x80, // time signed
+            0x01, 0x2c, // fudge
+            0x00, 0x00, // mac size
+            0xbe, 0xef, // original id
+            0x00, 0x12, // error
+            0x00, 0x06, // other len
+            0x00, 0x00, 0x65, 0x8e, 0x4e, 0xac, // other
         ],
         TSig {
-            algorithm: unsafe { DnsName::new_unchecked(b"\x03www\x00") },
-            time_signed: 10,
-            fudge: 11,
-            mac: unsafe { Characters::new_unchecked(b"www") },
-            original_id: 12,
-            error: 13,
-            other: unsafe { Characters::new_unchecked(b"www") },
+            algorithm: unsafe { DnsName::new_unchecked(b"\x0bhmac-sha256\x00") },
+            time_signed: 0x0100_658e_4d80,
+            fudge: 300,
+            mac: &[],
+            original_id: 0xbeef,
+            error: DnsHeaderResponseCode::BadTime,
+            other: &[0x00, 0x00, 0x65, 0x8e, 0x4e, 0xac],
         },
+        parse_bad_time,
+        write_bad_time,
     );
 }
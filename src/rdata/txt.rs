@@ -123,6 +123,42 @@ impl<'a> Txt<'a> {
             pos: 0,
         }
     }
+
+    /// Returns an iterator over the DNS-SD attributes of the txt record.
+    /// Strings without a key are ignored, as are later attributes with the
+    /// key of an earlier one. As the keys are not stored, each attribute is
+    /// compared with the earlier strings, which is quadratic in the number
+    /// of strings. DNS-SD records are meant to be short, use
+    /// [`Txt::attribute`] to look up a single key.
+    ///
+    /// According to [RFC 6763 Section 6](https://tools.ietf.org/rfc/rfc6763#section-6).
+    #[inline(always)]
+    pub fn attributes(&self) -> TxtAttributes<'a> {
+        TxtAttributes {
+            data: self.data,
+            strings: self.iter(),
+        }
+    }
+
+    /// Returns the DNS-SD attribute with the given key, compared
+    /// case-insensitively, if it is present.
+    ///
+    /// According to [RFC 6763 Section 6.4](https://tools.ietf.org/rfc/rfc6763#section-6.4).
+    pub fn attribute(&self, key: &[u8]) -> Result<Option<TxtAttribute<'a>>, DnsMessageError> {
+        if key.is_empty() {
+            return Ok(None);
+        }
+
+        // The first attribute with the key is used.
+        for string in self.iter() {
+            let attribute = TxtAttribute::parse(string?);
+            if attribute.key.eq_ignore_ascii_case(key) {
+                return Ok(Some(attribute));
+            }
+        }
+
+        Ok(None)
+    }
 }
 
 impl<'a> RDataParse<'a> for Txt<'a> {
@@ -184,4 +220,95 @@ impl<'a> Iterator for TxtIterator<'a> {
 
         Some(Ok(result))
     }
-}
\ No newline at end of file
+}
+
+/// A DNS-SD attribute of a txt record, a string of the form `key=value`.
+///
+/// According to [RFC 6763 Section 6.4](https://tools.ietf.org/rfc/rfc6763#section-6.4).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TxtAttribute<'a> {
+    /// The key, which is case-insensitive.
+    pub key: &'a [u8],
+    /// The value, which is `None` for a boolean attribute without `=`, and
+    /// empty for an attribute with an empty value.
+    pub value: Option<&'a [u8]>,
+}
+
+impl<'a> TxtAttribute<'a> {
+    #[inline]
+    fn parse(string: &'a [u8]) -> Self {
+        match string.iter().position(|&c| c == b'=') {
+            Some(i) => TxtAttribute {
+                key: &string[..i],
+                value: Some(&string[i + 1..]),
+            },
+            None => TxtAttribute {
+                key: string,
+                value: None,
+            },
+        }
+    }
+}
+
+/// An iterator over the DNS-SD attributes of a txt record.
+#[derive(Copy, Clone, Debug)]
+pub struct TxtAttributes<'a> {
+    data: &'a [u8],
+    strings: TxtIterator<'a>,
+}
+
+impl<'a> Iterator for TxtAttributes<'a> {
+    type Item = Result<TxtAttribute<'a>, DnsMessageError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let attribute = match self.strings.next()? {
+                Ok(string) => TxtAttribute::parse(string),
+                Err(e) => return Some(Err(e)),
+            };
+
+            if attribute.key.is_empty() {
+                continue;
+            }
+
+            // Only the first attribute with a key is used.
+            let earlier = TxtIterator { data: &self.data[..self.strings.pos], pos: 0 }
+                .filter_map(|string| string.ok())
+                .map(TxtAttribute::parse)
+                .filter(|earlier| earlier.key.eq_ignore_ascii_case(attribute.key))
+                .count();
+            if earlier == 1 {
+                return Some(Ok(attribute));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn attributes() {
+        let txt = Txt::new(b"\x09txtvers=1\x05Paper\x07PAPER=F\x05=oops\x05note=\x0bpath=/a=b/c").unwrap();
+        let mut attributes = txt.attributes();
+        assert_eq!(attributes.next(), Some(Ok(TxtAttribute { key: b"txtvers", value: Some(b"1") })));
+        assert_eq!(attributes.next(), Some(Ok(TxtAttribute { key: b"Paper", value: None })));
+        assert_eq!(attributes.next(), Some(Ok(TxtAttribute { key: b"note", value: Some(b"") })));
+        assert_eq!(attributes.next(), Some(Ok(TxtAttribute { key: b"path", value: Some(b"/a=b/c") })));
+        assert_eq!(attributes.next(), None);
+
+        assert_eq!(txt.attribute(b"paper"), Ok(Some(TxtAttribute { key: b"Paper", value: None })));
+        assert_eq!(txt.attribute(b"missing"), Ok(None));
+        assert_eq!(txt.attribute(b""), Ok(None));
+
+        // An empty txt record is a single empty string.
+        let txt = Txt::new(b"\x00").unwrap();
+        assert_eq!(txt.attributes().next(), None);
+
+        let txt = unsafe { Txt::new_unchecked(b"\x03a=1\x05b") };
+        let mut attributes = txt.attributes();
+        assert_eq!(attributes.next(), Some(Ok(TxtAttribute { key: b"a", value: Some(b"1") })));
+        assert_eq!(attributes.next(), Some(Err(DnsMessageError::DnsError(DnsError::InvalidTxtRecord))));
+    }
+}
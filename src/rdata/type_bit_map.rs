@@ -0,0 +1,324 @@
+use crate::{Buffer, BufferError, DnsError, DnsMessage, DnsMessageError, MutBuffer};
+use crate::question::DnsQType;
+use crate::rdata::{RawBytes, RData, RDataParse};
+use crate::write::WriteBytes;
+
+/// # Type bit map
+/// The set of record types present at a name, as used by the NSEC, NSEC3
+/// and CSYNC records. The types are split into windows of 256 types, each
+/// window is encoded as its number, the length of its bitmap and the bitmap
+/// itself. Windows without any types are omitted.
+///
+/// According to [RFC 4034 Section 4.1.2](https://tools.ietf.org/rfc/rfc4034#section-4.1.2).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TypeBitMap<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> TypeBitMap<'a> {
+    /// Create a new [`TypeBitMap`] from its wire format. The windows must be
+    /// in ascending order and each bitmap must be between 1 and 32 bytes long.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, DnsMessageError> {
+        let mut position = 0;
+        let mut last_window = None;
+        while position < bytes.len() {
+            if position + 2 > bytes.len() {
+                return Err(DnsMessageError::DnsError(DnsError::InvalidTypeBitMap));
+            }
+
+            let window = bytes[position];
+            let length = bytes[position + 1] as usize;
+            if last_window.is_some_and(|last| window <= last)
+                || length == 0
+                || length > 32
+                || position + 2 + length > bytes.len() {
+                return Err(DnsMessageError::DnsError(DnsError::InvalidTypeBitMap));
+            }
+
+            last_window = Some(window);
+            position += 2 + length;
+        }
+
+        Ok(Self { bytes })
+    }
+
+    /// Create a new [`TypeBitMap`] from its wire format without checking
+    /// the windows.
+    ///
+    /// # Safety
+    /// The windows are not checked, this can lead to an invalid DNS message.
+    #[inline(always)]
+    pub const unsafe fn new_unchecked(bytes: &'a [u8]) -> Self {
+        TypeBitMap { bytes }
+    }
+
+    /// Encode the given types into the buffer and return the resulting
+    /// [`TypeBitMap`]. The types may be given in any order and may contain
+    /// duplicates.
+    pub fn build(types: &[DnsQType], buffer: &'a mut [u8]) -> Result<Self, DnsMessageError> {
+        let mut position = 0;
+        let mut min_window = 0;
+        // Visit the windows in ascending order.
+        while let Some(next) = types.iter()
+            .map(|qtype| (qtype.id() >> 8) as u8)
+            .filter(|&w| w as u16 >= min_window)
+            .min() {
+            min_window = next as u16 + 1;
+
+            let length = types.iter()
+                .map(|qtype| qtype.id())
+                .filter(|id| (id >> 8) as u8 == next)
+                .map(|id| ((id & 0xFF) >> 3) as usize + 1)
+                .max()
+                .unwrap_or(0);
+            if position + 2 + length > buffer.len() {
+                return Err(DnsMessageError::BufferError(BufferError::OutOfMemory));
+            }
+
+            buffer[position] = next;
+            buffer[position + 1] = length as u8;
+            let octets = &mut buffer[position + 2..position + 2 + length];
+            octets.fill(0);
+            for id in types.iter().map(|qtype| qtype.id()).filter(|id| (id >> 8) as u8 == next) {
+                let bit = (id & 0xFF) as usize;
+                octets[bit >> 3] |= 0x80 >> (bit & 0x07);
+            }
+
+            position += 2 + length;
+        }
+
+        let bytes: &'a [u8] = buffer;
+        Ok(Self { bytes: &bytes[..position] })
+    }
+
+    /// Return whether the given type is present in the type bit map.
+    pub fn contains(&self, qtype: DnsQType) -> bool {
+        let id = qtype.id();
+        let window = (id >> 8) as u8;
+        let bit = (id & 0xFF) as usize;
+
+        for (w, octets) in self.windows() {
+            if w == window {
+                return octets.get(bit >> 3)
+                    .is_some_and(|octet| octet & (0x80 >> (bit & 0x07)) != 0);
+            }
+            if w > window {
+                break;
+            }
+        }
+
+        false
+    }
+
+    /// Return whether the type bit map contains no types.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+
+    /// Return an iterator over the types in the type bit map, in ascending
+    /// order.
+    #[inline]
+    pub fn iter(&self) -> TypeBitMapIterator<'a> {
+        TypeBitMapIterator {
+            bytes: self.bytes,
+            position: 0,
+            bit: 0,
+        }
+    }
+
+    /// Return the wire format of the type bit map.
+    #[inline(always)]
+    pub const fn bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    #[inline]
+    fn windows(&self) -> impl Iterator<Item = (u8, &'a [u8])> {
+        let bytes = self.bytes;
+        let mut position = 0;
+        core::iter::from_fn(move || {
+            if position + 2 > bytes.len() {
+                return None;
+            }
+
+            let window = bytes[position];
+            let end = (position + 2 + bytes[position + 1] as usize).min(bytes.len());
+            let octets = &bytes[position + 2..end];
+            position = end;
+
+            Some((window, octets))
+        })
+    }
+}
+
+impl<'a> RDataParse<'a> for TypeBitMap<'a> {
+    #[inline]
+    fn parse(rdata: &RData<'a>, i: &mut usize) -> Result<Self, DnsMessageError> {
+        let bytes = RawBytes::parse(rdata, i)?;
+
+        Self::new(bytes.bytes())
+    }
+}
+
+impl<'a> WriteBytes for TypeBitMap<'a> {
+    #[inline]
+    fn write<
+        const PTR_STORAGE: usize,
+        const DNS_SECTION: usize,
+        B: MutBuffer + Buffer,
+    >(&self, message: &mut DnsMessage<PTR_STORAGE, DNS_SECTION, B>) -> Result<usize, DnsMessageError> {
+        message.write_bytes(self.bytes)
+    }
+}
+
+impl<'a> AsRef<[u8]> for TypeBitMap<'a> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.bytes
+    }
+}
+
+impl<'a> IntoIterator for TypeBitMap<'a> {
+    type Item = DnsQType;
+    type IntoIter = TypeBitMapIterator<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the types in a [`TypeBitMap`].
+#[derive(Clone, Debug)]
+pub struct TypeBitMapIterator<'a> {
+    bytes: &'a [u8],
+    // Position of the current window.
+    position: usize,
+    // Index of the next bit in the bitmap of the current window.
+    bit: usize,
+}
+
+impl<'a> Iterator for TypeBitMapIterator<'a> {
+    type Item = DnsQType;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.position + 2 <= self.bytes.len() {
+            let window = self.bytes[self.position] as u16;
+            let end = (self.position + 2 + self.bytes[self.position + 1] as usize)
+                .min(self.bytes.len());
+            let octets = &self.bytes[self.position + 2..end];
+
+            while self.bit < octets.len() * 8 {
+                let bit = self.bit;
+                self.bit += 1;
+                if octets[bit >> 3] & (0x80 >> (bit & 0x07)) != 0 {
+                    return Some(DnsQType::from_id(window << 8 | bit as u16));
+                }
+            }
+
+            self.position = end;
+            self.bit = 0;
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // A MX RRSIG NSEC TYPE1234, from RFC 4034 Section 4.3.
+    const BYTES: [u8; 37] = [
+        0x00, 0x06, 0x40, 0x01, 0x00, 0x00, 0x00, 0x03,
+        0x04, 0x1b, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x20,
+    ];
+
+    const TYPES: [DnsQType; 5] = [
+        DnsQType::A,
+        DnsQType::MX,
+        DnsQType::RRSIG,
+        DnsQType::NSEC,
+        DnsQType::Unknown(1234),
+    ];
+
+    #[test]
+    fn iter() {
+        let bitmap = TypeBitMap::new(&BYTES).unwrap();
+        let mut iter = bitmap.iter();
+        for qtype in TYPES {
+            assert_eq!(iter.next(), Some(qtype));
+        }
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn contains() {
+        let bitmap = TypeBitMap::new(&BYTES).unwrap();
+        for qtype in TYPES {
+            assert!(bitmap.contains(qtype));
+        }
+        assert!(!bitmap.contains(DnsQType::AAAA));
+        assert!(!bitmap.contains(DnsQType::Unknown(1235)));
+        assert!(!bitmap.contains(DnsQType::CAA));
+        assert!(!bitmap.is_empty());
+        assert!(TypeBitMap::new(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn build() {
+        let mut buffer = [0xFF; 64];
+        let types = [
+            DnsQType::Unknown(1234),
+            DnsQType::NSEC,
+            DnsQType::A,
+            DnsQType::RRSIG,
+            DnsQType::MX,
+            DnsQType::A,
+        ];
+        let bitmap = TypeBitMap::build(&types, &mut buffer).unwrap();
+        assert_eq!(bitmap.bytes(), &BYTES);
+    }
+
+    #[test]
+    fn build_out_of_memory() {
+        let mut buffer = [0; 36];
+        assert_eq!(
+            TypeBitMap::build(&TYPES, &mut buffer),
+            Err(DnsMessageError::BufferError(BufferError::OutOfMemory)),
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        // Windows out of order.
+        assert_eq!(
+            TypeBitMap::new(&[0x01, 0x01, 0x40, 0x00, 0x01, 0x40]),
+            Err(DnsMessageError::DnsError(DnsError::InvalidTypeBitMap)),
+        );
+        // Empty bitmap.
+        assert_eq!(
+            TypeBitMap::new(&[0x00, 0x00]),
+            Err(DnsMessageError::DnsError(DnsError::InvalidTypeBitMap)),
+        );
+        // Bitmap longer than 32 bytes.
+        assert_eq!(
+            TypeBitMap::new(&[0x00, 0x21]),
+            Err(DnsMessageError::DnsError(DnsError::InvalidTypeBitMap)),
+        );
+        // Truncated bitmap.
+        assert_eq!(
+            TypeBitMap::new(&[0x00, 0x02, 0x40]),
+            Err(DnsMessageError::DnsError(DnsError::InvalidTypeBitMap)),
+        );
+        // Truncated window header.
+        assert_eq!(
+            TypeBitMap::new(&[0x00]),
+            Err(DnsMessageError::DnsError(DnsError::InvalidTypeBitMap)),
+        );
+    }
+}
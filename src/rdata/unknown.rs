@@ -0,0 +1,157 @@
+use core::fmt::Display;
+use crate::{Buffer, DnsMessage, DnsMessageError, MutBuffer};
+use crate::rdata::{RData, RDataParse};
+use crate::write::WriteBytes;
+
+/// # Unknown record
+/// This record holds the uninterpreted data of a record type without a
+/// structured parser, so that it can be written back unchanged. It is
+/// displayed in the generic `\# <length> <hex>` presentation format.
+///
+/// According to [RFC 3597 Section 3](https://tools.ietf.org/rfc/rfc3597#section-3)
+/// and [RFC 3597 Section 5](https://tools.ietf.org/rfc/rfc3597#section-5).
+///
+/// # Example
+/// ```
+/// use flex_dns::rdata::Unknown;
+///
+/// let unknown = Unknown::new(731, &[0xab, 0xcd, 0xef, 0x01, 0x23, 0x45]);
+/// assert_eq!(unknown.to_string(), "\\# 6 abcdef012345");
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Unknown<'a> {
+    /// The type of the record.
+    pub type_: u16,
+    /// The raw data of the record.
+    pub data: &'a [u8],
+}
+
+impl<'a> Unknown<'a> {
+    /// Create a new unknown record of the given type from its raw data.
+    #[inline(always)]
+    pub const fn new(type_: u16, data: &'a [u8]) -> Self {
+        Self {
+            type_,
+            data,
+        }
+    }
+}
+
+impl<'a> RDataParse<'a> for Unknown<'a> {
+    #[inline]
+    fn parse(rdata: &RData<'a>, i: &mut usize) -> Result<Self, DnsMessageError> {
+        let end = rdata.pos + rdata.len;
+        let data = &rdata.buffer[*i..end];
+        *i = end;
+
+        Ok(Self {
+            type_: rdata.type_.id(),
+            data,
+        })
+    }
+}
+
+impl<'a> WriteBytes for Unknown<'a> {
+    #[inline]
+    fn write<
+        const PTR_STORAGE: usize,
+        const DNS_SECTION: usize,
+        B: MutBuffer + Buffer,
+    >(&self, message: &mut DnsMessage<PTR_STORAGE, DNS_SECTION, B>) -> Result<usize, DnsMessageError> {
+        message.write_bytes(self.data)
+    }
+}
+
+impl<'a> Display for Unknown<'a> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "\\# {}", self.data.len())?;
+        if !self.data.is_empty() {
+            f.write_str(" ")?;
+        }
+
+        for &byte in self.data.iter() {
+            write!(f, "{:02x}", byte)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rdata::testutils::parse_write_test;
+
+    use super::*;
+
+    parse_write_test!(
+        4,
+        [0x01, 0x02, 0x03, 0x04],
+        Unknown {
+            // The test utilities parse with the ALL type.
+            type_: 255,
+            data: &[0x01, 0x02, 0x03, 0x04],
+        },
+    );
+
+    #[test]
+    fn into_parsed() {
+        use crate::question::DnsQType;
+        use crate::rdata::DnsAType;
+
+        let bytes = [0x00, 0x02, 0xab, 0xcd];
+        for type_ in [DnsQType::ALL, DnsQType::Unknown(731)] {
+            let rdata = RData::parse(&bytes, &mut 0, type_).unwrap();
+            assert_eq!(
+                rdata.into_parsed(),
+                Ok(DnsAType::Unknown(Unknown::new(type_.id(), &[0xab, 0xcd])))
+            );
+        }
+    }
+
+    #[cfg(feature = "arrayvec")]
+    #[test]
+    fn display() {
+        use core::fmt::Write;
+
+        let mut text = arrayvec::ArrayString::<64>::new();
+        write!(text, "{}", Unknown::new(731, &[0xab, 0xcd, 0xef, 0x01, 0x23, 0x45])).unwrap();
+        assert_eq!(text.as_str(), "\\# 6 abcdef012345");
+
+        text.clear();
+        write!(text, "{}", Unknown::new(62347, &[])).unwrap();
+        assert_eq!(text.as_str(), "\\# 0");
+    }
+
+    #[cfg(feature = "arrayvec")]
+    #[test]
+    fn write_answer() {
+        use arrayvec::ArrayVec;
+        use crate::answer::{DnsAClass, DnsAnswer};
+        use crate::name::DnsName;
+        use crate::rdata::DnsAType;
+
+        let message: DnsMessage<8, 1, _> = DnsMessage::new_mut(ArrayVec::<u8, 512>::new()).unwrap();
+        let mut answers = message.answers();
+        answers.append(DnsAnswer {
+            name: DnsName::new(b"\x01e\x07example\x00").unwrap(),
+            rdata: DnsAType::Unknown(Unknown::new(731, &[0xab, 0xcd, 0xef, 0x01, 0x23, 0x45])),
+            cache_flush: false,
+            aclass: DnsAClass::IN,
+            ttl: 300,
+        }).unwrap();
+        let buffer = answers.complete().unwrap().abort().unwrap();
+
+        assert_eq!(
+            &buffer[12..],
+            [
+                0x01, b'e', 0x07, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 0x00, // Name
+                0x02, 0xdb, // Type
+                0x00, 0x01, // Class
+                0x00, 0x00, 0x01, 0x2c, // TTL
+                0x00, 0x06, // Data length
+                0xab, 0xcd, 0xef, 0x01, 0x23, 0x45, // Data
+            ].as_slice()
+        );
+    }
+}
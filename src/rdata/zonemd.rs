@@ -1,31 +1,34 @@
 use crate::{Buffer, DnsMessage, DnsMessageError, MutBuffer};
-use crate::characters::Characters;
 use crate::parse::Parse;
-use crate::rdata::{RData, RDataParse};
+use crate::rdata::{RawBytes, RData, RDataParse};
 use crate::write::WriteBytes;
 
 /// # Message digest for DNS zone record (ZONEMD)
 /// This record is used to publish a message digest for a DNS zone.
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct ZoneMd<'a> {
-    /// algorithm is the algorithm of the digest
-    pub algorithm: u8,
-    /// digest_type is the algorithm used to construct the digest
-    pub digest_type: u8,
+    /// serial is the serial number of the zone's SOA record
+    pub serial: u32,
+    /// scheme is the method used to collate the zone's records
+    pub scheme: u8,
+    /// hash_algorithm is the algorithm used to construct the digest
+    pub hash_algorithm: u8,
     /// digest is the digest of the zone
-    pub digest: Characters<'a>,
+    pub digest: RawBytes<'a>,
 }
 
 impl<'a> RDataParse<'a> for ZoneMd<'a> {
     #[inline]
     fn parse(rdata: &RData<'a>, i: &mut usize) -> Result<Self, DnsMessageError> {
-        let algorithm = u8::parse(rdata, i)?;
-        let digest_type = u8::parse(rdata, i)?;
-        let digest = Characters::parse(rdata, i)?;
+        let serial = u32::parse(rdata.buffer, i)?;
+        let scheme = u8::parse(rdata.buffer, i)?;
+        let hash_algorithm = u8::parse(rdata.buffer, i)?;
+        let digest = RawBytes::parse(rdata, i)?;
 
         Ok(Self {
-            algorithm,
-            digest_type,
+            serial,
+            scheme,
+            hash_algorithm,
             digest
         })
     }
@@ -40,8 +43,9 @@ impl<'a> WriteBytes for ZoneMd<'a> {
     >(&self, message: &mut DnsMessage<PTR_STORAGE, DNS_SECTION, B>) -> Result<usize, DnsMessageError> {
         let mut bytes = 0;
 
-        bytes += self.algorithm.write(message)?;
-        bytes += self.digest_type.write(message)?;
+        bytes += self.serial.write(message)?;
+        bytes += self.scheme.write(message)?;
+        bytes += self.hash_algorithm.write(message)?;
         bytes += self.digest.write(message)?;
 
         Ok(bytes)
@@ -54,18 +58,36 @@ mod test {
 
     use super::*;
 
+    // example. IN ZONEMD 2018031900 1 1 c68090d90a7aed716bc459f9340e3d7c
+    //     1370d4d24b7e2fc3a1ddc0b9a87153b9a9713b3c9ae5cc27777f98b8e730044c
+    // From RFC 8976 Appendix A.1.
+    const DIGEST: [u8; 48] = [
+        0xc6, 0x80, 0x90, 0xd9, 0x0a, 0x7a, 0xed, 0x71,
+        0x6b, 0xc4, 0x59, 0xf9, 0x34, 0x0e, 0x3d, 0x7c,
+        0x13, 0x70, 0xd4, 0xd2, 0x4b, 0x7e, 0x2f, 0xc3,
+        0xa1, 0xdd, 0xc0, 0xb9, 0xa8, 0x71, 0x53, 0xb9,
+        0xa9, 0x71, 0x3b, 0x3c, 0x9a, 0xe5, 0xcc, 0x27,
+        0x77, 0x7f, 0x98, 0xb8, 0xe7, 0x30, 0x04, 0x4c,
+    ];
+
     parse_write_test!(
-        6,
+        54,
         [
-            0x0e, // algorithm
-            0x00, // digest_type
-            0x03, // digest length
-            b'w', b'w', b'w', // digest
+            0x78, 0x48, 0xb9, 0x1c, // serial
+            0x01, // scheme
+            0x01, // hash algorithm
+            0xc6, 0x80, 0x90, 0xd9, 0x0a, 0x7a, 0xed, 0x71, // digest
+            0x6b, 0xc4, 0x59, 0xf9, 0x34, 0x0e, 0x3d, 0x7c,
+            0x13, 0x70, 0xd4, 0xd2, 0x4b, 0x7e, 0x2f, 0xc3,
+            0xa1, 0xdd, 0xc0, 0xb9, 0xa8, 0x71, 0x53, 0xb9,
+            0xa9, 0x71, 0x3b, 0x3c, 0x9a, 0xe5, 0xcc, 0x27,
+            0x77, 0x7f, 0x98, 0xb8, 0xe7, 0x30, 0x04, 0x4c,
         ],
         ZoneMd {
-            algorithm: 14,
-            digest_type: 0,
-            digest: unsafe { Characters::new_unchecked(b"www") },
+            serial: 2018031900,
+            scheme: 1,
+            hash_algorithm: 1,
+            digest: RawBytes::new(&DIGEST),
         },
     );
-}
\ No newline at end of file
+}
@@ -0,0 +1,237 @@
+use crate::{DnsError, DnsMessageError};
+use crate::header::DnsHeaderResponseCode;
+use crate::rdata::Sig;
+
+// The SIG(0) record is owned by the root, of class ANY with a TTL of 0.
+pub(crate) const SIG0_CLASS: u16 = 255;
+pub(crate) const SIG0_TTL: u32 = 0;
+
+/// A private key used to sign messages. The key is held by the
+/// implementation. A new instance is used for every message.
+pub trait Sig0Signer {
+    /// The signature, e.g. `[u8; 64]` for Ed25519.
+    type Signature: AsRef<[u8]>;
+
+    /// The length of the signature returned by `finalize`. The padding of the
+    /// message is sized with it before the message is signed.
+    fn signature_length(&self) -> usize;
+
+    /// Add data to the signed data.
+    fn update(&mut self, data: &[u8]);
+
+    /// Return the signature of all data added.
+    fn finalize(self) -> Self::Signature;
+}
+
+/// A public key, from the KEY record of the signer, used to verify messages.
+/// A new instance is used for every message.
+pub trait Sig0Verifier {
+    /// Add data to the signed data.
+    fn update(&mut self, data: &[u8]);
+
+    /// Return whether the signature matches all data added.
+    fn verify(self, signature: &[u8]) -> bool;
+}
+
+/// The result of verifying the SIG(0) record of a message.
+///
+/// According to [RFC 2931 Section 3.2](https://tools.ietf.org/rfc/rfc2931#section-3.2).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Sig0Status {
+    /// The message is not signed.
+    Missing,
+    /// The signature is valid.
+    Valid,
+    /// The signature does not match.
+    BadSig,
+    /// The signature is expired or not yet valid.
+    BadTime,
+}
+
+impl Sig0Status {
+    /// Return the response code to answer the message with, if the
+    /// verification failed.
+    #[inline]
+    pub fn response_code(&self) -> Option<DnsHeaderResponseCode> {
+        match self {
+            Sig0Status::Missing | Sig0Status::Valid => None,
+            Sig0Status::BadSig => Some(DnsHeaderResponseCode::BadOptVersionOrBadSignature),
+            Sig0Status::BadTime => Some(DnsHeaderResponseCode::BadTime),
+        }
+    }
+}
+
+/// Pass the signed data of a message to the given function. The message must
+/// not contain the SIG(0) record, the additional count of its header is
+/// replaced with the given count. For a response, the data also covers the
+/// request, including its SIG(0) record.
+///
+/// According to [RFC 2931 Section 3.1](https://tools.ietf.org/rfc/rfc2931#section-3.1).
+pub(crate) fn digest<F: FnMut(&[u8])>(
+    mut f: F,
+    message: &[u8],
+    additional_count: u16,
+    sig: &Sig,
+    request: Option<&[u8]>,
+) -> Result<(), DnsMessageError> {
+    // The record data without the signature.
+    f(&sig.type_covered.to_be_bytes());
+    f(&[sig.algorithm, sig.labels]);
+    f(&sig.original_ttl.to_be_bytes());
+    f(&sig.signature_expiration.to_be_bytes());
+    f(&sig.signature_inception.to_be_bytes());
+    f(&sig.key_tag.to_be_bytes());
+    sig.signer_name.canonical(&mut f)?;
+
+    if let Some(request) = request {
+        f(request);
+    }
+
+    if message.len() < 12 {
+        return Err(DnsMessageError::DnsError(DnsError::MessageTooShort));
+    }
+    let mut header = [0; 12];
+    header.copy_from_slice(&message[..12]);
+    header[10..].copy_from_slice(&additional_count.to_be_bytes());
+    f(&header);
+    f(&message[12..]);
+
+    Ok(())
+}
+
+/// Return whether `now` is within the validity period of the signature. The
+/// times are compared using serial number arithmetic.
+#[inline]
+pub(crate) fn verify_time(sig: &Sig, now: u32) -> bool {
+    (now.wrapping_sub(sig.signature_inception) as i32) >= 0
+        && (sig.signature_expiration.wrapping_sub(now) as i32) >= 0
+}
+
+#[cfg(test)]
+mod test {
+    use arrayvec::ArrayVec;
+    use crate::edns::{Edns, PaddingPolicy};
+    use crate::name::DnsName;
+    use crate::rdata::RawBytes;
+    use crate::testutils::{additionals, query, QUERY, TestMac};
+    use super::*;
+
+    type TestKey<'a> = TestMac<'a, 64>;
+
+    const INCEPTION: u32 = 1703824768;
+    const EXPIRATION: u32 = INCEPTION + 300;
+
+    const RDATA: [u8; 34] = [
+        0x00, 0x00, // Type covered
+        0x0f, // Algorithm
+        0x00, // Labels
+        0x00, 0x00, 0x00, 0x00, // Original TTL
+        0x65, 0x8e, 0x4e, 0xac, // Signature expiration
+        0x65, 0x8e, 0x4d, 0x80, // Signature inception
+        0x30, 0x39, // Key tag
+        0x06, b'd', b'e', b'v', b'i', b'c', b'e', // Signer's name
+        0x07, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 0x00,
+    ];
+
+    fn sig() -> Sig<'static> {
+        Sig {
+            type_covered: 0,
+            algorithm: 15,
+            labels: 0,
+            original_ttl: 0,
+            signature_expiration: EXPIRATION,
+            signature_inception: INCEPTION,
+            key_tag: 12345,
+            signer_name: unsafe { DnsName::new_unchecked(b"\x06Device\x07example\x00") },
+            signature: RawBytes::new(&[]),
+        }
+    }
+
+    fn sign(signer: TestKey, request: Option<&[u8]>) -> ArrayVec<u8, 512> {
+        query(|additionals| additionals.append_sig0(sig(), signer, request).unwrap())
+    }
+
+    #[test]
+    fn sign_request() {
+        let mut input = ArrayVec::new();
+        let bytes = sign(TestKey::recording(1, &mut input), None);
+
+        // The signer's name is signed in lowercase.
+        let mut expected = ArrayVec::<u8, 512>::new();
+        expected.try_extend_from_slice(&RDATA).unwrap();
+        expected.try_extend_from_slice(&QUERY).unwrap();
+        assert_eq!(input, expected);
+
+        // The additional count includes the SIG(0) record.
+        assert_eq!(&bytes[10..12], &[0x00, 0x01]);
+        // The record is owned by the root, of class ANY with a TTL of 0.
+        assert_eq!(
+            &bytes[QUERY.len()..QUERY.len() + 11],
+            &[0x00, 0x00, 0x18, 0x00, 0xff, 0x00, 0x00, 0x00, 0x00, 0x00, 98],
+        );
+
+        let additionals = additionals(&bytes);
+        let sig = additionals.sig0().unwrap().unwrap();
+        assert_eq!(sig.signer_name, self::sig().signer_name);
+        assert_eq!(sig.signature.bytes(), &TestKey::new(1).mac(&expected));
+    }
+
+    #[test]
+    fn validity_period() {
+        let bytes = sign(TestKey::new(1), None);
+        let additionals = additionals(&bytes);
+
+        assert_eq!(additionals.verify_sig0(TestKey::new(1), None, INCEPTION).unwrap(), Sig0Status::Valid);
+        assert_eq!(additionals.verify_sig0(TestKey::new(1), None, EXPIRATION).unwrap(), Sig0Status::Valid);
+        assert_eq!(additionals.verify_sig0(TestKey::new(2), None, INCEPTION).unwrap(), Sig0Status::BadSig);
+        assert_eq!(additionals.verify_sig0(TestKey::new(1), None, INCEPTION - 1).unwrap(), Sig0Status::BadTime);
+        assert_eq!(additionals.verify_sig0(TestKey::new(1), None, EXPIRATION + 1).unwrap(), Sig0Status::BadTime);
+        assert_eq!(Sig0Status::BadTime.response_code().map(u16::from), Some(18));
+    }
+
+    #[test]
+    fn covers_request() {
+        // The request, including its SIG(0) record, is signed before the
+        // response.
+        let request = sign(TestKey::new(2), None);
+        let mut input = ArrayVec::new();
+        let bytes = sign(TestKey::recording(1, &mut input), Some(&request));
+        assert_eq!(&input[RDATA.len()..RDATA.len() + request.len()], request.as_slice());
+
+        let additionals = additionals(&bytes);
+        assert_eq!(
+            additionals.verify_sig0(TestKey::new(1), Some(&request), INCEPTION).unwrap(),
+            Sig0Status::Valid,
+        );
+        assert_eq!(
+            additionals.verify_sig0(TestKey::new(1), None, INCEPTION).unwrap(),
+            Sig0Status::BadSig,
+        );
+    }
+
+    #[test]
+    fn padded() {
+        let bytes = query(|additionals| {
+            additionals.set_padding(PaddingPolicy::QUERY).unwrap();
+            additionals.append_edns(Edns::new(1232)).unwrap();
+            additionals.append_sig0(sig(), TestKey::new(1), None).unwrap();
+        });
+
+        // The padding leaves room for the SIG(0) record.
+        assert_eq!(bytes.len(), 256);
+        let additionals = additionals(&bytes);
+        assert_eq!(additionals.verify_sig0(TestKey::new(1), None, INCEPTION).unwrap(), Sig0Status::Valid);
+    }
+
+    #[test]
+    fn time_wraps() {
+        let sig = Sig {
+            signature_inception: u32::MAX - 10,
+            signature_expiration: 10,
+            ..sig()
+        };
+        assert!(verify_time(&sig, u32::MAX));
+        assert!(verify_time(&sig, 5));
+        assert!(!verify_time(&sig, 11));
+    }
+}
@@ -0,0 +1,402 @@
+use crate::{DnsError, DnsMessageError};
+use crate::header::DnsHeaderResponseCode;
+use crate::name::DnsName;
+use crate::rdata::TSig;
+
+// The TSIG record is always of class ANY with a TTL of 0.
+pub(crate) const TSIG_CLASS: u16 = 255;
+pub(crate) const TSIG_TTL: u32 = 0;
+// The shortest MAC which may be accepted, regardless of the algorithm.
+const MIN_MAC_LENGTH: usize = 10;
+
+/// A keyed HMAC used to sign and verify messages. The key is held by the
+/// implementation. A new instance is used for every message.
+///
+/// [RFC 8945 Section 6](https://tools.ietf.org/rfc/rfc8945#section-6)
+/// requires HMAC-SHA256 for interoperability.
+pub trait TSigHmac {
+    /// The message authentication code, e.g. `[u8; 32]` for HMAC-SHA256.
+    type Mac: AsRef<[u8]>;
+
+    /// The length of the MAC returned by `finalize`, e.g. 32 for
+    /// HMAC-SHA256.
+    fn mac_length(&self) -> usize;
+
+    /// Add data to the MAC input.
+    fn update(&mut self, data: &[u8]);
+
+    /// Return the MAC of all data added.
+    fn finalize(self) -> Self::Mac;
+}
+
+/// The key used to sign or verify a message, without the secret.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TSigKey<'a> {
+    /// The name of the key, the owner name of the TSIG record.
+    pub name: DnsName<'a>,
+    /// The name of the algorithm, e.g. `hmac-sha256.`.
+    pub algorithm: DnsName<'a>,
+}
+
+/// The kind of a signed message, which determines what the MAC covers.
+///
+/// According to [RFC 8945 Section 4.3](https://tools.ietf.org/rfc/rfc8945#section-4.3).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TSigMode<'a> {
+    /// A request, the MAC covers the message and the TSIG variables.
+    Request,
+    /// The first message of a response, the MAC additionally covers the MAC
+    /// of the request.
+    Response(&'a [u8]),
+    /// A subsequent message of a multi-message response over TCP, with the
+    /// MAC of the previous signed message and the unsigned messages since,
+    /// of which there may be up to 99. The MAC covers the previous MAC, the
+    /// unsigned messages, the message and only the time signed and fudge of
+    /// the TSIG variables.
+    ///
+    /// According to [RFC 8945 Section 5.3.1](https://tools.ietf.org/rfc/rfc8945#section-5.3.1).
+    Continuation(&'a [u8], &'a [&'a [u8]]),
+}
+
+/// The result of verifying the TSIG record of a message.
+///
+/// According to [RFC 8945 Section 5.2](https://tools.ietf.org/rfc/rfc8945#section-5.2).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TSigStatus {
+    /// The message is not signed.
+    Missing,
+    /// The signature is valid.
+    Valid,
+    /// The message is signed with another key or algorithm.
+    BadKey,
+    /// The MAC does not match.
+    BadSig,
+    /// The message was signed outside of the allowed time window.
+    BadTime,
+    /// The MAC is truncated more than allowed.
+    BadTrunc,
+}
+
+impl TSigStatus {
+    /// Return the response code to answer the message with, if the
+    /// verification failed.
+    #[inline]
+    pub fn response_code(&self) -> Option<DnsHeaderResponseCode> {
+        match self {
+            TSigStatus::Missing | TSigStatus::Valid => None,
+            TSigStatus::BadKey => Some(DnsHeaderResponseCode::BadKey),
+            TSigStatus::BadSig => Some(DnsHeaderResponseCode::BadOptVersionOrBadSignature),
+            TSigStatus::BadTime => Some(DnsHeaderResponseCode::BadTime),
+            TSigStatus::BadTrunc => Some(DnsHeaderResponseCode::BadTrunc),
+        }
+    }
+}
+
+/// Add the MAC input of a message to the HMAC. The message must not contain
+/// the TSIG record, the ID and the additional count of its header are
+/// replaced with the original ID and the given count.
+pub(crate) fn digest<H: TSigHmac>(
+    hmac: &mut H,
+    message: &[u8],
+    additional_count: u16,
+    key_name: DnsName,
+    tsig: &TSig,
+    mode: TSigMode,
+) -> Result<(), DnsMessageError> {
+    match mode {
+        TSigMode::Request => {}
+        TSigMode::Response(mac) => {
+            hmac.update(&(mac.len() as u16).to_be_bytes());
+            hmac.update(mac);
+        }
+        TSigMode::Continuation(mac, unsigned) => {
+            hmac.update(&(mac.len() as u16).to_be_bytes());
+            hmac.update(mac);
+            for message in unsigned {
+                hmac.update(message);
+            }
+        }
+    }
+
+    if message.len() < 12 {
+        return Err(DnsMessageError::DnsError(DnsError::MessageTooShort));
+    }
+    let mut header = [0; 12];
+    header.copy_from_slice(&message[..12]);
+    header[..2].copy_from_slice(&tsig.original_id.to_be_bytes());
+    header[10..].copy_from_slice(&additional_count.to_be_bytes());
+    hmac.update(&header);
+    hmac.update(&message[12..]);
+
+    let time_signed = tsig.time_signed.to_be_bytes();
+    if let TSigMode::Continuation(..) = mode {
+        hmac.update(&time_signed[2..]);
+        hmac.update(&tsig.fudge.to_be_bytes());
+
+        return Ok(());
+    }
+
+    key_name.canonical(|data| hmac.update(data))?;
+    hmac.update(&TSIG_CLASS.to_be_bytes());
+    hmac.update(&TSIG_TTL.to_be_bytes());
+    tsig.algorithm.canonical(|data| hmac.update(data))?;
+    hmac.update(&time_signed[2..]);
+    hmac.update(&tsig.fudge.to_be_bytes());
+    hmac.update(&u16::from(tsig.error).to_be_bytes());
+    hmac.update(&(tsig.other.len() as u16).to_be_bytes());
+    hmac.update(tsig.other);
+
+    Ok(())
+}
+
+/// Compare the received MAC with the expected one. The received MAC may be
+/// truncated to half of the expected one, but not below 10 bytes.
+///
+/// According to [RFC 8945 Section 5.2.2](https://tools.ietf.org/rfc/rfc8945#section-5.2.2).
+pub(crate) fn verify_mac(expected: &[u8], mac: &[u8]) -> TSigStatus {
+    if mac.len() > expected.len() {
+        return TSigStatus::BadSig;
+    }
+    if mac.len() < expected.len() && mac.len() < MIN_MAC_LENGTH.max(expected.len() / 2) {
+        return TSigStatus::BadTrunc;
+    }
+
+    // Compare in constant time.
+    let difference = expected.iter()
+        .zip(mac)
+        .fold(0, |difference, (a, b)| difference | (a ^ b));
+    if difference == 0 {
+        TSigStatus::Valid
+    } else {
+        TSigStatus::BadSig
+    }
+}
+
+/// Return whether the message was signed within the fudge of `now`.
+#[inline]
+pub(crate) fn verify_time(tsig: &TSig, now: u64) -> bool {
+    now.abs_diff(tsig.time_signed) <= tsig.fudge as u64
+}
+
+#[cfg(test)]
+mod test {
+    use arrayvec::ArrayVec;
+    use crate::edns::{Edns, EdnsOption, PaddingPolicy};
+    use crate::testutils::{additionals, query, QUERY, TestMac};
+    use super::*;
+
+    type TestHmac<'a> = TestMac<'a, 32>;
+
+    const TIME_SIGNED: u64 = 1703824768;
+
+    const KEY: TSigKey<'static> = TSigKey {
+        name: unsafe { DnsName::new_unchecked(b"\x03key\x07example\x00") },
+        algorithm: unsafe { DnsName::new_unchecked(b"\x0bhmac-sha256\x00") },
+    };
+
+    const VARIABLES: [u8; 44] = [
+        0x03, b'k', b'e', b'y', 0x07, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 0x00, // Key name
+        0x00, 0xff, // Class
+        0x00, 0x00, 0x00, 0x00, // TTL
+        0x0b, b'h', b'm', b'a', b'c', b'-', b's', b'h', b'a', b'2', b'5', b'6', 0x00, // Algorithm
+        0x00, 0x00, 0x65, 0x8e, 0x4d, 0x80, // Time signed
+        0x01, 0x2c, // Fudge
+        0x00, 0x00, // Error
+        0x00, 0x00, // Other length
+    ];
+
+    fn tsig(mac: &[u8]) -> TSig<'_> {
+        TSig {
+            algorithm: KEY.algorithm,
+            time_signed: TIME_SIGNED,
+            fudge: 300,
+            mac,
+            original_id: 0x1234,
+            error: DnsHeaderResponseCode::NoError,
+            other: &[],
+        }
+    }
+
+    fn sign(hmac: TestHmac, mode: TSigMode) -> (ArrayVec<u8, 512>, [u8; 32]) {
+        let mut mac = [0; 32];
+        let bytes = query(|additionals| {
+            mac = additionals.append_tsig(KEY.name, tsig(&[]), hmac, mode).unwrap();
+        });
+
+        (bytes, mac)
+    }
+
+    #[test]
+    fn sign_request() {
+        let mut input = ArrayVec::new();
+        let hmac = TestHmac::recording(1, &mut input);
+        let (bytes, mac) = sign(hmac, TSigMode::Request);
+
+        let mut expected = ArrayVec::<u8, 512>::new();
+        expected.try_extend_from_slice(&QUERY).unwrap();
+        expected.try_extend_from_slice(&VARIABLES).unwrap();
+        assert_eq!(input, expected);
+
+        // The additional count includes the TSIG record.
+        assert_eq!(&bytes[10..12], &[0x00, 0x01]);
+        let additionals = additionals(&bytes);
+        let (name, tsig) = additionals.tsig().unwrap().unwrap();
+        assert_eq!(name, KEY.name);
+        assert_eq!(tsig, self::tsig(&mac));
+        // The record is class ANY with a TTL of 0.
+        assert_eq!(&bytes[QUERY.len() + 13..QUERY.len() + 21], &[0x00, 0xfa, 0x00, 0xff, 0x00, 0x00, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn verify_request() {
+        let (bytes, _) = sign(TestHmac::new(1), TSigMode::Request);
+        let additionals = additionals(&bytes);
+
+        assert_eq!(
+            additionals.verify_tsig(KEY, TestHmac::new(1), TSigMode::Request, TIME_SIGNED + 300).unwrap(),
+            TSigStatus::Valid,
+        );
+        assert_eq!(
+            additionals.verify_tsig(KEY, TestHmac::new(2), TSigMode::Request, TIME_SIGNED).unwrap(),
+            TSigStatus::BadSig,
+        );
+        assert_eq!(
+            additionals.verify_tsig(KEY, TestHmac::new(1), TSigMode::Request, TIME_SIGNED - 301).unwrap(),
+            TSigStatus::BadTime,
+        );
+        let key = TSigKey {
+            name: DnsName::new(b"\x05other\x00").unwrap(),
+            ..KEY
+        };
+        assert_eq!(
+            additionals.verify_tsig(key, TestHmac::new(1), TSigMode::Request, TIME_SIGNED).unwrap(),
+            TSigStatus::BadKey,
+        );
+
+        let mut tampered = bytes.clone();
+        tampered[3] = 0x80;
+        assert_eq!(
+            self::additionals(&tampered)
+                .verify_tsig(KEY, TestHmac::new(1), TSigMode::Request, TIME_SIGNED)
+                .unwrap(),
+            TSigStatus::BadSig,
+        );
+
+        assert_eq!(
+            self::additionals(&QUERY)
+                .verify_tsig(KEY, TestHmac::new(1), TSigMode::Request, TIME_SIGNED)
+                .unwrap(),
+            TSigStatus::Missing,
+        );
+    }
+
+    #[test]
+    fn verify_response() {
+        let request_mac = [0xaa; 32];
+        let mut input = ArrayVec::new();
+        let hmac = TestHmac::recording(1, &mut input);
+        let (bytes, _) = sign(hmac, TSigMode::Response(&request_mac));
+        assert_eq!(&input[..2], &[0x00, 0x20]);
+        assert_eq!(&input[2..34], &request_mac);
+        assert_eq!(&input[34..34 + QUERY.len()], &QUERY);
+
+        let additionals = additionals(&bytes);
+        assert_eq!(
+            additionals.verify_tsig(KEY, TestHmac::new(1), TSigMode::Response(&request_mac), TIME_SIGNED).unwrap(),
+            TSigStatus::Valid,
+        );
+        assert_eq!(
+            additionals.verify_tsig(KEY, TestHmac::new(1), TSigMode::Request, TIME_SIGNED).unwrap(),
+            TSigStatus::BadSig,
+        );
+    }
+
+    #[test]
+    fn continuation() {
+        let previous_mac = [0xbb; 32];
+        let mut input = ArrayVec::new();
+        let hmac = TestHmac::recording(1, &mut input);
+        let (bytes, _) = sign(hmac, TSigMode::Continuation(&previous_mac, &[]));
+
+        let mut expected = ArrayVec::<u8, 512>::new();
+        expected.try_extend_from_slice(&[0x00, 0x20]).unwrap();
+        expected.try_extend_from_slice(&previous_mac).unwrap();
+        expected.try_extend_from_slice(&QUERY).unwrap();
+        expected.try_extend_from_slice(&[0x00, 0x00, 0x65, 0x8e, 0x4d, 0x80, 0x01, 0x2c]).unwrap();
+        assert_eq!(input, expected);
+
+        assert_eq!(
+            additionals(&bytes)
+                .verify_tsig(KEY, TestHmac::new(1), TSigMode::Continuation(&previous_mac, &[]), TIME_SIGNED)
+                .unwrap(),
+            TSigStatus::Valid,
+        );
+    }
+
+    #[test]
+    fn continuation_after_unsigned() {
+        // The unsigned message between the signed ones is covered by the MAC
+        // of the next signed message.
+        let (_, first_mac) = sign(TestHmac::new(1), TSigMode::Response(&[0xaa; 32]));
+        let unsigned = query(|_| {});
+        let mut input = ArrayVec::new();
+        let hmac = TestHmac::recording(1, &mut input);
+        let (bytes, _) = sign(hmac, TSigMode::Continuation(&first_mac, &[&unsigned]));
+        assert_eq!(&input[34..34 + unsigned.len()], unsigned.as_slice());
+
+        let additionals = additionals(&bytes);
+        assert_eq!(
+            additionals.verify_tsig(KEY, TestHmac::new(1), TSigMode::Continuation(&first_mac, &[&unsigned]), TIME_SIGNED)
+                .unwrap(),
+            TSigStatus::Valid,
+        );
+        assert_eq!(
+            additionals.verify_tsig(KEY, TestHmac::new(1), TSigMode::Continuation(&first_mac, &[]), TIME_SIGNED)
+                .unwrap(),
+            TSigStatus::BadSig,
+        );
+    }
+
+    #[test]
+    fn padded() {
+        let bytes = query(|additionals| {
+            additionals.set_padding(PaddingPolicy::QUERY).unwrap();
+            additionals.append_edns(Edns::new(1232)).unwrap();
+            additionals.append_tsig(KEY.name, tsig(&[]), TestHmac::new(1), TSigMode::Request).unwrap();
+        });
+
+        // The padding is covered by the MAC and leaves room for the TSIG
+        // record.
+        assert_eq!(bytes.len(), 128);
+        assert_eq!(&bytes[10..12], &[0x00, 0x02]);
+        let additionals = additionals(&bytes);
+        let edns = additionals.edns().unwrap().unwrap();
+        assert_eq!(edns.iter().next(), Some(Ok(EdnsOption::Padding(0))));
+        assert_eq!(
+            additionals.verify_tsig(KEY, TestHmac::new(1), TSigMode::Request, TIME_SIGNED).unwrap(),
+            TSigStatus::Valid,
+        );
+    }
+
+    #[test]
+    fn truncated_mac() {
+        let expected = [0x5a; 32];
+        assert_eq!(verify_mac(&expected, &expected), TSigStatus::Valid);
+        assert_eq!(verify_mac(&expected, &expected[..16]), TSigStatus::Valid);
+        assert_eq!(verify_mac(&expected, &expected[..15]), TSigStatus::BadTrunc);
+        assert_eq!(verify_mac(&expected[..16], &expected[..9]), TSigStatus::BadTrunc);
+        assert_eq!(verify_mac(&expected[..20], &expected[..10]), TSigStatus::Valid);
+        assert_eq!(verify_mac(&expected[..16], &expected), TSigStatus::BadSig);
+        assert_eq!(verify_mac(&expected, &[0x5b; 32]), TSigStatus::BadSig);
+    }
+
+    #[test]
+    fn response_code() {
+        assert_eq!(TSigStatus::Valid.response_code(), None);
+        assert_eq!(TSigStatus::Missing.response_code(), None);
+        assert_eq!(TSigStatus::BadSig.response_code().map(u16::from), Some(16));
+        assert_eq!(TSigStatus::BadKey.response_code().map(u16::from), Some(17));
+        assert_eq!(TSigStatus::BadTime.response_code().map(u16::from), Some(18));
+        assert_eq!(TSigStatus::BadTrunc.response_code().map(u16::from), Some(22));
+    }
+}
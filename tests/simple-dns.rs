@@ -33,11 +33,13 @@ fn comparison_question_compressed() {
     questions.append(DnsQuestion {
         name: dns_name!(b"_srv._udp.local"),
         qtype: DnsQType::AAAA,
+        unicast_response: false,
         qclass: DnsQClass::IN,
     }).unwrap();
     questions.append(DnsQuestion {
         name: dns_name!(b"_srv._udp.local"),
         qtype: DnsQType::TXT,
+        unicast_response: false,
         qclass: DnsQClass::IN,
     }).unwrap();
     let message = questions.complete().unwrap();
@@ -81,11 +83,13 @@ fn comparison_question() {
     questions.append(DnsQuestion {
         name: dns_name!(b"_srv._udp.local"),
         qtype: DnsQType::AAAA,
+        unicast_response: false,
         qclass: DnsQClass::IN,
     }).unwrap();
     questions.append(DnsQuestion {
         name: dns_name!(b"_srv._udp.local"),
         qtype: DnsQType::TXT,
+        unicast_response: false,
         qclass: DnsQClass::IN,
     }).unwrap();
     let message = questions.complete().unwrap();
@@ -279,11 +283,13 @@ fn comparison_mixed() {
     questions.append(DnsQuestion {
         name: dns_name!(b"esp32.local"),
         qtype: DnsQType::AAAA,
+        unicast_response: false,
         qclass: DnsQClass::IN,
     }).unwrap();
     questions.append(DnsQuestion {
         name: dns_name!(b"_srv._udp.local"),
         qtype: DnsQType::TXT,
+        unicast_response: false,
         qclass: DnsQClass::IN,
     }).unwrap();
     let message = questions.complete().unwrap();
@@ -372,11 +378,13 @@ fn comparison_mixed_compressed() {
     questions.append(DnsQuestion {
         name: dns_name!(b"esp32.local"),
         qtype: DnsQType::AAAA,
+        unicast_response: false,
         qclass: DnsQClass::IN,
     }).unwrap();
     questions.append(DnsQuestion {
         name: dns_name!(b"_srv._udp.local"),
         qtype: DnsQType::TXT,
+        unicast_response: false,
         qclass: DnsQClass::IN,
     }).unwrap();
     let message = questions.complete().unwrap();
@@ -522,4 +530,152 @@ fn test_complex() {
     let buffer = message.abort().unwrap();
 
     assert_eq!(bytes_reference.as_slice(), buffer.as_slice());
-}
\ No newline at end of file
+}
+
+#[cfg(feature = "arrayvec")]
+#[test]
+fn comparison_mdns_class_bits() {
+    use simple_dns::{CLASS, Name, Packet, Question, ResourceRecord, TYPE};
+    use simple_dns::rdata::RData;
+    use flex_dns::{dns_name, DnsMessage};
+    use flex_dns::answer::{DnsAClass, DnsAnswer};
+    use flex_dns::header::{DnsHeaderKind, DnsHeaderOpcode, DnsHeaderResponseCode};
+    use flex_dns::question::{DnsQClass, DnsQType, DnsQuestion};
+    use flex_dns::rdata::{A, DnsAType};
+
+    let mut packet = Packet::new_query(0);
+    packet.questions.push(Question::new(
+        Name::new_unchecked("esp32.local"),
+        TYPE::A.into(),
+        CLASS::IN.into(),
+        true,
+    ));
+    packet.answers.push(ResourceRecord::new(
+        Name::new_unchecked("esp32.local"),
+        CLASS::IN,
+        120,
+        RData::A(simple_dns::rdata::A {
+            address: 0x01020304
+        }),
+    ).with_cache_flush(true));
+    let bytes_reference = packet.build_bytes_vec().unwrap();
+
+    let buffer: arrayvec::ArrayVec<u8, 512> = arrayvec::ArrayVec::new();
+    let mut message: DnsMessage<0, 0, _> = DnsMessage::new_mut(buffer).unwrap();
+    message.header_mut().unwrap().set_id(0);
+    message.header_mut().unwrap().set_opcode(DnsHeaderOpcode::Query);
+    message.header_mut().unwrap().set_kind(DnsHeaderKind::Query);
+    message.header_mut().unwrap().set_recursion_desired(false);
+    message.header_mut().unwrap().set_response_code(DnsHeaderResponseCode::NoError);
+    let mut questions = message.questions();
+    let question = DnsQuestion {
+        name: dns_name!(b"esp32.local"),
+        qtype: DnsQType::A,
+        unicast_response: true,
+        qclass: DnsQClass::IN,
+    };
+    questions.append(question).unwrap();
+    let message = questions.complete().unwrap();
+    let mut answers = message.answers();
+    answers.append(DnsAnswer {
+        name: dns_name!(b"esp32.local"),
+        rdata: DnsAType::A(A {
+            address: [1, 2, 3, 4],
+        }),
+        cache_flush: true,
+        aclass: DnsAClass::IN,
+        ttl: 120,
+    }).unwrap();
+    let message = answers.complete().unwrap();
+    let buffer = message.abort().unwrap();
+
+    assert_eq!(bytes_reference.as_slice(), buffer.as_slice());
+
+    // The bits are parsed from the class, without changing the type.
+    let message: DnsMessage<0, 0, _> = DnsMessage::new(bytes_reference.as_slice()).unwrap();
+    let mut questions = message.questions();
+    let parsed = questions.iter().unwrap().next().unwrap().unwrap();
+    assert_eq!(parsed, DnsQuestion {
+        name: dns_name!(b"esp32.local"),
+        qtype: DnsQType::A,
+        unicast_response: true,
+        qclass: DnsQClass::IN,
+    });
+    let message = questions.complete().unwrap();
+    let mut answers = message.answers();
+    let answer = answers.iter().unwrap().next().unwrap().unwrap().into_parsed().unwrap();
+    assert_eq!(answer.rdata, DnsAType::A(A { address: [1, 2, 3, 4] }));
+    assert!(answer.cache_flush);
+    assert_eq!(answer.aclass, DnsAClass::IN);
+}
+
+#[test]
+fn comparison_dns_sd_resolve() {
+    use simple_dns::{CLASS, Name, Packet, ResourceRecord};
+    use simple_dns::rdata::RData;
+    use flex_dns::dns_name;
+    use flex_dns::dnssd::{DnsSdAddress, DnsSdInstance, DnsSdInstances};
+    use flex_dns::rdata::TxtAttribute;
+
+    let mut packet = Packet::new_reply(0);
+    packet.answers.push(ResourceRecord::new(
+        Name::new_unchecked("_http._tcp.local"),
+        CLASS::IN,
+        4500,
+        RData::PTR(simple_dns::rdata::PTR(Name::new_unchecked("Printer._http._tcp.local"))),
+    ));
+    packet.additional_records.push(ResourceRecord::new(
+        Name::new_unchecked("Printer._http._tcp.local"),
+        CLASS::IN,
+        120,
+        RData::SRV(simple_dns::rdata::SRV {
+            priority: 0,
+            weight: 0,
+            port: 631,
+            target: Name::new_unchecked("printer.local"),
+        }),
+    ).with_cache_flush(true));
+    packet.additional_records.push(ResourceRecord::new(
+        Name::new_unchecked("Printer._http._tcp.local"),
+        CLASS::IN,
+        120,
+        RData::TXT(simple_dns::rdata::TXT::new().with_string("txtvers=1").unwrap().with_string("rp=ipp/print").unwrap()),
+    ).with_cache_flush(true));
+    packet.additional_records.push(ResourceRecord::new(
+        Name::new_unchecked("printer.local"),
+        CLASS::IN,
+        120,
+        RData::A(simple_dns::rdata::A {
+            address: 0xc0a80114,
+        }),
+    ).with_cache_flush(true));
+    packet.additional_records.push(ResourceRecord::new(
+        Name::new_unchecked("printer.local"),
+        CLASS::IN,
+        120,
+        RData::AAAA(simple_dns::rdata::AAAA {
+            address: 0xfe80_0000_0000_0000_0000_0000_0000_0001,
+        }),
+    ).with_cache_flush(true));
+    let bytes_reference = packet.build_bytes_vec().unwrap();
+
+    let service = dns_name!(b"_http._tcp.local");
+    let mut instances = DnsSdInstances::new(&bytes_reference, &service).unwrap();
+    let name = instances.next().unwrap().unwrap();
+    assert_eq!(name, dns_name!(b"Printer._http._tcp.local"));
+    assert_eq!(instances.next(), None);
+
+    let instance = DnsSdInstance::resolve(&bytes_reference, &name).unwrap().unwrap();
+    assert_eq!(instance.host, dns_name!(b"printer.local"));
+    assert_eq!(instance.port, 631);
+    let txt = instance.txt.unwrap();
+    assert_eq!(txt.attribute(b"rp"), Ok(Some(TxtAttribute { key: b"rp", value: Some(b"ipp/print") })));
+
+    let mut addresses = instance.addresses().unwrap();
+    assert_eq!(addresses.next(), Some(Ok(DnsSdAddress::V4([192, 168, 1, 20]))));
+    assert_eq!(
+        addresses.next(),
+        Some(Ok(DnsSdAddress::V6([0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]))),
+    );
+    assert_eq!(addresses.next(), None);
+}